@@ -9,15 +9,18 @@ INSERT INTO categories (id, name, description, icon, color, char_limit, sort_ord
 ('55555555-5555-5555-5555-555555555555', '概念説明', 'わかりやすく例える', '💡', '#f3e5f5', 50, 5);
 
 -- Users
-INSERT INTO users (id, email, name, avatar, bio, total_likes) VALUES
-('aaaa1111-1111-1111-1111-111111111111', 'yukino@example.com', 'ゆきの', NULL, '言葉の力を信じています。毎日コツコツ言語化トレーニング中。目標は小説家になること。', 156),
-('aaaa2222-2222-2222-2222-222222222222', 'kenta@example.com', 'けんた', NULL, 'ITエンジニア。難しいことを簡単に説明するのが好き。', 89),
-('aaaa3333-3333-3333-3333-333333333333', 'saki@example.com', 'さき', NULL, '詩を書くのが趣味です。日常の小さな感動を言葉にしたい。', 234),
-('aaaa4444-4444-4444-4444-444444444444', 'takeshi@example.com', 'たけし', NULL, '会社員。日々の業務で言語化力の必要性を痛感中。', 45),
-('aaaa5555-5555-5555-5555-555555555555', 'misaki@example.com', 'みさき', NULL, '大学生。就活に向けて言語化力を鍛え中！', 67),
-('aaaa6666-6666-6666-6666-666666666666', 'ryo@example.com', 'りょう', NULL, 'コピーライター見習い。言葉の重みを日々学んでいます。', 123),
-('aaaa7777-7777-7777-7777-777777777777', 'ayaka@example.com', 'あやか', NULL, '主婦です。子どもに物事をわかりやすく伝えたくて始めました。', 78),
-('aaaa8888-8888-8888-8888-888888888888', 'demo@example.com', 'デモユーザー', NULL, 'テスト用のデモユーザーです。', 0);
+INSERT INTO users (id, email, name, avatar, bio, total_likes, search_name) VALUES
+('aaaa1111-1111-1111-1111-111111111111', 'yukino@example.com', 'ゆきの', NULL, '言葉の力を信じています。毎日コツコツ言語化トレーニング中。目標は小説家になること。', 156, 'ゆきの'),
+('aaaa2222-2222-2222-2222-222222222222', 'kenta@example.com', 'けんた', NULL, 'ITエンジニア。難しいことを簡単に説明するのが好き。', 89, 'けんた'),
+('aaaa3333-3333-3333-3333-333333333333', 'saki@example.com', 'さき', NULL, '詩を書くのが趣味です。日常の小さな感動を言葉にしたい。', 234, 'さき'),
+('aaaa4444-4444-4444-4444-444444444444', 'takeshi@example.com', 'たけし', NULL, '会社員。日々の業務で言語化力の必要性を痛感中。', 45, 'たけし'),
+('aaaa5555-5555-5555-5555-555555555555', 'misaki@example.com', 'みさき', NULL, '大学生。就活に向けて言語化力を鍛え中！', 67, 'みさき'),
+('aaaa6666-6666-6666-6666-666666666666', 'ryo@example.com', 'りょう', NULL, 'コピーライター見習い。言葉の重みを日々学んでいます。', 123, 'りょう'),
+('aaaa7777-7777-7777-7777-777777777777', 'ayaka@example.com', 'あやか', NULL, '主婦です。子どもに物事をわかりやすく伝えたくて始めました。', 78, 'あやか'),
+('aaaa8888-8888-8888-8888-888888888888', 'demo@example.com', 'デモユーザー', NULL, 'テスト用のデモユーザーです。', 0, 'でもゆーざー');
+
+-- Staff roles (the demo user doubles as a moderator for local testing)
+UPDATE users SET role = 'moderator' WHERE id = 'aaaa8888-8888-8888-8888-888888888888';
 
 -- Challenges (状況描写)
 INSERT INTO challenges (id, category_id, title, description, char_limit, release_date, answer_count) VALUES
@@ -73,16 +76,19 @@ INSERT INTO comments (answer_id, user_id, content) VALUES
 ('cccc3331-3333-3333-3333-333333333331', 'aaaa6666-6666-6666-6666-666666666666', '「きゅっと締まる」がまさにその感覚！共感しました'),
 ('cccc3332-3333-3333-3333-333333333332', 'aaaa3333-3333-3333-3333-333333333333', '詩的な表現ですね。情緒が伝わってきます');
 
--- Sample Likes
-INSERT INTO likes (answer_id, user_id) VALUES
-('cccc1111-1111-1111-1111-111111111111', 'aaaa2222-2222-2222-2222-222222222222'),
-('cccc1111-1111-1111-1111-111111111111', 'aaaa3333-3333-3333-3333-333333333333'),
-('cccc1111-1111-1111-1111-111111111111', 'aaaa4444-4444-4444-4444-444444444444'),
-('cccc5551-5555-5555-5555-555555555551', 'aaaa1111-1111-1111-1111-111111111111'),
-('cccc5551-5555-5555-5555-555555555551', 'aaaa3333-3333-3333-3333-333333333333'),
-('cccc3331-3333-3333-3333-333333333331', 'aaaa1111-1111-1111-1111-111111111111'),
-('cccc3331-3333-3333-3333-333333333331', 'aaaa2222-2222-2222-2222-222222222222'),
-('cccc3331-3333-3333-3333-333333333331', 'aaaa4444-4444-4444-4444-444444444444');
+-- Sample Reactions
+INSERT INTO reactions (answer_id, user_id, reaction_type) VALUES
+('cccc1111-1111-1111-1111-111111111111', 'aaaa2222-2222-2222-2222-222222222222', 'like'),
+('cccc1111-1111-1111-1111-111111111111', 'aaaa3333-3333-3333-3333-333333333333', 'like'),
+('cccc1111-1111-1111-1111-111111111111', 'aaaa4444-4444-4444-4444-444444444444', 'like'),
+('cccc5551-5555-5555-5555-555555555551', 'aaaa1111-1111-1111-1111-111111111111', 'like'),
+('cccc5551-5555-5555-5555-555555555551', 'aaaa3333-3333-3333-3333-333333333333', 'like'),
+('cccc3331-3333-3333-3333-333333333331', 'aaaa1111-1111-1111-1111-111111111111', 'like'),
+('cccc3331-3333-3333-3333-333333333331', 'aaaa2222-2222-2222-2222-222222222222', 'like'),
+('cccc3331-3333-3333-3333-333333333331', 'aaaa4444-4444-4444-4444-444444444444', 'like'),
+('cccc1111-1111-1111-1111-111111111111', 'aaaa5555-5555-5555-5555-555555555555', 'insightful'),
+('cccc5551-5555-5555-5555-555555555551', 'aaaa2222-2222-2222-2222-222222222222', 'funny'),
+('cccc3331-3333-3333-3333-333333333331', 'aaaa5555-5555-5555-5555-555555555555', 'moving');
 
 -- Sample Follows
 INSERT INTO follows (follower_id, following_id) VALUES
@@ -0,0 +1,50 @@
+use actix_web::HttpResponse;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::utils;
+
+// Values stored in users.role
+pub const ROLE_MODERATOR: &str = "moderator";
+pub const ROLE_EDITOR: &str = "editor";
+pub const ROLE_ADMIN: &str = "admin";
+
+/// Roles allowed to work the moderation queue.
+pub const MODERATION_ROLES: &[&str] = &[ROLE_MODERATOR, ROLE_ADMIN];
+
+/// Roles allowed to curate content, such as picking exemplary answers.
+pub const EDITORIAL_ROLES: &[&str] = &[ROLE_EDITOR, ROLE_ADMIN];
+
+/// Roles allowed to change site-wide configuration such as the reaction set.
+pub const ADMIN_ROLES: &[&str] = &[ROLE_ADMIN];
+
+/// Ensures the user exists, is active and holds one of `roles`.
+/// Returns the ready-to-send error response otherwise.
+pub async fn require_role(pool: &PgPool, user_id: Uuid, roles: &[&str]) -> Result<(), HttpResponse> {
+    let role = sqlx::query_scalar::<_, String>(
+        r#"SELECT role FROM users WHERE id = $1 AND status = 'active'"#,
+    )
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await;
+
+    match role {
+        Ok(Some(role)) if roles.contains(&role.as_str()) => Ok(()),
+        Ok(_) => Err(utils::forbidden("Insufficient permissions")),
+        Err(e) => {
+            tracing::error!("Failed to fetch user role: {}", e);
+            Err(utils::internal_error("Failed to verify permissions"))
+        }
+    }
+}
+
+/// Suspended users keep read access but cannot post or report.
+pub async fn is_suspended(pool: &PgPool, user_id: Uuid) -> bool {
+    sqlx::query_scalar::<_, bool>(
+        r#"SELECT EXISTS(SELECT 1 FROM users WHERE id = $1 AND status = 'suspended')"#,
+    )
+    .bind(user_id)
+    .fetch_one(pool)
+    .await
+    .unwrap_or(false)
+}
@@ -5,6 +5,16 @@ pub struct Config {
     pub server: ServerConfig,
     pub database: DatabaseConfig,
     pub pagination: PaginationConfig,
+    pub moderation: ModerationConfig,
+    pub content_filter: ContentFilterConfig,
+    pub streak: StreakConfig,
+    pub xp: XpConfig,
+    pub daily: DailyConfig,
+    pub timed: TimedConfig,
+    pub peer_review: PeerReviewConfig,
+    pub battle: BattleConfig,
+    pub contest: ContestConfig,
+    pub challenge_rating: ChallengeRatingConfig,
 }
 
 #[derive(Debug, Clone)]
@@ -29,6 +39,93 @@ pub struct PaginationConfig {
     pub max_page_size: i64,
 }
 
+#[derive(Debug, Clone)]
+pub struct ModerationConfig {
+    /// Distinct open reports after which an answer or comment is hidden automatically
+    pub auto_hide_threshold: i64,
+}
+
+#[derive(Debug, Clone)]
+pub struct ContentFilterConfig {
+    /// NG word dictionary file; the built-in list is used when unset
+    pub ng_words_path: Option<String>,
+    /// allow, hold or reject
+    pub url_action: String,
+    /// allow, hold or reject
+    pub repeat_action: String,
+    pub repeat_window_minutes: i64,
+    /// Earlier identical posts within the window that trigger the repeat check
+    pub repeat_threshold: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct StreakConfig {
+    /// Consecutive active days needed to earn one freeze token
+    pub freeze_earn_days: i32,
+    /// Freeze tokens a user can hold at once; each covers one missed day
+    pub max_freeze_tokens: i32,
+}
+
+/// XP rules applied when reading the ledger, so changing them rebalances all history
+#[derive(Debug, Clone)]
+pub struct XpConfig {
+    /// XP for the first answer to each challenge
+    pub answer_xp: i32,
+    /// XP per like received
+    pub like_xp: i32,
+    /// XP per AI score point, using each answer's latest score
+    pub score_multiplier: f64,
+    /// XP needed for Lv.2; reaching Lv.n takes level_base * (n - 1)^2
+    pub level_base: i32,
+}
+
+#[derive(Debug, Clone)]
+pub struct DailyConfig {
+    /// Timezone whose calendar day decides the daily set
+    pub timezone: String,
+    /// Days of daily sets generated ahead of time, including today
+    pub schedule_days: i32,
+}
+
+#[derive(Debug, Clone)]
+pub struct TimedConfig {
+    /// Writing time allowed in timed mode
+    pub time_limit_secs: i32,
+    /// Extra seconds accepted after the deadline to absorb network latency
+    pub grace_secs: i32,
+    /// Reject late submissions instead of storing them flagged as late
+    pub reject_late: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct PeerReviewConfig {
+    /// Answers handed to each user to review per challenge they answer
+    pub review_count: i64,
+    /// Completed reviews an answer needs before its peer score is used to
+    /// judge its reviewers' reliability
+    pub min_reviews: i32,
+}
+
+#[derive(Debug, Clone)]
+pub struct BattleConfig {
+    /// Elo K-factor: the most rating points a single battle can move
+    pub k_factor: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct ContestConfig {
+    /// Share of an entry's contest score taken from its AI score; votes make up the rest
+    pub ai_weight: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct ChallengeRatingConfig {
+    /// Public challenges whose average user rating (1-5) falls below this are retired
+    pub retire_below: f64,
+    /// Ratings a challenge needs before it can be retired
+    pub min_ratings: i32,
+}
+
 impl Config {
     pub fn from_env() -> Self {
         dotenvy::dotenv().ok();
@@ -65,6 +162,107 @@ impl Config {
                     .parse()
                     .unwrap_or(100),
             },
+            moderation: ModerationConfig {
+                auto_hide_threshold: env::var("MODERATION_AUTO_HIDE_THRESHOLD")
+                    .unwrap_or_else(|_| "3".to_string())
+                    .parse()
+                    .unwrap_or(3),
+            },
+            content_filter: ContentFilterConfig {
+                ng_words_path: env::var("CONTENT_FILTER_NG_WORDS_PATH").ok(),
+                url_action: env::var("CONTENT_FILTER_URL_ACTION").unwrap_or_else(|_| "hold".to_string()),
+                repeat_action: env::var("CONTENT_FILTER_REPEAT_ACTION")
+                    .unwrap_or_else(|_| "hold".to_string()),
+                repeat_window_minutes: env::var("CONTENT_FILTER_REPEAT_WINDOW_MINUTES")
+                    .unwrap_or_else(|_| "1440".to_string())
+                    .parse()
+                    .unwrap_or(1440),
+                repeat_threshold: env::var("CONTENT_FILTER_REPEAT_THRESHOLD")
+                    .unwrap_or_else(|_| "2".to_string())
+                    .parse()
+                    .unwrap_or(2),
+            },
+            streak: StreakConfig {
+                freeze_earn_days: env::var("STREAK_FREEZE_EARN_DAYS")
+                    .unwrap_or_else(|_| "7".to_string())
+                    .parse()
+                    .unwrap_or(7),
+                max_freeze_tokens: env::var("STREAK_MAX_FREEZE_TOKENS")
+                    .unwrap_or_else(|_| "2".to_string())
+                    .parse()
+                    .unwrap_or(2),
+            },
+            xp: XpConfig {
+                answer_xp: env::var("XP_ANSWER")
+                    .unwrap_or_else(|_| "10".to_string())
+                    .parse()
+                    .unwrap_or(10),
+                like_xp: env::var("XP_LIKE")
+                    .unwrap_or_else(|_| "2".to_string())
+                    .parse()
+                    .unwrap_or(2),
+                score_multiplier: env::var("XP_SCORE_MULTIPLIER")
+                    .unwrap_or_else(|_| "0.2".to_string())
+                    .parse()
+                    .unwrap_or(0.2),
+                level_base: env::var("XP_LEVEL_BASE")
+                    .unwrap_or_else(|_| "50".to_string())
+                    .parse()
+                    .unwrap_or(50),
+            },
+            daily: DailyConfig {
+                timezone: env::var("DAILY_TIMEZONE").unwrap_or_else(|_| "Asia/Tokyo".to_string()),
+                schedule_days: env::var("DAILY_SCHEDULE_DAYS")
+                    .unwrap_or_else(|_| "7".to_string())
+                    .parse()
+                    .unwrap_or(7),
+            },
+            timed: TimedConfig {
+                time_limit_secs: env::var("TIMED_MODE_SECONDS")
+                    .unwrap_or_else(|_| "60".to_string())
+                    .parse()
+                    .unwrap_or(60),
+                grace_secs: env::var("TIMED_MODE_GRACE_SECONDS")
+                    .unwrap_or_else(|_| "5".to_string())
+                    .parse()
+                    .unwrap_or(5),
+                reject_late: env::var("TIMED_MODE_REJECT_LATE")
+                    .unwrap_or_else(|_| "false".to_string())
+                    .parse()
+                    .unwrap_or(false),
+            },
+            peer_review: PeerReviewConfig {
+                review_count: env::var("PEER_REVIEW_COUNT")
+                    .unwrap_or_else(|_| "3".to_string())
+                    .parse()
+                    .unwrap_or(3),
+                min_reviews: env::var("PEER_REVIEW_MIN_REVIEWS")
+                    .unwrap_or_else(|_| "2".to_string())
+                    .parse()
+                    .unwrap_or(2),
+            },
+            battle: BattleConfig {
+                k_factor: env::var("BATTLE_K_FACTOR")
+                    .unwrap_or_else(|_| "32".to_string())
+                    .parse()
+                    .unwrap_or(32.0),
+            },
+            contest: ContestConfig {
+                ai_weight: env::var("CONTEST_AI_WEIGHT")
+                    .unwrap_or_else(|_| "0.7".to_string())
+                    .parse()
+                    .unwrap_or(0.7),
+            },
+            challenge_rating: ChallengeRatingConfig {
+                retire_below: env::var("CHALLENGE_RETIRE_BELOW")
+                    .unwrap_or_else(|_| "2.0".to_string())
+                    .parse()
+                    .unwrap_or(2.0),
+                min_ratings: env::var("CHALLENGE_RETIRE_MIN_RATINGS")
+                    .unwrap_or_else(|_| "10".to_string())
+                    .parse()
+                    .unwrap_or(10),
+            },
         }
     }
 }
@@ -0,0 +1,261 @@
+mod ngword;
+mod repeat;
+mod url;
+
+use serde::Serialize;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::config::ContentFilterConfig;
+use crate::utils::text::{compact, fold};
+
+/// What to do with a piece of user content. Ordered by severity so the
+/// strictest verdict of the pipeline wins.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Verdict {
+    Allow,
+    Hold,
+    Reject,
+}
+
+impl Verdict {
+    /// Parses an action from configuration; anything but hold/reject disables the check.
+    fn from_action(action: &str) -> Self {
+        match action {
+            "reject" => Verdict::Reject,
+            "hold" => Verdict::Hold,
+            _ => Verdict::Allow,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReasonCode {
+    NgWord,
+    Url,
+    RepeatedPost,
+}
+
+impl ReasonCode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReasonCode::NgWord => "ng_word",
+            ReasonCode::Url => "url",
+            ReasonCode::RepeatedPost => "repeated_post",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContentKind {
+    Answer,
+    Comment,
+    UserName,
+    UserBio,
+    Proposal,
+}
+
+/// Text handed to each filter, pre-normalized once for the whole pipeline.
+pub struct FilterInput<'a> {
+    pub kind: ContentKind,
+    /// Width/kana-folded text with punctuation intact
+    pub folded: String,
+    /// Folded text stripped down to letters and digits
+    pub compact: String,
+    /// Compacted recent posts by the same author, for repeat detection
+    pub recent_posts: &'a [String],
+}
+
+pub struct FilterHit {
+    pub verdict: Verdict,
+    pub reason: ReasonCode,
+}
+
+/// A single check in the pipeline. Implementations must be cheap and
+/// synchronous; anything that needs the database is loaded up front.
+pub trait ContentFilter: Send + Sync {
+    fn check(&self, input: &FilterInput) -> Option<FilterHit>;
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FilterOutcome {
+    pub verdict: Verdict,
+    pub reasons: Vec<ReasonCode>,
+}
+
+impl FilterOutcome {
+    /// Combines the outcomes of several fields checked for the same submission.
+    pub fn merge(mut self, other: FilterOutcome) -> Self {
+        self.verdict = self.verdict.max(other.verdict);
+        for reason in other.reasons {
+            if !self.reasons.contains(&reason) {
+                self.reasons.push(reason);
+            }
+        }
+        self
+    }
+
+    pub fn describe(&self) -> String {
+        self.reasons
+            .iter()
+            .map(|r| r.as_str())
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+}
+
+pub struct FilterPipeline {
+    filters: Vec<Box<dyn ContentFilter>>,
+    repeat_window_minutes: i64,
+}
+
+impl FilterPipeline {
+    pub fn from_config(config: &ContentFilterConfig) -> Self {
+        let dictionary = match &config.ng_words_path {
+            Some(path) => match std::fs::read_to_string(path) {
+                Ok(contents) => ngword::NgWordFilter::parse(&contents),
+                Err(e) => {
+                    tracing::warn!("Failed to read NG word dictionary {}: {}", path, e);
+                    ngword::NgWordFilter::builtin()
+                }
+            },
+            None => ngword::NgWordFilter::builtin(),
+        };
+
+        let filters: Vec<Box<dyn ContentFilter>> = vec![
+            Box::new(dictionary),
+            Box::new(url::UrlFilter::new(Verdict::from_action(&config.url_action))),
+            Box::new(repeat::RepeatFilter::new(
+                Verdict::from_action(&config.repeat_action),
+                config.repeat_threshold,
+            )),
+        ];
+
+        Self {
+            filters,
+            repeat_window_minutes: config.repeat_window_minutes,
+        }
+    }
+
+    pub fn check(&self, kind: ContentKind, text: &str, recent_posts: &[String]) -> FilterOutcome {
+        let folded = fold(text);
+        let input = FilterInput {
+            kind,
+            compact: compact(&folded),
+            folded,
+            recent_posts,
+        };
+
+        let mut outcome = FilterOutcome {
+            verdict: Verdict::Allow,
+            reasons: Vec::new(),
+        };
+        for hit in self.filters.iter().filter_map(|f| f.check(&input)) {
+            if hit.verdict == Verdict::Allow {
+                continue;
+            }
+            outcome = outcome.merge(FilterOutcome {
+                verdict: hit.verdict,
+                reasons: vec![hit.reason],
+            });
+        }
+        outcome
+    }
+
+    /// Loads the author's recent answers and comments in compacted form,
+    /// leaving out the answer being edited so it is not compared with itself.
+    pub async fn recent_posts(&self, pool: &PgPool, user_id: Uuid, editing_answer_id: Option<Uuid>) -> Vec<String> {
+        let posts = sqlx::query_scalar::<_, String>(
+            r#"
+            SELECT content FROM answers
+            WHERE user_id = $1 AND status <> 'deleted'
+              AND created_at > NOW() - make_interval(mins => $2::int)
+              AND ($3::uuid IS NULL OR id <> $3)
+            UNION ALL
+            SELECT content FROM comments
+            WHERE user_id = $1 AND status <> 'deleted'
+              AND created_at > NOW() - make_interval(mins => $2::int)
+            "#,
+        )
+        .bind(user_id)
+        .bind(self.repeat_window_minutes)
+        .bind(editing_answer_id)
+        .fetch_all(pool)
+        .await
+        .unwrap_or_default();
+
+        posts.iter().map(|p| compact(&fold(p))).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pipeline(url_action: &str, repeat_action: &str) -> FilterPipeline {
+        FilterPipeline::from_config(&ContentFilterConfig {
+            ng_words_path: None,
+            url_action: url_action.to_string(),
+            repeat_action: repeat_action.to_string(),
+            repeat_window_minutes: 10,
+            repeat_threshold: 1,
+        })
+    }
+
+    #[test]
+    fn parses_actions() {
+        assert_eq!(Verdict::from_action("reject"), Verdict::Reject);
+        assert_eq!(Verdict::from_action("hold"), Verdict::Hold);
+        assert_eq!(Verdict::from_action("allow"), Verdict::Allow);
+        assert_eq!(Verdict::from_action("unknown"), Verdict::Allow);
+    }
+
+    #[test]
+    fn allows_clean_text() {
+        let outcome = pipeline("hold", "hold").check(ContentKind::Answer, "朝の光が窓を染める", &[]);
+        assert_eq!(outcome.verdict, Verdict::Allow);
+        assert!(outcome.reasons.is_empty());
+    }
+
+    #[test]
+    fn holds_and_rejects_with_reasons() {
+        let filters = pipeline("hold", "hold");
+
+        let outcome = filters.check(ContentKind::Comment, "詳しくは example.com へ", &[]);
+        assert_eq!(outcome.verdict, Verdict::Hold);
+        assert_eq!(outcome.reasons, vec![ReasonCode::Url]);
+
+        let outcome = filters.check(ContentKind::Comment, "死 ね example.com", &[]);
+        assert_eq!(outcome.verdict, Verdict::Reject);
+        assert_eq!(outcome.describe(), "ng_word,url");
+    }
+
+    #[test]
+    fn disabled_checks_do_not_report() {
+        let recent = vec!["またね".to_string()];
+        let outcome = pipeline("allow", "allow").check(ContentKind::Answer, "マタね example.com", &recent);
+        assert_eq!(outcome.verdict, Verdict::Allow);
+        assert!(outcome.reasons.is_empty());
+
+        let outcome = pipeline("allow", "reject").check(ContentKind::Answer, "マタね", &recent);
+        assert_eq!(outcome.verdict, Verdict::Reject);
+        assert_eq!(outcome.reasons, vec![ReasonCode::RepeatedPost]);
+    }
+
+    #[test]
+    fn merge_keeps_the_strictest_verdict_and_unique_reasons() {
+        let a = FilterOutcome {
+            verdict: Verdict::Hold,
+            reasons: vec![ReasonCode::Url],
+        };
+        let b = FilterOutcome {
+            verdict: Verdict::Reject,
+            reasons: vec![ReasonCode::Url, ReasonCode::NgWord],
+        };
+        let merged = a.merge(b);
+        assert_eq!(merged.verdict, Verdict::Reject);
+        assert_eq!(merged.reasons, vec![ReasonCode::Url, ReasonCode::NgWord]);
+    }
+}
@@ -0,0 +1,107 @@
+use super::{ContentFilter, FilterHit, FilterInput, ReasonCode, Verdict};
+use crate::utils::text::{compact, fold};
+
+// Used when no dictionary file is configured. Kept deliberately short:
+// substring matching on Japanese text is prone to false positives.
+const BUILTIN_DICTIONARY: &str = "\
+reject:死ね
+reject:氏ね
+reject:ぶっ殺
+reject:殺すぞ
+hold:殺す
+hold:きもい
+hold:うざい
+hold:消えろ
+";
+
+/// Matches normalized text against a dictionary of NG words.
+///
+/// Dictionary format: one entry per line as `reject:<word>` or `hold:<word>`
+/// (a bare word means reject); blank lines and lines starting with `#` are
+/// ignored. Entries are normalized the same way as the checked text, so
+/// "シネ", "ｼﾈ" and "し ね" all match an entry of "しね".
+pub struct NgWordFilter {
+    entries: Vec<(String, Verdict)>,
+}
+
+impl NgWordFilter {
+    pub fn builtin() -> Self {
+        Self::parse(BUILTIN_DICTIONARY)
+    }
+
+    pub fn parse(contents: &str) -> Self {
+        let entries = contents
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .filter_map(|line| {
+                let (verdict, word) = match line.split_once(':') {
+                    Some(("hold", word)) => (Verdict::Hold, word),
+                    Some(("reject", word)) => (Verdict::Reject, word),
+                    _ => (Verdict::Reject, line),
+                };
+                let word = compact(&fold(word));
+                (!word.is_empty()).then_some((word, verdict))
+            })
+            .collect();
+
+        Self { entries }
+    }
+}
+
+impl ContentFilter for NgWordFilter {
+    fn check(&self, input: &FilterInput) -> Option<FilterHit> {
+        self.entries
+            .iter()
+            .filter(|(word, _)| input.compact.contains(word.as_str()))
+            .map(|(_, verdict)| *verdict)
+            .max()
+            .map(|verdict| FilterHit {
+                verdict,
+                reason: ReasonCode::NgWord,
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::content_filter::ContentKind;
+
+    fn check(filter: &NgWordFilter, text: &str) -> Option<Verdict> {
+        let folded = fold(text);
+        let input = FilterInput {
+            kind: ContentKind::Comment,
+            compact: compact(&folded),
+            folded,
+            recent_posts: &[],
+        };
+        filter.check(&input).map(|hit| hit.verdict)
+    }
+
+    #[test]
+    fn matches_entries_after_normalisation() {
+        let filter = NgWordFilter::parse("しね");
+        assert_eq!(check(&filter, "シネ"), Some(Verdict::Reject));
+        assert_eq!(check(&filter, "ｼﾈ"), Some(Verdict::Reject));
+        assert_eq!(check(&filter, "し ・ ね"), Some(Verdict::Reject));
+        assert_eq!(check(&filter, "しない"), None);
+    }
+
+    #[test]
+    fn parses_actions_and_skips_comments() {
+        let filter = NgWordFilter::parse("# comment\n\nhold:うざい\nreject:ぶっ殺\nきもい\n");
+        assert_eq!(filter.entries.len(), 3);
+        assert_eq!(check(&filter, "ウザい"), Some(Verdict::Hold));
+        assert_eq!(check(&filter, "キモい"), Some(Verdict::Reject));
+        assert_eq!(check(&filter, "# comment"), None);
+    }
+
+    #[test]
+    fn strictest_entry_wins() {
+        let filter = NgWordFilter::builtin();
+        assert_eq!(check(&filter, "殺す"), Some(Verdict::Hold));
+        assert_eq!(check(&filter, "殺すぞ"), Some(Verdict::Reject));
+        assert_eq!(check(&filter, "素敵な文章です"), None);
+    }
+}
@@ -0,0 +1,66 @@
+use super::{ContentFilter, FilterHit, FilterInput, ReasonCode, Verdict};
+
+/// Flags text the author has already posted `threshold` times within the
+/// configured window, comparing compacted forms so trivial edits don't evade it.
+pub struct RepeatFilter {
+    verdict: Verdict,
+    threshold: usize,
+}
+
+impl RepeatFilter {
+    pub fn new(verdict: Verdict, threshold: usize) -> Self {
+        Self {
+            verdict,
+            threshold: threshold.max(1),
+        }
+    }
+}
+
+impl ContentFilter for RepeatFilter {
+    fn check(&self, input: &FilterInput) -> Option<FilterHit> {
+        if input.compact.is_empty() {
+            return None;
+        }
+
+        let repeats = input
+            .recent_posts
+            .iter()
+            .filter(|post| **post == input.compact)
+            .count();
+
+        (repeats >= self.threshold).then_some(FilterHit {
+            verdict: self.verdict,
+            reason: ReasonCode::RepeatedPost,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::content_filter::ContentKind;
+
+    fn check(threshold: usize, compact: &str, recent_posts: &[String]) -> bool {
+        let input = FilterInput {
+            kind: ContentKind::Comment,
+            folded: compact.to_string(),
+            compact: compact.to_string(),
+            recent_posts,
+        };
+        RepeatFilter::new(Verdict::Hold, threshold).check(&input).is_some()
+    }
+
+    #[test]
+    fn flags_posts_repeated_up_to_the_threshold() {
+        let recent = vec!["よろしく".to_string(), "よろしく".to_string(), "ほか".to_string()];
+        assert!(check(2, "よろしく", &recent));
+        assert!(!check(3, "よろしく", &recent));
+        assert!(!check(1, "はじめまして", &recent));
+    }
+
+    #[test]
+    fn ignores_empty_text_and_clamps_threshold() {
+        assert!(!check(1, "", &[String::new()]));
+        assert!(check(0, "a", &["a".to_string()]));
+    }
+}
@@ -0,0 +1,88 @@
+use super::{ContentFilter, ContentKind, FilterHit, FilterInput, ReasonCode, Verdict};
+
+// Top-level domains common in spam links; bare "example.com" style tokens
+// ending in one of these count as links even without a scheme.
+const LINK_TLDS: &[&str] = &[
+    "com", "net", "org", "jp", "io", "co", "me", "ly", "app", "dev", "info", "xyz", "biz", "link",
+    "site", "shop",
+];
+
+/// Flags links in answers, comments and display names. Profile bios are
+/// exempt since linking a personal site there is legitimate.
+pub struct UrlFilter {
+    verdict: Verdict,
+}
+
+impl UrlFilter {
+    pub fn new(verdict: Verdict) -> Self {
+        Self { verdict }
+    }
+}
+
+impl ContentFilter for UrlFilter {
+    fn check(&self, input: &FilterInput) -> Option<FilterHit> {
+        if input.kind == ContentKind::UserBio || !contains_link(&input.folded) {
+            return None;
+        }
+        Some(FilterHit {
+            verdict: self.verdict,
+            reason: ReasonCode::Url,
+        })
+    }
+}
+
+fn contains_link(folded: &str) -> bool {
+    if folded.contains("http://") || folded.contains("https://") || folded.contains("www.") {
+        return true;
+    }
+
+    folded
+        .split(|c: char| !(c.is_ascii_alphanumeric() || c == '.' || c == '-' || c == '/'))
+        .any(|token| {
+            let host = token.split('/').next().unwrap_or("");
+            match host.trim_end_matches('.').rsplit_once('.') {
+                Some((name, tld)) => !name.is_empty() && LINK_TLDS.contains(&tld),
+                None => false,
+            }
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::text::{compact, fold};
+
+    fn check(kind: ContentKind, text: &str) -> bool {
+        let folded = fold(text);
+        let input = FilterInput {
+            kind,
+            compact: compact(&folded),
+            folded,
+            recent_posts: &[],
+        };
+        UrlFilter::new(Verdict::Hold).check(&input).is_some()
+    }
+
+    #[test]
+    fn detects_links_with_and_without_scheme() {
+        assert!(contains_link("see https://example.com/x"));
+        assert!(contains_link("www.example"));
+        assert!(contains_link("visit spam-site.xyz/path now"));
+        assert!(contains_link("example.com."));
+    }
+
+    #[test]
+    fn ignores_text_that_only_looks_dotted() {
+        assert!(!contains_link("version 1.2 is out"));
+        assert!(!contains_link("e.g. this"));
+        assert!(!contains_link(".com"));
+        assert!(!contains_link("素晴らしい。com"));
+    }
+
+    #[test]
+    fn folds_full_width_links_and_exempts_bios() {
+        assert!(check(ContentKind::Answer, "ｈｔｔｐｓ：／／ｅｘａｍｐｌｅ．ｃｏｍ"));
+        assert!(check(ContentKind::UserName, "me.jp"));
+        assert!(!check(ContentKind::UserBio, "https://example.com"));
+    }
+}
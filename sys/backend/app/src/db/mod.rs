@@ -1,8 +1,12 @@
+pub mod visibility;
+
 use sqlx::postgres::PgPoolOptions;
 use sqlx::PgPool;
 use tracing::info;
+use uuid::Uuid;
 
 use crate::config::DatabaseConfig;
+use crate::utils::text::search_key;
 
 pub type DbPool = PgPool;
 
@@ -120,22 +124,6 @@ pub async fn run_migrations(pool: &DbPool) {
     .await
     .expect("Failed to create comments table");
 
-    // Likes table
-    sqlx::query(
-        r#"
-        CREATE TABLE IF NOT EXISTS likes (
-            id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
-            answer_id UUID NOT NULL REFERENCES answers(id) ON DELETE CASCADE,
-            user_id UUID NOT NULL REFERENCES users(id),
-            created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
-            UNIQUE(answer_id, user_id)
-        )
-        "#,
-    )
-    .execute(pool)
-    .await
-    .expect("Failed to create likes table");
-
     // Follows table
     sqlx::query(
         r#"
@@ -152,51 +140,1117 @@ pub async fn run_migrations(pool: &DbPool) {
     .await
     .expect("Failed to create follows table");
 
-    // Indexes
-    sqlx::query("CREATE INDEX IF NOT EXISTS idx_challenges_category ON challenges(category_id)")
+    // User roles (user, moderator, admin)
+    sqlx::query("ALTER TABLE users ADD COLUMN IF NOT EXISTS role VARCHAR(20) NOT NULL DEFAULT 'user'")
         .execute(pool)
         .await
-        .ok();
+        .expect("Failed to add users.role column");
 
-    sqlx::query("CREATE INDEX IF NOT EXISTS idx_challenges_release_date ON challenges(release_date)")
+    // Folded display name for user search (see utils::text::search_key)
+    sqlx::query("ALTER TABLE users ADD COLUMN IF NOT EXISTS search_name TEXT")
         .execute(pool)
         .await
-        .ok();
+        .expect("Failed to add users.search_name column");
 
-    sqlx::query("CREATE INDEX IF NOT EXISTS idx_answers_challenge ON answers(challenge_id)")
+    // Private accounts only show answers to approved followers
+    sqlx::query("ALTER TABLE users ADD COLUMN IF NOT EXISTS is_private BOOLEAN NOT NULL DEFAULT FALSE")
         .execute(pool)
         .await
-        .ok();
+        .expect("Failed to add users.is_private column");
 
-    sqlx::query("CREATE INDEX IF NOT EXISTS idx_answers_user ON answers(user_id)")
+    // Timezone used for daily streaks and the activity calendar
+    sqlx::query("ALTER TABLE users ADD COLUMN IF NOT EXISTS timezone VARCHAR(64) NOT NULL DEFAULT 'Asia/Tokyo'")
         .execute(pool)
         .await
-        .ok();
+        .expect("Failed to add users.timezone column");
 
-    sqlx::query("CREATE INDEX IF NOT EXISTS idx_answers_created ON answers(created_at DESC)")
+    // Follow status (pending requests to private accounts, accepted)
+    sqlx::query("ALTER TABLE follows ADD COLUMN IF NOT EXISTS status VARCHAR(20) NOT NULL DEFAULT 'accepted'")
         .execute(pool)
         .await
-        .ok();
+        .expect("Failed to add follows.status column");
 
-    sqlx::query("CREATE INDEX IF NOT EXISTS idx_comments_answer ON comments(answer_id)")
+    // Answer visibility (public, followers, private practice)
+    sqlx::query("ALTER TABLE answers ADD COLUMN IF NOT EXISTS visibility VARCHAR(20) NOT NULL DEFAULT 'public'")
         .execute(pool)
         .await
-        .ok();
+        .expect("Failed to add answers.visibility column");
 
-    sqlx::query("CREATE INDEX IF NOT EXISTS idx_likes_answer ON likes(answer_id)")
+    // Multiple attempts per challenge; one attempt per user and challenge is published
+    sqlx::query("ALTER TABLE answers ADD COLUMN IF NOT EXISTS attempt_number INTEGER NOT NULL DEFAULT 1")
         .execute(pool)
         .await
-        .ok();
+        .expect("Failed to add answers.attempt_number column");
 
-    sqlx::query("CREATE INDEX IF NOT EXISTS idx_follows_follower ON follows(follower_id)")
+    sqlx::query("ALTER TABLE answers ADD COLUMN IF NOT EXISTS is_published BOOLEAN NOT NULL DEFAULT TRUE")
         .execute(pool)
         .await
-        .ok();
+        .expect("Failed to add answers.is_published column");
 
-    sqlx::query("CREATE INDEX IF NOT EXISTS idx_follows_following ON follows(following_id)")
+    // Difficulty: manual tier, tier derived from scores, and the hard-mode char limit
+    sqlx::query("ALTER TABLE challenges ADD COLUMN IF NOT EXISTS difficulty VARCHAR(10)")
         .execute(pool)
         .await
-        .ok();
+        .expect("Failed to add challenges.difficulty column");
 
-    info!("Migrations completed successfully");
+    sqlx::query("ALTER TABLE challenges ADD COLUMN IF NOT EXISTS derived_difficulty VARCHAR(10) NOT NULL DEFAULT 'normal'")
+        .execute(pool)
+        .await
+        .expect("Failed to add challenges.derived_difficulty column");
+
+    sqlx::query("ALTER TABLE challenges ADD COLUMN IF NOT EXISTS hard_mode_char_limit INTEGER")
+        .execute(pool)
+        .await
+        .expect("Failed to add challenges.hard_mode_char_limit column");
+
+    sqlx::query("ALTER TABLE answers ADD COLUMN IF NOT EXISTS hard_mode BOOLEAN NOT NULL DEFAULT FALSE")
+        .execute(pool)
+        .await
+        .expect("Failed to add answers.hard_mode column");
+
+    // Timed mode: the writing session an answer was submitted from
+    sqlx::query("ALTER TABLE answers ADD COLUMN IF NOT EXISTS session_id UUID")
+        .execute(pool)
+        .await
+        .expect("Failed to add answers.session_id column");
+
+    sqlx::query("ALTER TABLE answers ADD COLUMN IF NOT EXISTS elapsed_ms INTEGER")
+        .execute(pool)
+        .await
+        .expect("Failed to add answers.elapsed_ms column");
+
+    sqlx::query("ALTER TABLE answers ADD COLUMN IF NOT EXISTS is_late BOOLEAN NOT NULL DEFAULT FALSE")
+        .execute(pool)
+        .await
+        .expect("Failed to add answers.is_late column");
+
+    // Answer revisions table (wording and score history of each attempt)
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS answer_revisions (
+            id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+            answer_id UUID NOT NULL REFERENCES answers(id) ON DELETE CASCADE,
+            challenge_id UUID NOT NULL REFERENCES challenges(id),
+            user_id UUID NOT NULL REFERENCES users(id),
+            attempt_number INTEGER NOT NULL,
+            revision_number INTEGER NOT NULL,
+            content TEXT NOT NULL,
+            score INTEGER,
+            ai_feedback JSONB,
+            created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+            UNIQUE(answer_id, revision_number)
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .expect("Failed to create answer_revisions table");
+
+    // Answers written before revisions existed start their history with the current wording
+    sqlx::query(
+        r#"
+        INSERT INTO answer_revisions (answer_id, challenge_id, user_id, attempt_number, revision_number, content, score, ai_feedback, created_at)
+        SELECT a.id, a.challenge_id, a.user_id, a.attempt_number, 1, a.content, a.score, a.ai_feedback, a.created_at
+        FROM answers a
+        WHERE NOT EXISTS (SELECT 1 FROM answer_revisions r WHERE r.answer_id = a.id)
+        "#,
+    )
+    .execute(pool)
+    .await
+    .expect("Failed to backfill answer_revisions");
+
+    // Drafts table (one autosaved draft per user and challenge)
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS drafts (
+            id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+            user_id UUID NOT NULL REFERENCES users(id),
+            challenge_id UUID NOT NULL REFERENCES challenges(id),
+            content TEXT NOT NULL DEFAULT '',
+            version INTEGER NOT NULL DEFAULT 1,
+            created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+            updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+            UNIQUE(user_id, challenge_id)
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .expect("Failed to create drafts table");
+
+    // Timed writing sessions (issued by the server, consumed by one answer)
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS answer_sessions (
+            id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+            user_id UUID NOT NULL REFERENCES users(id),
+            challenge_id UUID NOT NULL REFERENCES challenges(id),
+            started_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+            deadline_at TIMESTAMPTZ NOT NULL,
+            submitted_at TIMESTAMPTZ
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .expect("Failed to create answer_sessions table");
+
+    // User streaks table (consecutive active days in the user's timezone)
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS user_streaks (
+            user_id UUID PRIMARY KEY REFERENCES users(id),
+            current_streak INTEGER NOT NULL DEFAULT 0,
+            longest_streak INTEGER NOT NULL DEFAULT 0,
+            last_active_date DATE,
+            freeze_tokens INTEGER NOT NULL DEFAULT 0,
+            updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .expect("Failed to create user_streaks table");
+
+    // Badge catalog; each badge is awarded when its rule's metric reaches the threshold
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS badges (
+            key VARCHAR(50) PRIMARY KEY,
+            name VARCHAR(100) NOT NULL,
+            description TEXT,
+            icon VARCHAR(20),
+            rule VARCHAR(50) NOT NULL,
+            threshold INTEGER NOT NULL DEFAULT 1,
+            sort_order INTEGER NOT NULL DEFAULT 0,
+            status VARCHAR(20) NOT NULL DEFAULT 'active',
+            created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .expect("Failed to create badges table");
+
+    // Default badges; existing rows keep any admin edits
+    sqlx::query(
+        r#"
+        INSERT INTO badges (key, name, description, icon, rule, threshold, sort_order) VALUES
+        ('first_answer', 'はじめの一歩', '初めて回答を投稿した', '🌱', 'answer_count', 1, 1),
+        ('streak_7', '七日坊主卒業', '7日連続で回答した', '🔥', 'longest_streak', 7, 2),
+        ('likes_100', '人気者', 'いいねを100回もらった', '❤️', 'total_likes', 100, 3),
+        ('all_categories', '全カテゴリ制覇', 'すべてのカテゴリに回答した', '🗾', 'all_categories', 1, 4),
+        ('high_score', '言語化の達人', 'AIスコア90点以上を獲得した', '🏆', 'max_score', 90, 5),
+        ('contest_winner', 'チャンピオン', 'コンテストで優勝した', '🥇', 'contest_wins', 1, 6),
+        ('contest_podium', '表彰台', 'コンテストで3位以内に入った', '🎖️', 'contest_podiums', 1, 7)
+        ON CONFLICT (key) DO NOTHING
+        "#,
+    )
+    .execute(pool)
+    .await
+    .expect("Failed to seed badges");
+
+    // Awarded badges (one row per user and badge)
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS user_badges (
+            id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+            user_id UUID NOT NULL REFERENCES users(id),
+            badge_key VARCHAR(50) NOT NULL REFERENCES badges(key),
+            awarded_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+            UNIQUE(user_id, badge_key)
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .expect("Failed to create user_badges table");
+
+    // Reports table (reporter_id is NULL for system-filed reports)
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS reports (
+            id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+            reporter_id UUID REFERENCES users(id),
+            target_type VARCHAR(20) NOT NULL,
+            target_id UUID NOT NULL,
+            reason VARCHAR(30) NOT NULL,
+            detail TEXT,
+            status VARCHAR(20) NOT NULL DEFAULT 'open',
+            resolved_by UUID REFERENCES users(id),
+            resolved_at TIMESTAMPTZ,
+            created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .expect("Failed to create reports table");
+
+    // Moderation action log (moderator_id is NULL for automatic actions)
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS moderation_actions (
+            id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+            moderator_id UUID REFERENCES users(id),
+            target_type VARCHAR(20) NOT NULL,
+            target_id UUID NOT NULL,
+            action VARCHAR(20) NOT NULL,
+            reason TEXT,
+            created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .expect("Failed to create moderation_actions table");
+
+    // Warnings issued to users, shown to them until acknowledged
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS user_warnings (
+            id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+            user_id UUID NOT NULL REFERENCES users(id),
+            moderator_id UUID NOT NULL REFERENCES users(id),
+            reason TEXT NOT NULL,
+            acknowledged_at TIMESTAMPTZ,
+            created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .expect("Failed to create user_warnings table");
+
+    // Bookmarks table (private to the bookmarking user)
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS bookmarks (
+            id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+            user_id UUID NOT NULL REFERENCES users(id),
+            answer_id UUID NOT NULL REFERENCES answers(id) ON DELETE CASCADE,
+            created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+            UNIQUE(user_id, answer_id)
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .expect("Failed to create bookmarks table");
+
+    // Collections table (named, private lists of answers)
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS collections (
+            id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+            user_id UUID NOT NULL REFERENCES users(id),
+            name VARCHAR(100) NOT NULL,
+            description TEXT,
+            created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+            updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .expect("Failed to create collections table");
+
+    // Collection items table
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS collection_items (
+            id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+            collection_id UUID NOT NULL REFERENCES collections(id) ON DELETE CASCADE,
+            answer_id UUID NOT NULL REFERENCES answers(id) ON DELETE CASCADE,
+            position INTEGER NOT NULL,
+            created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+            UNIQUE(collection_id, answer_id)
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .expect("Failed to create collection_items table");
+
+    // Reaction types table (the configurable reaction set)
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS reaction_types (
+            key VARCHAR(30) PRIMARY KEY,
+            label VARCHAR(50) NOT NULL,
+            emoji VARCHAR(20),
+            ranking_weight DOUBLE PRECISION NOT NULL DEFAULT 1.0,
+            sort_order INTEGER NOT NULL DEFAULT 0,
+            status VARCHAR(20) NOT NULL DEFAULT 'active',
+            created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .expect("Failed to create reaction_types table");
+
+    // Default reaction set; existing rows keep any admin edits
+    sqlx::query(
+        r#"
+        INSERT INTO reaction_types (key, label, emoji, ranking_weight, sort_order) VALUES
+        ('like', 'いいね', '❤️', 1.0, 1),
+        ('funny', '面白い', '😂', 1.0, 2),
+        ('insightful', 'なるほど', '💡', 1.5, 3),
+        ('moving', '刺さった', '🎯', 2.0, 4)
+        ON CONFLICT (key) DO NOTHING
+        "#,
+    )
+    .execute(pool)
+    .await
+    .expect("Failed to seed reaction_types");
+
+    // Reactions table
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS reactions (
+            id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+            answer_id UUID NOT NULL REFERENCES answers(id) ON DELETE CASCADE,
+            user_id UUID NOT NULL REFERENCES users(id),
+            reaction_type VARCHAR(30) NOT NULL REFERENCES reaction_types(key),
+            created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+            UNIQUE(answer_id, user_id, reaction_type)
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .expect("Failed to create reactions table");
+
+    // Move likes from the legacy likes table into reactions, once
+    sqlx::query(
+        r#"
+        DO $$
+        BEGIN
+            IF to_regclass('likes') IS NOT NULL THEN
+                INSERT INTO reactions (answer_id, user_id, reaction_type, created_at)
+                SELECT answer_id, user_id, 'like', created_at FROM likes
+                ON CONFLICT (answer_id, user_id, reaction_type) DO NOTHING;
+                DROP TABLE likes;
+            END IF;
+        END
+        $$
+        "#,
+    )
+    .execute(pool)
+    .await
+    .expect("Failed to migrate likes to reactions");
+
+    // XP ledger (append-only; XP amounts are derived from config when read)
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS xp_events (
+            id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+            user_id UUID NOT NULL REFERENCES users(id),
+            event_type VARCHAR(20) NOT NULL,
+            answer_id UUID NOT NULL REFERENCES answers(id) ON DELETE CASCADE,
+            challenge_id UUID NOT NULL REFERENCES challenges(id),
+            category_id UUID NOT NULL REFERENCES categories(id),
+            value INTEGER,
+            created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .expect("Failed to create xp_events table");
+
+    // Seed the ledger from existing answers, scores and likes the first time it is created
+    sqlx::query(
+        r#"
+        INSERT INTO xp_events (user_id, event_type, answer_id, challenge_id, category_id, value, created_at)
+        SELECT * FROM (
+            SELECT a.user_id, 'answer', a.id, a.challenge_id, ch.category_id, NULL::INTEGER, a.created_at
+            FROM answers a JOIN challenges ch ON ch.id = a.challenge_id
+            UNION ALL
+            SELECT r.user_id, 'score', r.answer_id, r.challenge_id, ch.category_id, r.score, r.created_at
+            FROM answer_revisions r JOIN challenges ch ON ch.id = r.challenge_id
+            WHERE r.score IS NOT NULL
+            UNION ALL
+            SELECT a.user_id, 'like', a.id, a.challenge_id, ch.category_id, NULL::INTEGER, re.created_at
+            FROM reactions re
+            JOIN answers a ON a.id = re.answer_id
+            JOIN challenges ch ON ch.id = a.challenge_id
+            WHERE re.reaction_type = 'like'
+        ) history
+        WHERE NOT EXISTS (SELECT 1 FROM xp_events)
+        "#,
+    )
+    .execute(pool)
+    .await
+    .expect("Failed to backfill xp_events");
+
+    // Daily set schedule: one challenge per category per day
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS daily_challenges (
+            schedule_date DATE NOT NULL,
+            category_id UUID NOT NULL REFERENCES categories(id),
+            challenge_id UUID NOT NULL REFERENCES challenges(id),
+            created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+            PRIMARY KEY (schedule_date, category_id)
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .expect("Failed to create daily_challenges table");
+
+    // Courses: curated, ordered sequences of challenges
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS courses (
+            id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+            title VARCHAR(200) NOT NULL,
+            description TEXT,
+            category_id UUID REFERENCES categories(id),
+            status VARCHAR(20) NOT NULL DEFAULT 'active',
+            created_by UUID NOT NULL REFERENCES users(id),
+            created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+            updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .expect("Failed to create courses table");
+
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS course_steps (
+            id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+            course_id UUID NOT NULL REFERENCES courses(id) ON DELETE CASCADE,
+            challenge_id UUID NOT NULL REFERENCES challenges(id),
+            position INTEGER NOT NULL,
+            unlock_rule VARCHAR(20) NOT NULL DEFAULT 'sequential',
+            pass_score INTEGER,
+            UNIQUE(course_id, position),
+            UNIQUE(course_id, challenge_id)
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .expect("Failed to create course_steps table");
+
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS course_enrollments (
+            id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+            course_id UUID NOT NULL REFERENCES courses(id) ON DELETE CASCADE,
+            user_id UUID NOT NULL REFERENCES users(id),
+            completed_steps INTEGER NOT NULL DEFAULT 0,
+            enrolled_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+            updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+            completed_at TIMESTAMPTZ,
+            UNIQUE(course_id, user_id)
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .expect("Failed to create course_enrollments table");
+
+    // Completion certificates (one per user and course, kept as a permanent record)
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS course_certificates (
+            id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+            course_id UUID NOT NULL REFERENCES courses(id),
+            user_id UUID NOT NULL REFERENCES users(id),
+            course_title VARCHAR(200) NOT NULL,
+            average_score DOUBLE PRECISION,
+            issued_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+            UNIQUE(course_id, user_id)
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .expect("Failed to create course_certificates table");
+
+    // Organizations (companies, schools) with role-based membership
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS organizations (
+            id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+            name VARCHAR(100) NOT NULL,
+            description TEXT,
+            created_by UUID NOT NULL REFERENCES users(id),
+            created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+            updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .expect("Failed to create organizations table");
+
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS organization_members (
+            organization_id UUID NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+            user_id UUID NOT NULL REFERENCES users(id),
+            role VARCHAR(20) NOT NULL DEFAULT 'learner', -- owner, instructor, learner
+            joined_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+            PRIMARY KEY (organization_id, user_id)
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .expect("Failed to create organization_members table");
+
+    // Groups (classes, teams) within an organization
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS org_groups (
+            id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+            organization_id UUID NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+            name VARCHAR(100) NOT NULL,
+            created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+            UNIQUE(organization_id, name)
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .expect("Failed to create org_groups table");
+
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS org_group_members (
+            group_id UUID NOT NULL REFERENCES org_groups(id) ON DELETE CASCADE,
+            user_id UUID NOT NULL REFERENCES users(id),
+            added_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+            PRIMARY KEY (group_id, user_id)
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .expect("Failed to create org_group_members table");
+
+    // Private challenges belong to an organization and are only visible to its members
+    sqlx::query("ALTER TABLE challenges ADD COLUMN IF NOT EXISTS organization_id UUID REFERENCES organizations(id)")
+        .execute(pool)
+        .await
+        .expect("Failed to add challenges.organization_id column");
+
+    // Challenges assigned to a group with a deadline
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS assignments (
+            id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+            organization_id UUID NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+            group_id UUID NOT NULL REFERENCES org_groups(id) ON DELETE CASCADE,
+            challenge_id UUID NOT NULL REFERENCES challenges(id),
+            deadline_at TIMESTAMPTZ NOT NULL,
+            created_by UUID NOT NULL REFERENCES users(id),
+            created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+            UNIQUE(group_id, challenge_id)
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .expect("Failed to create assignments table");
+
+    // Grading rubric per category, scored by human reviewers
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS rubric_criteria (
+            id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+            category_id UUID NOT NULL REFERENCES categories(id),
+            key VARCHAR(50) NOT NULL,
+            name VARCHAR(100) NOT NULL,
+            description TEXT,
+            max_points INTEGER NOT NULL DEFAULT 5,
+            position INTEGER NOT NULL,
+            created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+            UNIQUE(category_id, key)
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .expect("Failed to create rubric_criteria table");
+
+    // Human reviews (one per reviewer and answer); scores holds [{name, score}] per criterion key
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS answer_reviews (
+            id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+            answer_id UUID NOT NULL REFERENCES answers(id),
+            reviewer_id UUID NOT NULL REFERENCES users(id),
+            scores JSONB NOT NULL DEFAULT '[]',
+            total_score INTEGER NOT NULL,
+            feedback TEXT,
+            created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+            updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+            UNIQUE(answer_id, reviewer_id)
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .expect("Failed to create answer_reviews table");
+
+    // Average of the human reviews' total scores, next to the AI score
+    sqlx::query("ALTER TABLE answers ADD COLUMN IF NOT EXISTS human_score INTEGER")
+        .execute(pool)
+        .await
+        .expect("Failed to add answers.human_score column");
+
+    // Peer review tasks: answers handed out anonymously to other users of the
+    // same challenge, completed once scored on the category rubric
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS peer_reviews (
+            id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+            answer_id UUID NOT NULL REFERENCES answers(id),
+            reviewer_id UUID NOT NULL REFERENCES users(id),
+            scores JSONB,
+            total_score INTEGER,
+            feedback TEXT,
+            assigned_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+            completed_at TIMESTAMPTZ,
+            UNIQUE(answer_id, reviewer_id)
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .expect("Failed to create peer_reviews table");
+
+    // Reliability-weighted average of completed peer reviews
+    sqlx::query("ALTER TABLE answers ADD COLUMN IF NOT EXISTS peer_score INTEGER")
+        .execute(pool)
+        .await
+        .expect("Failed to add answers.peer_score column");
+
+    sqlx::query("ALTER TABLE answers ADD COLUMN IF NOT EXISTS peer_review_count INTEGER NOT NULL DEFAULT 0")
+        .execute(pool)
+        .await
+        .expect("Failed to add answers.peer_review_count column");
+
+    // Weight of the user's peer reviews, lowered when they stray from consensus
+    sqlx::query("ALTER TABLE users ADD COLUMN IF NOT EXISTS peer_reliability DOUBLE PRECISION NOT NULL DEFAULT 1.0")
+        .execute(pool)
+        .await
+        .expect("Failed to add users.peer_reliability column");
+
+    // Elo ratings from head-to-head battles, for answers and their authors
+    sqlx::query("ALTER TABLE answers ADD COLUMN IF NOT EXISTS rating DOUBLE PRECISION NOT NULL DEFAULT 1500")
+        .execute(pool)
+        .await
+        .expect("Failed to add answers.rating column");
+
+    sqlx::query("ALTER TABLE answers ADD COLUMN IF NOT EXISTS battle_count INTEGER NOT NULL DEFAULT 0")
+        .execute(pool)
+        .await
+        .expect("Failed to add answers.battle_count column");
+
+    sqlx::query("ALTER TABLE users ADD COLUMN IF NOT EXISTS rating DOUBLE PRECISION NOT NULL DEFAULT 1500")
+        .execute(pool)
+        .await
+        .expect("Failed to add users.rating column");
+
+    sqlx::query("ALTER TABLE users ADD COLUMN IF NOT EXISTS battle_count INTEGER NOT NULL DEFAULT 0")
+        .execute(pool)
+        .await
+        .expect("Failed to add users.battle_count column");
+
+    // Pairs of answers to the same challenge shown to a voter; winner_id is set once they vote
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS battles (
+            id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+            challenge_id UUID NOT NULL REFERENCES challenges(id),
+            voter_id UUID NOT NULL REFERENCES users(id),
+            answer_a_id UUID NOT NULL REFERENCES answers(id),
+            answer_b_id UUID NOT NULL REFERENCES answers(id),
+            winner_id UUID REFERENCES answers(id),
+            created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+            voted_at TIMESTAMPTZ
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .expect("Failed to create battles table");
+
+    // Time-boxed contests: entries are accepted from starts_at until
+    // entries_close_at, then voted on until ends_at, when standings freeze
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS contests (
+            id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+            title VARCHAR(200) NOT NULL,
+            description TEXT,
+            starts_at TIMESTAMPTZ NOT NULL,
+            entries_close_at TIMESTAMPTZ NOT NULL,
+            ends_at TIMESTAMPTZ NOT NULL,
+            finalized_at TIMESTAMPTZ,
+            created_by UUID NOT NULL REFERENCES users(id),
+            created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .expect("Failed to create contests table");
+
+    sqlx::query("ALTER TABLE challenges ADD COLUMN IF NOT EXISTS contest_id UUID REFERENCES contests(id)")
+        .execute(pool)
+        .await
+        .expect("Failed to add challenges.contest_id column");
+
+    // One vote per user and entry, cast while the contest is in its voting window
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS contest_votes (
+            id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+            contest_id UUID NOT NULL REFERENCES contests(id),
+            answer_id UUID NOT NULL REFERENCES answers(id),
+            voter_id UUID NOT NULL REFERENCES users(id),
+            created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+            UNIQUE(answer_id, voter_id)
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .expect("Failed to create contest_votes table");
+
+    // Final standings, written once when the contest ends
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS contest_standings (
+            contest_id UUID NOT NULL REFERENCES contests(id),
+            user_id UUID NOT NULL REFERENCES users(id),
+            rank INTEGER NOT NULL,
+            total_score DOUBLE PRECISION NOT NULL,
+            ai_score INTEGER NOT NULL,
+            vote_count INTEGER NOT NULL,
+            entry_count INTEGER NOT NULL,
+            PRIMARY KEY (contest_id, user_id)
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .expect("Failed to create contest_standings table");
+
+    // Answers editors picked as exemplary for their challenge
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS editorial_picks (
+            id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+            challenge_id UUID NOT NULL REFERENCES challenges(id),
+            answer_id UUID NOT NULL UNIQUE REFERENCES answers(id),
+            picked_by UUID NOT NULL REFERENCES users(id),
+            note TEXT,
+            created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .expect("Failed to create editorial_picks table");
+
+    // A picked answer shown instead of the AI's example answer in feedback
+    sqlx::query("ALTER TABLE challenges ADD COLUMN IF NOT EXISTS example_answer_id UUID REFERENCES answers(id)")
+        .execute(pool)
+        .await
+        .expect("Failed to add challenges.example_answer_id column");
+
+    // User-submitted challenge ideas; editors accept them as draft challenges
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS challenge_proposals (
+            id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+            category_id UUID NOT NULL REFERENCES categories(id),
+            user_id UUID NOT NULL REFERENCES users(id),
+            title VARCHAR(200) NOT NULL,
+            description TEXT,
+            status VARCHAR(20) NOT NULL DEFAULT 'open',
+            upvote_count INTEGER NOT NULL DEFAULT 0,
+            challenge_id UUID REFERENCES challenges(id),
+            reviewed_by UUID REFERENCES users(id),
+            review_note TEXT,
+            reviewed_at TIMESTAMPTZ,
+            created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .expect("Failed to create challenge_proposals table");
+
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS proposal_upvotes (
+            proposal_id UUID NOT NULL REFERENCES challenge_proposals(id),
+            user_id UUID NOT NULL REFERENCES users(id),
+            created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+            PRIMARY KEY (proposal_id, user_id)
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .expect("Failed to create proposal_upvotes table");
+
+    // 1-5 quality ratings from users who answered the challenge
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS challenge_ratings (
+            challenge_id UUID NOT NULL REFERENCES challenges(id),
+            user_id UUID NOT NULL REFERENCES users(id),
+            rating INTEGER NOT NULL,
+            created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+            updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+            PRIMARY KEY (challenge_id, user_id)
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .expect("Failed to create challenge_ratings table");
+
+    sqlx::query("ALTER TABLE challenges ADD COLUMN IF NOT EXISTS rating_avg DOUBLE PRECISION")
+        .execute(pool)
+        .await
+        .expect("Failed to add challenges.rating_avg column");
+
+    sqlx::query("ALTER TABLE challenges ADD COLUMN IF NOT EXISTS rating_count INTEGER NOT NULL DEFAULT 0")
+        .execute(pool)
+        .await
+        .expect("Failed to add challenges.rating_count column");
+
+    // Indexes
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_challenges_category ON challenges(category_id)")
+        .execute(pool)
+        .await
+        .ok();
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_challenges_release_date ON challenges(release_date)")
+        .execute(pool)
+        .await
+        .ok();
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_answers_challenge ON answers(challenge_id)")
+        .execute(pool)
+        .await
+        .ok();
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_answers_user ON answers(user_id)")
+        .execute(pool)
+        .await
+        .ok();
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_answers_created ON answers(created_at DESC)")
+        .execute(pool)
+        .await
+        .ok();
+
+    sqlx::query(
+        "CREATE UNIQUE INDEX IF NOT EXISTS idx_answers_published_unique ON answers(challenge_id, user_id) WHERE is_published AND status <> 'deleted'",
+    )
+    .execute(pool)
+    .await
+    .ok();
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_answer_sessions_user ON answer_sessions(user_id, started_at DESC)")
+        .execute(pool)
+        .await
+        .ok();
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_answer_revisions_answer ON answer_revisions(answer_id, revision_number)")
+        .execute(pool)
+        .await
+        .ok();
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_daily_challenges_challenge ON daily_challenges(challenge_id, schedule_date)")
+        .execute(pool)
+        .await
+        .ok();
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_course_enrollments_user ON course_enrollments(user_id)")
+        .execute(pool)
+        .await
+        .ok();
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_organization_members_user ON organization_members(user_id)")
+        .execute(pool)
+        .await
+        .ok();
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_challenges_organization ON challenges(organization_id)")
+        .execute(pool)
+        .await
+        .ok();
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_challenges_contest ON challenges(contest_id)")
+        .execute(pool)
+        .await
+        .ok();
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_assignments_organization ON assignments(organization_id, deadline_at)")
+        .execute(pool)
+        .await
+        .ok();
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_peer_reviews_reviewer ON peer_reviews(reviewer_id, completed_at)")
+        .execute(pool)
+        .await
+        .ok();
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_battles_voter ON battles(voter_id, challenge_id)")
+        .execute(pool)
+        .await
+        .ok();
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_answers_rating ON answers(rating DESC)")
+        .execute(pool)
+        .await
+        .ok();
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_contest_votes_contest ON contest_votes(contest_id, answer_id)")
+        .execute(pool)
+        .await
+        .ok();
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_contests_ends ON contests(ends_at) WHERE finalized_at IS NULL")
+        .execute(pool)
+        .await
+        .ok();
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_editorial_picks_challenge ON editorial_picks(challenge_id, created_at DESC)")
+        .execute(pool)
+        .await
+        .ok();
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_challenge_proposals_status ON challenge_proposals(status, upvote_count DESC)")
+        .execute(pool)
+        .await
+        .ok();
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_comments_answer ON comments(answer_id)")
+        .execute(pool)
+        .await
+        .ok();
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_reactions_answer ON reactions(answer_id, reaction_type)")
+        .execute(pool)
+        .await
+        .ok();
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_xp_events_user ON xp_events(user_id, category_id)")
+        .execute(pool)
+        .await
+        .ok();
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_follows_follower ON follows(follower_id)")
+        .execute(pool)
+        .await
+        .ok();
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_follows_following ON follows(following_id)")
+        .execute(pool)
+        .await
+        .ok();
+
+    // One open report per reporter and target; resolved reports may be filed again
+    sqlx::query(
+        "CREATE UNIQUE INDEX IF NOT EXISTS idx_reports_open_unique ON reports(reporter_id, target_type, target_id) WHERE status = 'open'",
+    )
+    .execute(pool)
+    .await
+    .ok();
+
+    // NULL reporters never collide above, so system reports get their own index;
+    // duplicates filed before it existed are folded into the oldest one first
+    sqlx::query(
+        r#"
+        DELETE FROM reports r
+        USING reports keep
+        WHERE r.reporter_id IS NULL AND keep.reporter_id IS NULL
+          AND r.status = 'open' AND keep.status = 'open'
+          AND r.target_type = keep.target_type AND r.target_id = keep.target_id
+          AND (r.created_at, r.id) > (keep.created_at, keep.id)
+        "#,
+    )
+    .execute(pool)
+    .await
+    .ok();
+
+    sqlx::query(
+        "CREATE UNIQUE INDEX IF NOT EXISTS idx_reports_system_open_unique ON reports(target_type, target_id) WHERE reporter_id IS NULL AND status = 'open'",
+    )
+    .execute(pool)
+    .await
+    .ok();
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_reports_target ON reports(target_type, target_id)")
+        .execute(pool)
+        .await
+        .ok();
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_moderation_actions_target ON moderation_actions(target_type, target_id)")
+        .execute(pool)
+        .await
+        .ok();
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_user_warnings_user ON user_warnings(user_id, created_at DESC)")
+        .execute(pool)
+        .await
+        .ok();
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_bookmarks_user ON bookmarks(user_id, created_at DESC)")
+        .execute(pool)
+        .await
+        .ok();
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_collections_user ON collections(user_id)")
+        .execute(pool)
+        .await
+        .ok();
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_collection_items_collection ON collection_items(collection_id, position)")
+        .execute(pool)
+        .await
+        .ok();
+
+    info!("Migrations completed successfully");
+}
+
+/// Fills `users.search_name` for rows written before the column existed or
+/// inserted directly (e.g. by seed scripts). User search matches on this
+/// column alone, so it runs at startup before requests are served.
+pub async fn backfill_search_names(pool: &DbPool) {
+    let users: Vec<(Uuid, String)> = sqlx::query_as(
+        "SELECT id, name FROM users WHERE search_name IS NULL",
+    )
+    .fetch_all(pool)
+    .await
+    .unwrap_or_default();
+
+    for (id, name) in &users {
+        let _ = sqlx::query("UPDATE users SET search_name = $1 WHERE id = $2")
+            .bind(search_key(name))
+            .bind(id)
+            .execute(pool)
+            .await;
+    }
+
+    if !users.is_empty() {
+        info!("Backfilled search names for {} users", users.len());
+    }
 }
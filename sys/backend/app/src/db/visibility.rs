@@ -0,0 +1,88 @@
+//! Shared SQL predicates deciding which challenges and answers a viewer may see.
+
+/// Predicate limiting the challenges row `alias` to public challenges and the
+/// private challenges of organizations the viewer bound at `$viewer_param`
+/// belongs to. Contest challenges stay hidden until their contest starts.
+pub fn challenge_visible_to(alias: &str, viewer_param: usize) -> String {
+    format!(
+        r#"(
+            ({a}.organization_id IS NULL
+            OR EXISTS (
+                SELECT 1 FROM organization_members vm
+                WHERE vm.organization_id = {a}.organization_id AND vm.user_id = ${v}
+            ))
+            AND ({a}.contest_id IS NULL
+            OR EXISTS (SELECT 1 FROM contests vk WHERE vk.id = {a}.contest_id AND vk.starts_at <= NOW()))
+        )"#,
+        a = alias,
+        v = viewer_param
+    )
+}
+
+/// Predicate keeping answers to organizations' private challenges out of
+/// public timelines and rankings.
+pub fn answer_on_public_challenge(alias: &str) -> String {
+    format!(
+        "NOT EXISTS (SELECT 1 FROM challenges pc WHERE pc.id = {a}.challenge_id AND pc.organization_id IS NOT NULL)",
+        a = alias
+    )
+}
+
+/// Predicate limiting the users row `alias` to accounts whose activity the
+/// viewer bound at `$viewer_param` may see: their own, public accounts, and
+/// private accounts they are an approved follower of.
+pub fn profile_visible_to(alias: &str, viewer_param: usize) -> String {
+    format!(
+        r#"(
+            {a}.id = ${v} OR NOT {a}.is_private
+            OR EXISTS (
+                SELECT 1 FROM follows pf
+                WHERE pf.follower_id = ${v} AND pf.following_id = {a}.id AND pf.status = 'accepted'
+            )
+        )"#,
+        a = alias,
+        v = viewer_param
+    )
+}
+
+/// Predicate limiting the answers row `alias` to what the viewer bound at
+/// `$viewer_param` may see: all of their own attempts, plus other users'
+/// published attempts that are public from public accounts or non-private
+/// from accounts they are an approved follower of. Answers to a private
+/// challenge are only shown to members of its organization, and contest
+/// entries only once the contest stops taking entries. Bind `None` for
+/// anonymous viewers.
+pub fn answer_visible_to(alias: &str, viewer_param: usize) -> String {
+    format!(
+        r#"(
+            {a}.user_id = ${v}
+            OR ({a}.is_published AND EXISTS (
+                SELECT 1 FROM challenges vc WHERE vc.id = {a}.challenge_id AND {challenge}
+                  AND NOT EXISTS (
+                      SELECT 1 FROM contests ve WHERE ve.id = vc.contest_id AND ve.entries_close_at > NOW()
+                  )
+            ) AND (
+                ({a}.visibility = 'public' AND NOT EXISTS (SELECT 1 FROM users vu WHERE vu.id = {a}.user_id AND vu.is_private))
+                OR ({a}.visibility <> 'private' AND EXISTS (
+                    SELECT 1 FROM follows vf
+                    WHERE vf.follower_id = ${v} AND vf.following_id = {a}.user_id AND vf.status = 'accepted'
+                ))
+            ))
+        )"#,
+        a = alias,
+        v = viewer_param,
+        challenge = challenge_visible_to("vc", viewer_param)
+    )
+}
+
+/// Like [`answer_visible_to`], but for timelines and rankings, which only
+/// include published, non-private attempts to public challenges, even for
+/// the viewer's own.
+pub fn answer_listed_for(alias: &str, viewer_param: usize) -> String {
+    format!(
+        "({a}.is_published AND {a}.visibility <> 'private' AND {} AND {})",
+        answer_on_public_challenge(alias),
+        answer_visible_to(alias, viewer_param),
+        a = alias
+    )
+}
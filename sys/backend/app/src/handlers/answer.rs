@@ -2,7 +2,23 @@ use actix_web::{web, HttpRequest, HttpResponse};
 use sqlx::PgPool;
 use uuid::Uuid;
 
+use crate::auth;
 use crate::config::Config;
+use crate::content_filter::{ContentKind, FilterPipeline, Verdict};
+use crate::db::visibility::{answer_visible_to, challenge_visible_to};
+use crate::handlers::attempt::record_revision;
+use crate::handlers::badge::{self, BadgeEvent};
+use crate::handlers::bookmark::is_bookmarked;
+use crate::handlers::contest;
+use crate::handlers::course::refresh_course_progress;
+use crate::handlers::difficulty::hard_mode_char_limit;
+use crate::handlers::editorial::apply_curated_examples;
+use crate::handlers::moderation;
+use crate::handlers::peer_review::assign_peer_reviews;
+use crate::handlers::reaction::{reaction_summary, LIKE_REACTION};
+use crate::handlers::session;
+use crate::handlers::streak;
+use crate::handlers::xp::{record_xp, XpEvent};
 use crate::models::{
     Answer, AnswerQueryParams, AnswerWithDetails, AnswerWithUser, Challenge,
     CreateAnswerRequest, UpdateAnswerRequest, User, UserSummary,
@@ -32,10 +48,12 @@ pub async fn get_challenge_answers(
         _ => "a.created_at DESC",
     };
 
-    let total: (i64,) = sqlx::query_as(
-        r#"SELECT COUNT(*) FROM answers WHERE challenge_id = $1 AND status = 'active'"#,
-    )
+    let total: (i64,) = sqlx::query_as(&format!(
+        r#"SELECT COUNT(*) FROM answers a WHERE a.challenge_id = $1 AND a.status = 'active' AND {}"#,
+        answer_visible_to("a", 2)
+    ))
     .bind(challenge_id)
+    .bind(current_user_id)
     .fetch_one(pool.get_ref())
     .await
     .unwrap_or((0,));
@@ -43,15 +61,17 @@ pub async fn get_challenge_answers(
     let answers = sqlx::query_as::<_, Answer>(&format!(
         r#"
         SELECT a.* FROM answers a
-        WHERE a.challenge_id = $1 AND a.status = 'active'
+        WHERE a.challenge_id = $1 AND a.status = 'active' AND {}
         ORDER BY {}
         LIMIT $2 OFFSET $3
         "#,
+        answer_visible_to("a", 4),
         order_by
     ))
     .bind(challenge_id)
     .bind(page_size)
     .bind(offset)
+    .bind(current_user_id)
     .fetch_all(pool.get_ref())
     .await;
 
@@ -63,7 +83,7 @@ pub async fn get_challenge_answers(
         }
     };
 
-    // Fetch users and likes for each answer
+    // Fetch users and reactions for each answer
     let mut results: Vec<AnswerWithUser> = Vec::new();
     for answer in answers {
         let user = sqlx::query_as::<_, User>(
@@ -74,18 +94,10 @@ pub async fn get_challenge_answers(
         .await
         .ok();
 
-        let is_liked = if let Some(uid) = current_user_id {
-            sqlx::query_scalar::<_, bool>(
-                r#"SELECT EXISTS(SELECT 1 FROM likes WHERE answer_id = $1 AND user_id = $2)"#,
-            )
-            .bind(answer.id)
-            .bind(uid)
-            .fetch_one(pool.get_ref())
-            .await
-            .unwrap_or(false)
-        } else {
-            false
-        };
+        let (reactions, my_reactions) = reaction_summary(pool.get_ref(), answer.id, current_user_id).await;
+        let is_liked = my_reactions.iter().any(|r| r == LIKE_REACTION);
+
+        let is_bookmarked = is_bookmarked(pool.get_ref(), answer.id, current_user_id).await;
 
         if let Some(user) = user {
             results.push(AnswerWithUser {
@@ -96,6 +108,9 @@ pub async fn get_challenge_answers(
                     avatar: user.avatar,
                 },
                 is_liked,
+                is_bookmarked,
+                reactions,
+                my_reactions,
             });
         }
     }
@@ -119,14 +134,17 @@ pub async fn get_answer(
     .execute(pool.get_ref())
     .await;
 
-    let answer = sqlx::query_as::<_, Answer>(
-        r#"SELECT * FROM answers WHERE id = $1 AND status = 'active'"#,
-    )
+    // Answers the viewer may not see are reported as not found
+    let answer = sqlx::query_as::<_, Answer>(&format!(
+        r#"SELECT * FROM answers WHERE id = $1 AND status = 'active' AND {}"#,
+        answer_visible_to("answers", 2)
+    ))
     .bind(answer_id)
+    .bind(current_user_id)
     .fetch_optional(pool.get_ref())
     .await;
 
-    let answer = match answer {
+    let mut answer = match answer {
         Ok(Some(a)) => a,
         Ok(None) => return utils::not_found("Answer not found"),
         Err(e) => {
@@ -134,6 +152,7 @@ pub async fn get_answer(
             return utils::internal_error("Failed to fetch answer");
         }
     };
+    apply_curated_examples(pool.get_ref(), std::slice::from_mut(&mut answer)).await;
 
     let user = sqlx::query_as::<_, User>(
         r#"SELECT * FROM users WHERE id = $1"#,
@@ -149,18 +168,10 @@ pub async fn get_answer(
     .fetch_one(pool.get_ref())
     .await;
 
-    let is_liked = if let Some(uid) = current_user_id {
-        sqlx::query_scalar::<_, bool>(
-            r#"SELECT EXISTS(SELECT 1 FROM likes WHERE answer_id = $1 AND user_id = $2)"#,
-        )
-        .bind(answer_id)
-        .bind(uid)
-        .fetch_one(pool.get_ref())
-        .await
-        .unwrap_or(false)
-    } else {
-        false
-    };
+    let (reactions, my_reactions) = reaction_summary(pool.get_ref(), answer_id, current_user_id).await;
+    let is_liked = my_reactions.iter().any(|r| r == LIKE_REACTION);
+
+    let is_bookmarked = is_bookmarked(pool.get_ref(), answer_id, current_user_id).await;
 
     match (user, challenge) {
         (Ok(user), Ok(challenge)) => utils::success(AnswerWithDetails {
@@ -172,6 +183,9 @@ pub async fn get_answer(
             },
             challenge,
             is_liked,
+            is_bookmarked,
+            reactions,
+            my_reactions,
         }),
         _ => utils::internal_error("Failed to fetch answer details"),
     }
@@ -179,6 +193,8 @@ pub async fn get_answer(
 
 pub async fn create_answer(
     pool: web::Data<PgPool>,
+    config: web::Data<Config>,
+    content_filter: web::Data<FilterPipeline>,
     req: HttpRequest,
     path: web::Path<Uuid>,
     body: web::Json<CreateAnswerRequest>,
@@ -189,12 +205,49 @@ pub async fn create_answer(
         None => return utils::unauthorized("User ID required"),
     };
 
-    // Check challenge exists and get char limit
-    let challenge = sqlx::query_as::<_, Challenge>(
-        r#"SELECT * FROM challenges WHERE id = $1 AND status = 'active'"#,
-    )
+    let submission = AnswerSubmission {
+        content: &body.content,
+        visibility: body.visibility.as_deref(),
+        hard_mode: body.hard_mode.unwrap_or(false),
+        session_id: body.session_id,
+    };
+    submit_answer(pool.get_ref(), &content_filter, &config, user_id, challenge_id, submission).await
+}
+
+/// What the user is submitting for a new attempt.
+pub struct AnswerSubmission<'a> {
+    pub content: &'a str,
+    pub visibility: Option<&'a str>,
+    /// Answer under the challenge's tighter hard-mode char limit
+    pub hard_mode: bool,
+    /// Timed session the answer was written in
+    pub session_id: Option<Uuid>,
+}
+
+/// Runs a new attempt through the char limit and content filter and stores it.
+/// Shared by direct submissions and draft promotion.
+pub async fn submit_answer(
+    pool: &PgPool,
+    content_filter: &FilterPipeline,
+    config: &Config,
+    user_id: Uuid,
+    challenge_id: Uuid,
+    submission: AnswerSubmission<'_>,
+) -> HttpResponse {
+    let content = submission.content;
+
+    if auth::is_suspended(pool, user_id).await {
+        return utils::forbidden("Your account is suspended");
+    }
+
+    // Check challenge exists (and is open to the user) and get char limit
+    let challenge = sqlx::query_as::<_, Challenge>(&format!(
+        r#"SELECT * FROM challenges WHERE id = $1 AND status = 'active' AND {}"#,
+        challenge_visible_to("challenges", 2)
+    ))
     .bind(challenge_id)
-    .fetch_optional(pool.get_ref())
+    .bind(user_id)
+    .fetch_optional(pool)
     .await;
 
     let challenge = match challenge {
@@ -206,61 +259,125 @@ pub async fn create_answer(
         }
     };
 
+    if let Err(resp) = contest::ensure_entries_open(pool, challenge_id).await {
+        return resp;
+    }
+
     // Validate content length
-    if body.content.chars().count() > challenge.char_limit as usize {
+    let char_limit = if submission.hard_mode {
+        hard_mode_char_limit(&challenge)
+    } else {
+        challenge.char_limit
+    };
+    if content.chars().count() > char_limit as usize {
         return utils::bad_request(&format!(
             "Content exceeds {} character limit",
-            challenge.char_limit
+            char_limit
         ));
     }
 
-    // Check if user already answered this challenge
-    let existing = sqlx::query_scalar::<_, bool>(
-        r#"SELECT EXISTS(SELECT 1 FROM answers WHERE challenge_id = $1 AND user_id = $2 AND status = 'active')"#,
-    )
-    .bind(challenge_id)
-    .bind(user_id)
-    .fetch_one(pool.get_ref())
-    .await
-    .unwrap_or(false);
+    let visibility = submission.visibility.unwrap_or("public");
+    if !ANSWER_VISIBILITIES.contains(&visibility) {
+        return utils::bad_request("visibility must be public, followers or private");
+    }
 
-    if existing {
-        return utils::conflict("You have already answered this challenge");
+    let recent_posts = content_filter.recent_posts(pool, user_id, None).await;
+    let outcome = content_filter.check(ContentKind::Answer, content, &recent_posts);
+    if outcome.verdict == Verdict::Reject {
+        return utils::unprocessable("Content rejected by filter", outcome);
     }
+    // Held answers stay invisible until a moderator approves them
+    let status = if outcome.verdict == Verdict::Hold { "pending" } else { "active" };
 
-    // Create answer
+    // The timed session is only consumed if the attempt is stored
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => {
+            tracing::error!("Failed to begin transaction: {}", e);
+            return utils::internal_error("Failed to create answer");
+        }
+    };
+
+    let timed = match submission.session_id {
+        Some(session_id) => {
+            match session::claim_session(&mut tx, &config.timed, session_id, user_id, challenge_id).await {
+                Ok(t) => Some(t),
+                Err(resp) => return resp,
+            }
+        }
+        None => None,
+    };
+
+    // Each submission is a new attempt; the first one (or the first after the
+    // published attempt was deleted) becomes the published attempt
     let result = sqlx::query_as::<_, Answer>(
         r#"
-        INSERT INTO answers (challenge_id, user_id, content)
-        VALUES ($1, $2, $3)
+        INSERT INTO answers
+            (challenge_id, user_id, content, status, visibility, hard_mode, session_id, elapsed_ms, is_late, attempt_number, is_published)
+        SELECT $1, $2, $3, $4, $5, $6, $7, $8, $9,
+            COALESCE(MAX(attempt_number), 0) + 1,
+            NOT EXISTS (
+                SELECT 1 FROM answers
+                WHERE challenge_id = $1 AND user_id = $2 AND is_published AND status <> 'deleted'
+            )
+        FROM answers
+        WHERE challenge_id = $1 AND user_id = $2
         RETURNING *
         "#,
     )
     .bind(challenge_id)
     .bind(user_id)
-    .bind(&body.content)
-    .fetch_one(pool.get_ref())
+    .bind(content)
+    .bind(status)
+    .bind(visibility)
+    .bind(submission.hard_mode)
+    .bind(submission.session_id)
+    .bind(timed.as_ref().map(|t| t.elapsed_ms))
+    .bind(timed.as_ref().is_some_and(|t| t.is_late))
+    .fetch_one(&mut *tx)
     .await;
 
-    // Update challenge answer count
-    let _ = sqlx::query(
-        r#"UPDATE challenges SET answer_count = answer_count + 1 WHERE id = $1"#,
-    )
-    .bind(challenge_id)
-    .execute(pool.get_ref())
-    .await;
+    let result = match result {
+        Ok(answer) => tx.commit().await.map(|_| answer),
+        Err(e) => Err(e),
+    };
 
     match result {
-        Ok(answer) => utils::created(answer),
+        Ok(answer) => {
+            record_revision(pool, &answer).await;
+            if counts_toward_total(&answer.status, &answer.visibility, answer.is_published) {
+                // Update challenge answer count
+                adjust_answer_count(pool, challenge_id, 1).await;
+            }
+            if answer.status == "pending" {
+                moderation::file_system_report(pool, "answer", answer.id, &outcome.describe()).await;
+            }
+            record_xp(pool, answer.id, XpEvent::Answer).await;
+            streak::record_activity(pool, &config.streak, user_id).await;
+            badge::evaluate_badges(pool, user_id, BadgeEvent::AnswerSubmitted).await;
+            refresh_course_progress(pool, user_id).await;
+            assign_peer_reviews(pool, &config.peer_review, user_id, challenge_id).await;
+            utils::created(answer)
+        }
         Err(e) => {
             tracing::error!("Failed to create answer: {}", e);
-            utils::internal_error("Failed to create answer")
+            // Concurrent submissions can compute the same attempt_number
+            let unique_violation = e
+                .as_database_error()
+                .and_then(|db_err| db_err.code())
+                .is_some_and(|code| code == "23505");
+            if unique_violation {
+                utils::conflict("Another attempt was submitted at the same time")
+            } else {
+                utils::internal_error("Failed to create answer")
+            }
         }
     }
 }
 
 pub async fn update_answer(
     pool: web::Data<PgPool>,
+    content_filter: web::Data<FilterPipeline>,
     req: HttpRequest,
     path: web::Path<Uuid>,
     body: web::Json<UpdateAnswerRequest>,
@@ -273,7 +390,7 @@ pub async fn update_answer(
 
     // Check ownership
     let answer = sqlx::query_as::<_, Answer>(
-        r#"SELECT * FROM answers WHERE id = $1 AND status = 'active'"#,
+        r#"SELECT * FROM answers WHERE id = $1 AND status IN ('active', 'pending')"#,
     )
     .bind(answer_id)
     .fetch_optional(pool.get_ref())
@@ -292,23 +409,99 @@ pub async fn update_answer(
         return utils::forbidden("You can only update your own answers");
     }
 
+    if auth::is_suspended(pool.get_ref(), user_id).await {
+        return utils::forbidden("Your account is suspended");
+    }
+
+    // Contest entries are frozen once entries close
+    if let Err(resp) = contest::ensure_entries_open(pool.get_ref(), answer.challenge_id).await {
+        return resp;
+    }
+
     let content = body.content.as_ref().unwrap_or(&answer.content);
+    let visibility = body.visibility.as_deref().unwrap_or(&answer.visibility);
+    if !ANSWER_VISIBILITIES.contains(&visibility) {
+        return utils::bad_request("visibility must be public, followers or private");
+    }
 
+    // Edits are held to the limit the answer was submitted under, hard mode included
+    if body.content.is_some() {
+        let challenge = sqlx::query_as::<_, Challenge>(r#"SELECT * FROM challenges WHERE id = $1"#)
+            .bind(answer.challenge_id)
+            .fetch_one(pool.get_ref())
+            .await;
+
+        let challenge = match challenge {
+            Ok(c) => c,
+            Err(e) => {
+                tracing::error!("Failed to fetch challenge: {}", e);
+                return utils::internal_error("Failed to update answer");
+            }
+        };
+
+        let char_limit = if answer.hard_mode {
+            hard_mode_char_limit(&challenge)
+        } else {
+            challenge.char_limit
+        };
+        if content.chars().count() > char_limit as usize {
+            return utils::bad_request(&format!(
+                "Content exceeds {} character limit",
+                char_limit
+            ));
+        }
+    }
+
+    let mut outcome = None;
+    if body.content.is_some() {
+        let recent_posts = content_filter.recent_posts(pool.get_ref(), user_id, Some(answer_id)).await;
+        let checked = content_filter.check(ContentKind::Answer, content, &recent_posts);
+        if checked.verdict == Verdict::Reject {
+            return utils::unprocessable("Content rejected by filter", checked);
+        }
+        outcome = Some(checked);
+    }
+    let held = matches!(&outcome, Some(o) if o.verdict == Verdict::Hold);
+    let status = if held { "pending" } else { answer.status.as_str() };
+
+    // New wording needs a new score, so feedback is cleared when the content changes
     let result = sqlx::query_as::<_, Answer>(
         r#"
         UPDATE answers
-        SET content = $1, updated_at = NOW()
-        WHERE id = $2
+        SET content = $1, status = $2, visibility = $3,
+            score = CASE WHEN content = $1 THEN score END,
+            ai_feedback = CASE WHEN content = $1 THEN ai_feedback END,
+            updated_at = NOW()
+        WHERE id = $4
         RETURNING *
         "#,
     )
     .bind(content)
+    .bind(status)
+    .bind(visibility)
     .bind(answer_id)
     .fetch_one(pool.get_ref())
     .await;
 
     match result {
-        Ok(answer) => utils::success(answer),
+        Ok(updated) => {
+            // Holding or changing visibility can move the answer in or out of the count
+            if updated.content != answer.content {
+                record_revision(pool.get_ref(), &updated).await;
+            }
+            if answer.score.is_some() && updated.score.is_none() {
+                record_xp(pool.get_ref(), answer_id, XpEvent::Unscore).await;
+            }
+            let was_counted = counts_toward_total(&answer.status, &answer.visibility, answer.is_published);
+            let is_counted = counts_toward_total(&updated.status, &updated.visibility, updated.is_published);
+            if was_counted != is_counted {
+                adjust_answer_count(pool.get_ref(), answer.challenge_id, if is_counted { 1 } else { -1 }).await;
+            }
+            if let (true, Some(outcome)) = (held, &outcome) {
+                moderation::file_system_report(pool.get_ref(), "answer", answer_id, &outcome.describe()).await;
+            }
+            utils::success(updated)
+        }
         Err(e) => {
             tracing::error!("Failed to update answer: {}", e);
             utils::internal_error("Failed to update answer")
@@ -329,7 +522,7 @@ pub async fn delete_answer(
 
     // Check ownership
     let answer = sqlx::query_as::<_, Answer>(
-        r#"SELECT * FROM answers WHERE id = $1 AND status = 'active'"#,
+        r#"SELECT * FROM answers WHERE id = $1 AND status IN ('active', 'pending')"#,
     )
     .bind(answer_id)
     .fetch_optional(pool.get_ref())
@@ -356,13 +549,10 @@ pub async fn delete_answer(
     .execute(pool.get_ref())
     .await;
 
-    // Update challenge answer count
-    let _ = sqlx::query(
-        r#"UPDATE challenges SET answer_count = answer_count - 1 WHERE id = $1"#,
-    )
-    .bind(answer.challenge_id)
-    .execute(pool.get_ref())
-    .await;
+    // Update challenge answer count (held, private and unpublished answers were never counted)
+    if counts_toward_total(&answer.status, &answer.visibility, answer.is_published) {
+        adjust_answer_count(pool.get_ref(), answer.challenge_id, -1).await;
+    }
 
     match result {
         Ok(_) => utils::no_content(),
@@ -372,3 +562,72 @@ pub async fn delete_answer(
         }
     }
 }
+
+/// Values accepted for `answers.visibility`.
+const ANSWER_VISIBILITIES: &[&str] = &["public", "followers", "private"];
+
+/// Whether an answer is included in `challenges.answer_count`: active, published
+/// attempts except private practice ones.
+pub fn counts_toward_total(status: &str, visibility: &str, is_published: bool) -> bool {
+    status == "active" && visibility != "private" && is_published
+}
+
+pub async fn adjust_answer_count(pool: &PgPool, challenge_id: Uuid, delta: i32) {
+    let _ = sqlx::query(
+        r#"UPDATE challenges SET answer_count = GREATEST(0, answer_count + $1) WHERE id = $2"#,
+    )
+    .bind(delta)
+    .bind(challenge_id)
+    .execute(pool)
+    .await;
+}
+
+/// Attaches author, challenge, reaction counts and the viewer's reaction/bookmark
+/// state to each answer.
+/// Answers whose author or challenge can no longer be loaded are skipped.
+pub async fn answers_with_details(
+    pool: &PgPool,
+    mut answers: Vec<Answer>,
+    current_user_id: Option<Uuid>,
+) -> Vec<AnswerWithDetails> {
+    apply_curated_examples(pool, &mut answers).await;
+
+    let mut results: Vec<AnswerWithDetails> = Vec::new();
+    for answer in answers {
+        let user = sqlx::query_as::<_, User>(
+            r#"SELECT * FROM users WHERE id = $1"#,
+        )
+        .bind(answer.user_id)
+        .fetch_one(pool)
+        .await;
+
+        let challenge = sqlx::query_as::<_, Challenge>(
+            r#"SELECT * FROM challenges WHERE id = $1"#,
+        )
+        .bind(answer.challenge_id)
+        .fetch_one(pool)
+        .await;
+
+        let (reactions, my_reactions) = reaction_summary(pool, answer.id, current_user_id).await;
+        let is_liked = my_reactions.iter().any(|r| r == LIKE_REACTION);
+
+        let is_bookmarked = is_bookmarked(pool, answer.id, current_user_id).await;
+
+        if let (Ok(user), Ok(challenge)) = (user, challenge) {
+            results.push(AnswerWithDetails {
+                answer,
+                user: UserSummary {
+                    id: user.id,
+                    name: user.name,
+                    avatar: user.avatar,
+                },
+                challenge,
+                is_liked,
+                is_bookmarked,
+                reactions,
+                my_reactions,
+            });
+        }
+    }
+    results
+}
@@ -0,0 +1,295 @@
+use actix_web::{web, HttpRequest, HttpResponse};
+use sqlx::PgPool;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::auth;
+use crate::handlers::badge::{evaluate_badges, BadgeEvent};
+use crate::handlers::contest;
+use crate::handlers::course::refresh_course_progress;
+use crate::handlers::difficulty::refresh_derived_difficulty;
+use crate::handlers::editorial::apply_curated_examples;
+use crate::handlers::xp::{record_xp, XpEvent};
+use crate::handlers::answer::{adjust_answer_count, counts_toward_total};
+use crate::models::{AiFeedback, Answer, AnswerRevision};
+use crate::utils;
+
+pub async fn get_challenge_attempts(
+    pool: web::Data<PgPool>,
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+) -> HttpResponse {
+    let challenge_id = path.into_inner();
+    let user_id = match utils::get_user_id(&req) {
+        Some(id) => id,
+        None => return utils::unauthorized("User ID required"),
+    };
+
+    // The caller's own attempts, including ones still held for review
+    let result = sqlx::query_as::<_, Answer>(
+        r#"
+        SELECT * FROM answers
+        WHERE challenge_id = $1 AND user_id = $2 AND status IN ('active', 'pending', 'hidden')
+        ORDER BY attempt_number ASC
+        "#,
+    )
+    .bind(challenge_id)
+    .bind(user_id)
+    .fetch_all(pool.get_ref())
+    .await;
+
+    match result {
+        Ok(mut attempts) => {
+            apply_curated_examples(pool.get_ref(), &mut attempts).await;
+            utils::success(attempts)
+        }
+        Err(e) => {
+            tracing::error!("Failed to fetch attempts: {}", e);
+            utils::internal_error("Failed to fetch attempts")
+        }
+    }
+}
+
+pub async fn publish_answer(
+    pool: web::Data<PgPool>,
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+) -> HttpResponse {
+    let answer_id = path.into_inner();
+    let user_id = match utils::get_user_id(&req) {
+        Some(id) => id,
+        None => return utils::unauthorized("User ID required"),
+    };
+
+    let answer = match fetch_own_answer(pool.get_ref(), answer_id, user_id).await {
+        Ok(a) => a,
+        Err(resp) => return resp,
+    };
+
+    if answer.is_published {
+        return utils::success(answer);
+    }
+    // Hidden and held attempts stay out of view until a moderator restores them
+    if answer.status != "active" {
+        return utils::conflict("Only active attempts can be published");
+    }
+
+    if let Err(resp) = contest::ensure_entries_open(pool.get_ref(), answer.challenge_id).await {
+        return resp;
+    }
+
+    // Unpublish the current attempt first so the one-published-attempt index holds
+    let result: Result<Option<(Option<Answer>, Answer)>, sqlx::Error> = async {
+        let mut tx = pool.begin().await?;
+
+        let previous = sqlx::query_as::<_, Answer>(
+            r#"
+            UPDATE answers SET is_published = FALSE, updated_at = NOW()
+            WHERE challenge_id = $1 AND user_id = $2 AND is_published AND status <> 'deleted'
+            RETURNING *
+            "#,
+        )
+        .bind(answer.challenge_id)
+        .bind(user_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let published = sqlx::query_as::<_, Answer>(
+            r#"UPDATE answers SET is_published = TRUE, updated_at = NOW() WHERE id = $1 AND status = 'active' RETURNING *"#,
+        )
+        .bind(answer_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        // Hidden in the meantime: dropping the transaction keeps the old attempt published
+        let published = match published {
+            Some(p) => p,
+            None => return Ok(None),
+        };
+
+        tx.commit().await?;
+        Ok(Some((previous, published)))
+    }
+    .await;
+
+    match result {
+        Ok(None) => utils::conflict("Only active attempts can be published"),
+        Ok(Some((previous, published))) => {
+            if let Some(prev) = previous {
+                if counts_toward_total(&prev.status, &prev.visibility, true) {
+                    adjust_answer_count(pool.get_ref(), prev.challenge_id, -1).await;
+                }
+            }
+            if counts_toward_total(&published.status, &published.visibility, true) {
+                adjust_answer_count(pool.get_ref(), published.challenge_id, 1).await;
+            }
+            utils::success(published)
+        }
+        Err(e) => {
+            tracing::error!("Failed to publish answer: {}", e);
+            utils::internal_error("Failed to publish answer")
+        }
+    }
+}
+
+pub async fn get_answer_revisions(
+    pool: web::Data<PgPool>,
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+) -> HttpResponse {
+    let answer_id = path.into_inner();
+    let user_id = match utils::get_user_id(&req) {
+        Some(id) => id,
+        None => return utils::unauthorized("User ID required"),
+    };
+
+    if let Err(resp) = fetch_own_answer(pool.get_ref(), answer_id, user_id).await {
+        return resp;
+    }
+
+    let result = sqlx::query_as::<_, AnswerRevision>(
+        r#"
+        SELECT * FROM answer_revisions
+        WHERE answer_id = $1
+        ORDER BY revision_number ASC
+        "#,
+    )
+    .bind(answer_id)
+    .fetch_all(pool.get_ref())
+    .await;
+
+    match result {
+        Ok(revisions) => utils::success(revisions),
+        Err(e) => {
+            tracing::error!("Failed to fetch answer revisions: {}", e);
+            utils::internal_error("Failed to fetch answer revisions")
+        }
+    }
+}
+
+/// Stores the AI score and feedback for an attempt's current wording.
+/// Called by the scoring integration, which runs as an admin account.
+pub async fn update_answer_feedback(
+    pool: web::Data<PgPool>,
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+    body: web::Json<AiFeedback>,
+) -> HttpResponse {
+    let answer_id = path.into_inner();
+    let user_id = match utils::get_user_id(&req) {
+        Some(id) => id,
+        None => return utils::unauthorized("User ID required"),
+    };
+    if let Err(resp) = auth::require_role(pool.get_ref(), user_id, auth::ADMIN_ROLES).await {
+        return resp;
+    }
+
+    if let Err(e) = body.validate() {
+        return utils::bad_request(&e.to_string());
+    }
+
+    let feedback = match serde_json::to_value(&*body) {
+        Ok(v) => v,
+        Err(e) => {
+            tracing::error!("Failed to serialize feedback: {}", e);
+            return utils::internal_error("Failed to store feedback");
+        }
+    };
+
+    let result = sqlx::query_as::<_, Answer>(
+        r#"
+        UPDATE answers SET score = $1, ai_feedback = $2, updated_at = NOW()
+        WHERE id = $3 AND status <> 'deleted'
+        RETURNING *
+        "#,
+    )
+    .bind(body.score)
+    .bind(&feedback)
+    .bind(answer_id)
+    .fetch_optional(pool.get_ref())
+    .await;
+
+    match result {
+        Ok(Some(answer)) => {
+            // The score belongs to the latest wording of the attempt
+            let _ = sqlx::query(
+                r#"
+                UPDATE answer_revisions SET score = $1, ai_feedback = $2
+                WHERE id = (
+                    SELECT id FROM answer_revisions
+                    WHERE answer_id = $3
+                    ORDER BY revision_number DESC
+                    LIMIT 1
+                )
+                "#,
+            )
+            .bind(body.score)
+            .bind(&feedback)
+            .bind(answer_id)
+            .execute(pool.get_ref())
+            .await;
+
+            record_xp(pool.get_ref(), answer.id, XpEvent::Score(body.score)).await;
+            evaluate_badges(pool.get_ref(), answer.user_id, BadgeEvent::AnswerScored).await;
+            refresh_derived_difficulty(pool.get_ref(), Some(answer.challenge_id)).await;
+            refresh_course_progress(pool.get_ref(), answer.user_id).await;
+            utils::success(answer)
+        }
+        Ok(None) => utils::not_found("Answer not found"),
+        Err(e) => {
+            tracing::error!("Failed to store feedback: {}", e);
+            utils::internal_error("Failed to store feedback")
+        }
+    }
+}
+
+/// Appends the answer's current wording and score to its revision history.
+pub async fn record_revision(pool: &PgPool, answer: &Answer) {
+    let result = sqlx::query(
+        r#"
+        INSERT INTO answer_revisions
+            (answer_id, challenge_id, user_id, attempt_number, revision_number, content, score, ai_feedback)
+        SELECT $1, $2, $3, $4, COALESCE(MAX(revision_number), 0) + 1, $5, $6, $7
+        FROM answer_revisions
+        WHERE answer_id = $1
+        "#,
+    )
+    .bind(answer.id)
+    .bind(answer.challenge_id)
+    .bind(answer.user_id)
+    .bind(answer.attempt_number)
+    .bind(&answer.content)
+    .bind(answer.score)
+    .bind(&answer.ai_feedback)
+    .execute(pool)
+    .await;
+
+    if let Err(e) = result {
+        tracing::error!("Failed to record answer revision: {}", e);
+    }
+}
+
+/// Loads one of the caller's own attempts. Other users' attempts are
+/// reported as not found so unpublished work stays private.
+async fn fetch_own_answer(
+    pool: &PgPool,
+    answer_id: Uuid,
+    user_id: Uuid,
+) -> Result<Answer, HttpResponse> {
+    let answer = sqlx::query_as::<_, Answer>(
+        r#"SELECT * FROM answers WHERE id = $1 AND user_id = $2 AND status IN ('active', 'pending', 'hidden')"#,
+    )
+    .bind(answer_id)
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await;
+
+    match answer {
+        Ok(Some(a)) => Ok(a),
+        Ok(None) => Err(utils::not_found("Answer not found")),
+        Err(e) => {
+            tracing::error!("Failed to fetch answer: {}", e);
+            Err(utils::internal_error("Failed to fetch answer"))
+        }
+    }
+}
@@ -0,0 +1,160 @@
+use actix_web::{web, HttpResponse};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::models::{AwardedBadge, Badge};
+use crate::utils;
+
+/// Things that happen to a user that can complete a badge rule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BadgeEvent {
+    AnswerSubmitted,
+    LikeReceived,
+    AnswerScored,
+    ContestFinalized,
+}
+
+pub async fn list_badges(pool: web::Data<PgPool>) -> HttpResponse {
+    match active_badges(pool.get_ref()).await {
+        Ok(badges) => utils::success(badges),
+        Err(e) => {
+            tracing::error!("Failed to fetch badges: {}", e);
+            utils::internal_error("Failed to fetch badges")
+        }
+    }
+}
+
+/// Badges the user has earned, in catalog order.
+pub async fn awarded_badges(pool: &PgPool, user_id: Uuid) -> Vec<AwardedBadge> {
+    sqlx::query_as::<_, AwardedBadge>(
+        r#"
+        SELECT b.key, b.name, b.description, b.icon, ub.awarded_at
+        FROM user_badges ub
+        JOIN badges b ON b.key = ub.badge_key
+        WHERE ub.user_id = $1
+        ORDER BY b.sort_order ASC, ub.awarded_at ASC
+        "#,
+    )
+    .bind(user_id)
+    .fetch_all(pool)
+    .await
+    .unwrap_or_default()
+}
+
+/// Re-checks the badges whose rules the event can affect and awards any the
+/// user now qualifies for. Already-awarded badges are left untouched.
+pub async fn evaluate_badges(pool: &PgPool, user_id: Uuid, event: BadgeEvent) {
+    let badges = match active_badges(pool).await {
+        Ok(b) => b,
+        Err(e) => {
+            tracing::error!("Failed to fetch badges: {}", e);
+            return;
+        }
+    };
+
+    for badge in badges {
+        let (metric, events) = match rule_metric(&badge.rule) {
+            Some(rule) => rule,
+            None => continue,
+        };
+        if events.contains(&event) {
+            award_badge(pool, &badge, metric, Some(user_id)).await;
+        }
+    }
+}
+
+/// Awards every badge to all users who already qualify, so rules added later
+/// also cover historical activity. Runs at startup.
+pub async fn backfill_badges(pool: &PgPool) {
+    let badges = match active_badges(pool).await {
+        Ok(b) => b,
+        Err(e) => {
+            tracing::error!("Failed to fetch badges: {}", e);
+            return;
+        }
+    };
+
+    for badge in badges {
+        match rule_metric(&badge.rule) {
+            Some((metric, _)) => award_badge(pool, &badge, metric, None).await,
+            None => tracing::warn!("Badge {} has unknown rule {}", badge.key, badge.rule),
+        }
+    }
+}
+
+/// SQL expression computing a rule's metric for the user aliased `u`, and the
+/// events that can change it.
+fn rule_metric(rule: &str) -> Option<(&'static str, &'static [BadgeEvent])> {
+    match rule {
+        "answer_count" => Some((
+            "(SELECT COUNT(*) FROM answers a WHERE a.user_id = u.id AND a.status <> 'deleted')",
+            &[BadgeEvent::AnswerSubmitted],
+        )),
+        "longest_streak" => Some((
+            "COALESCE((SELECT s.longest_streak FROM user_streaks s WHERE s.user_id = u.id), 0)",
+            &[BadgeEvent::AnswerSubmitted],
+        )),
+        "total_likes" => Some(("u.total_likes", &[BadgeEvent::LikeReceived])),
+        // 1 once the user has answered in every active category
+        "all_categories" => Some((
+            r#"
+            CASE WHEN NOT EXISTS (
+                SELECT 1 FROM categories c
+                WHERE c.status = 'active' AND NOT EXISTS (
+                    SELECT 1 FROM answers a
+                    JOIN challenges ch ON ch.id = a.challenge_id
+                    WHERE a.user_id = u.id AND a.status <> 'deleted' AND ch.category_id = c.id
+                )
+            ) THEN 1 ELSE 0 END
+            "#,
+            &[BadgeEvent::AnswerSubmitted],
+        )),
+        "max_score" => Some((
+            "COALESCE((SELECT MAX(a.score) FROM answers a WHERE a.user_id = u.id AND a.status <> 'deleted'), 0)",
+            &[BadgeEvent::AnswerScored],
+        )),
+        "contest_wins" => Some((
+            "(SELECT COUNT(*) FROM contest_standings cs WHERE cs.user_id = u.id AND cs.rank = 1)",
+            &[BadgeEvent::ContestFinalized],
+        )),
+        "contest_podiums" => Some((
+            "(SELECT COUNT(*) FROM contest_standings cs WHERE cs.user_id = u.id AND cs.rank <= 3)",
+            &[BadgeEvent::ContestFinalized],
+        )),
+        _ => None,
+    }
+}
+
+/// Awards the badge to the given user, or to every qualifying user when `user_id` is None.
+async fn award_badge(pool: &PgPool, badge: &Badge, metric: &str, user_id: Option<Uuid>) {
+    let result = sqlx::query(&format!(
+        r#"
+        INSERT INTO user_badges (user_id, badge_key)
+        SELECT u.id, $1 FROM users u
+        WHERE u.status = 'active' AND ($2::uuid IS NULL OR u.id = $2) AND {} >= $3
+        ON CONFLICT (user_id, badge_key) DO NOTHING
+        "#,
+        metric
+    ))
+    .bind(&badge.key)
+    .bind(user_id)
+    .bind(badge.threshold)
+    .execute(pool)
+    .await;
+
+    if let Err(e) = result {
+        tracing::error!("Failed to award badge {}: {}", badge.key, e);
+    }
+}
+
+async fn active_badges(pool: &PgPool) -> Result<Vec<Badge>, sqlx::Error> {
+    sqlx::query_as::<_, Badge>(
+        r#"
+        SELECT * FROM badges
+        WHERE status = 'active'
+        ORDER BY sort_order ASC, key ASC
+        "#,
+    )
+    .fetch_all(pool)
+    .await
+}
@@ -0,0 +1,351 @@
+use actix_web::{web, HttpRequest, HttpResponse};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::config::Config;
+use crate::db::visibility::{answer_listed_for, challenge_visible_to};
+use crate::models::{AnswerRating, Battle, BattleAnswer, BattlePair, BattleVoteRequest, BattleVoteResult};
+use crate::utils;
+
+/// The user's unvoted battle on the challenge, if any. Read-only; battles are
+/// started with [`create_battle`].
+pub async fn get_battle(
+    pool: web::Data<PgPool>,
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+) -> HttpResponse {
+    let challenge_id = path.into_inner();
+    let user_id = match utils::get_user_id(&req) {
+        Some(id) => id,
+        None => return utils::unauthorized("User ID required"),
+    };
+
+    if let Err(resp) = ensure_challenge_visible(pool.get_ref(), challenge_id, user_id).await {
+        return resp;
+    }
+
+    let battle = match fetch_open_battle(pool.get_ref(), user_id, challenge_id).await {
+        Ok(Some(b)) => b,
+        Ok(None) => return utils::not_found("No open battle"),
+        Err(e) => {
+            tracing::error!("Failed to fetch battle: {}", e);
+            return utils::internal_error("Failed to fetch battle");
+        }
+    };
+
+    match battle_pair(pool.get_ref(), battle).await {
+        Ok(pair) => utils::success(pair),
+        Err(e) => {
+            tracing::error!("Failed to fetch battle answers: {}", e);
+            utils::internal_error("Failed to fetch battle")
+        }
+    }
+}
+
+/// Hands the user a pair of answers to the challenge to judge. An unvoted
+/// battle is returned again until it is voted on; otherwise a new pair is
+/// sampled from listed answers by two different authors, never the user's own
+/// and never a pair they already judged. Answers with the fewest battles go
+/// first, then the closest ratings, and the A/B order is random. Authors are
+/// not revealed.
+pub async fn create_battle(
+    pool: web::Data<PgPool>,
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+) -> HttpResponse {
+    let challenge_id = path.into_inner();
+    let user_id = match utils::get_user_id(&req) {
+        Some(id) => id,
+        None => return utils::unauthorized("User ID required"),
+    };
+
+    if let Err(resp) = ensure_challenge_visible(pool.get_ref(), challenge_id, user_id).await {
+        return resp;
+    }
+
+    let (battle, created) = match fetch_open_battle(pool.get_ref(), user_id, challenge_id).await {
+        Ok(Some(b)) => (b, false),
+        Ok(None) => match sample_battle(pool.get_ref(), user_id, challenge_id).await {
+            Ok(Some(b)) => (b, true),
+            Ok(None) => return utils::not_found("No answers left to compare"),
+            Err(e) => {
+                tracing::error!("Failed to create battle: {}", e);
+                return utils::internal_error("Failed to create battle");
+            }
+        },
+        Err(e) => {
+            tracing::error!("Failed to fetch battle: {}", e);
+            return utils::internal_error("Failed to create battle");
+        }
+    };
+
+    match battle_pair(pool.get_ref(), battle).await {
+        Ok(pair) if created => utils::created(pair),
+        Ok(pair) => utils::success(pair),
+        Err(e) => {
+            tracing::error!("Failed to fetch battle answers: {}", e);
+            utils::internal_error("Failed to create battle")
+        }
+    }
+}
+
+/// Records the voter's pick and moves both answers' and both authors' Elo
+/// ratings.
+pub async fn vote_battle(
+    pool: web::Data<PgPool>,
+    config: web::Data<Config>,
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+    body: web::Json<BattleVoteRequest>,
+) -> HttpResponse {
+    let battle_id = path.into_inner();
+    let user_id = match utils::get_user_id(&req) {
+        Some(id) => id,
+        None => return utils::unauthorized("User ID required"),
+    };
+
+    let battle = sqlx::query_as::<_, Battle>(r#"SELECT * FROM battles WHERE id = $1 AND voter_id = $2"#)
+        .bind(battle_id)
+        .bind(user_id)
+        .fetch_optional(pool.get_ref())
+        .await;
+
+    let battle = match battle {
+        Ok(Some(b)) if b.winner_id.is_some() => return utils::conflict("Battle already voted"),
+        Ok(Some(b)) => b,
+        Ok(None) => return utils::not_found("Battle not found"),
+        Err(e) => {
+            tracing::error!("Failed to fetch battle: {}", e);
+            return utils::internal_error("Failed to vote");
+        }
+    };
+
+    let loser_id = if body.winner_id == battle.answer_a_id {
+        battle.answer_b_id
+    } else if body.winner_id == battle.answer_b_id {
+        battle.answer_a_id
+    } else {
+        return utils::bad_request("winner_id must be one of the battle's answers");
+    };
+    let winner_id = body.winner_id;
+    let k_factor = config.battle.k_factor;
+
+    let result: Result<Option<BattleVoteResult>, sqlx::Error> = async {
+        let mut tx = pool.begin().await?;
+
+        let battle = sqlx::query_as::<_, Battle>(
+            r#"
+            UPDATE battles SET winner_id = $1, voted_at = NOW()
+            WHERE id = $2 AND winner_id IS NULL
+            RETURNING *
+            "#,
+        )
+        .bind(winner_id)
+        .bind(battle_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let battle = match battle {
+            Some(b) => b,
+            None => return Ok(None),
+        };
+
+        // Rows are locked in id order so opposite votes on the same pair cannot deadlock
+        let answers = sqlx::query_as::<_, (Uuid, Uuid, f64)>(
+            r#"SELECT id, user_id, rating FROM answers WHERE id = ANY($1) ORDER BY id FOR UPDATE"#,
+        )
+        .bind([winner_id, loser_id])
+        .fetch_all(&mut *tx)
+        .await?;
+        let answer = |id: Uuid| answers.iter().find(|a| a.0 == id).map(|a| (a.1, a.2)).ok_or(sqlx::Error::RowNotFound);
+        let (winner_author, winner_rating) = answer(winner_id)?;
+        let (loser_author, loser_rating) = answer(loser_id)?;
+
+        let (winner_rating, loser_rating) = elo(winner_rating, loser_rating, k_factor);
+        let mut ratings = Vec::with_capacity(2);
+        for (answer_id, rating) in [(winner_id, winner_rating), (loser_id, loser_rating)] {
+            let r = sqlx::query_as::<_, AnswerRating>(
+                r#"
+                UPDATE answers SET rating = $1, battle_count = battle_count + 1
+                WHERE id = $2
+                RETURNING id AS answer_id, rating, battle_count
+                "#,
+            )
+            .bind(rating)
+            .bind(answer_id)
+            .fetch_one(&mut *tx)
+            .await?;
+            ratings.push(r);
+        }
+
+        let users = sqlx::query_as::<_, (Uuid, f64)>(
+            r#"SELECT id, rating FROM users WHERE id = ANY($1) ORDER BY id FOR UPDATE"#,
+        )
+        .bind([winner_author, loser_author])
+        .fetch_all(&mut *tx)
+        .await?;
+        let user_rating = |id: Uuid| users.iter().find(|u| u.0 == id).map(|u| u.1).ok_or(sqlx::Error::RowNotFound);
+        let winner_user_rating = user_rating(winner_author)?;
+        let loser_user_rating = user_rating(loser_author)?;
+
+        let (winner_user_rating, loser_user_rating) = elo(winner_user_rating, loser_user_rating, k_factor);
+        for (author, rating) in [(winner_author, winner_user_rating), (loser_author, loser_user_rating)] {
+            sqlx::query(r#"UPDATE users SET rating = $1, battle_count = battle_count + 1 WHERE id = $2"#)
+                .bind(rating)
+                .bind(author)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        tx.commit().await?;
+        Ok(Some(BattleVoteResult { battle, ratings }))
+    }
+    .await;
+
+    match result {
+        Ok(Some(r)) => utils::success(r),
+        Ok(None) => utils::conflict("Battle already voted"),
+        Err(e) => {
+            tracing::error!("Failed to vote on battle: {}", e);
+            utils::internal_error("Failed to vote")
+        }
+    }
+}
+
+async fn ensure_challenge_visible(pool: &PgPool, challenge_id: Uuid, user_id: Uuid) -> Result<(), HttpResponse> {
+    let exists = sqlx::query_scalar::<_, bool>(&format!(
+        r#"SELECT EXISTS(SELECT 1 FROM challenges c WHERE c.id = $1 AND c.status = 'active' AND {})"#,
+        challenge_visible_to("c", 2)
+    ))
+    .bind(challenge_id)
+    .bind(user_id)
+    .fetch_one(pool)
+    .await
+    .unwrap_or(false);
+
+    if exists {
+        Ok(())
+    } else {
+        Err(utils::not_found("Challenge not found"))
+    }
+}
+
+/// The voter's oldest unvoted battle on the challenge whose answers are both
+/// still active.
+async fn fetch_open_battle(pool: &PgPool, voter_id: Uuid, challenge_id: Uuid) -> Result<Option<Battle>, sqlx::Error> {
+    sqlx::query_as::<_, Battle>(
+        r#"
+        SELECT b.* FROM battles b
+        WHERE b.voter_id = $1 AND b.challenge_id = $2 AND b.winner_id IS NULL
+          AND NOT EXISTS (
+              SELECT 1 FROM answers a
+              WHERE a.id IN (b.answer_a_id, b.answer_b_id) AND a.status <> 'active'
+          )
+        ORDER BY b.created_at ASC
+        LIMIT 1
+        "#,
+    )
+    .bind(voter_id)
+    .bind(challenge_id)
+    .fetch_optional(pool)
+    .await
+}
+
+/// The battle's two answers in A/B order, without their authors.
+async fn battle_pair(pool: &PgPool, battle: Battle) -> Result<BattlePair, sqlx::Error> {
+    let mut answers = sqlx::query_as::<_, BattleAnswer>(r#"SELECT id, content FROM answers WHERE id IN ($1, $2)"#)
+        .bind(battle.answer_a_id)
+        .bind(battle.answer_b_id)
+        .fetch_all(pool)
+        .await?;
+
+    let answer_b = answers
+        .iter()
+        .position(|a| a.id == battle.answer_b_id)
+        .map(|i| answers.swap_remove(i))
+        .ok_or(sqlx::Error::RowNotFound)?;
+    let answer_a = answers.pop().ok_or(sqlx::Error::RowNotFound)?;
+
+    Ok(BattlePair {
+        battle_id: battle.id,
+        challenge_id: battle.challenge_id,
+        answer_a,
+        answer_b,
+    })
+}
+
+/// Samples and stores a new battle for the voter, or returns `None` when every
+/// eligible pair has been judged.
+async fn sample_battle(pool: &PgPool, voter_id: Uuid, challenge_id: Uuid) -> Result<Option<Battle>, sqlx::Error> {
+    sqlx::query_as::<_, Battle>(&format!(
+        r#"
+        WITH candidates AS (
+            SELECT a.id, a.user_id, a.rating, a.battle_count
+            FROM answers a
+            WHERE a.challenge_id = $1 AND a.status = 'active' AND a.user_id <> $2 AND {}
+        ),
+        pair AS (
+            SELECT x.id AS x_id, y.id AS y_id, RANDOM() < 0.5 AS flip
+            FROM candidates x
+            JOIN candidates y ON x.id < y.id AND x.user_id <> y.user_id
+            WHERE NOT EXISTS (
+                SELECT 1 FROM battles b
+                WHERE b.voter_id = $2
+                  AND ((b.answer_a_id = x.id AND b.answer_b_id = y.id)
+                    OR (b.answer_a_id = y.id AND b.answer_b_id = x.id))
+            )
+            ORDER BY x.battle_count + y.battle_count ASC, ABS(x.rating - y.rating) ASC, RANDOM()
+            LIMIT 1
+        )
+        INSERT INTO battles (challenge_id, voter_id, answer_a_id, answer_b_id)
+        SELECT $1, $2,
+            CASE WHEN flip THEN y_id ELSE x_id END,
+            CASE WHEN flip THEN x_id ELSE y_id END
+        FROM pair
+        RETURNING *
+        "#,
+        answer_listed_for("a", 2)
+    ))
+    .bind(challenge_id)
+    .bind(voter_id)
+    .fetch_optional(pool)
+    .await
+}
+
+/// New (winner, loser) Elo ratings after one game.
+fn elo(winner: f64, loser: f64, k_factor: f64) -> (f64, f64) {
+    let expected = 1.0 / (1.0 + 10f64.powf((loser - winner) / 400.0));
+    let delta = k_factor * (1.0 - expected);
+    (winner + delta, loser - delta)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-9
+    }
+
+    #[test]
+    fn equal_ratings_move_by_half_k() {
+        let (winner, loser) = elo(1500.0, 1500.0, 32.0);
+        assert!(close(winner, 1516.0));
+        assert!(close(loser, 1484.0));
+    }
+
+    #[test]
+    fn upsets_move_ratings_more_than_expected_wins() {
+        let (favourite, _) = elo(1900.0, 1500.0, 32.0);
+        let (underdog, _) = elo(1500.0, 1900.0, 32.0);
+        assert!(close(favourite - 1900.0, 32.0 / 11.0));
+        assert!(close(underdog - 1500.0, 32.0 * 10.0 / 11.0));
+    }
+
+    #[test]
+    fn rating_is_conserved() {
+        let (winner, loser) = elo(1612.5, 1437.25, 24.0);
+        assert!(close(winner + loser, 1612.5 + 1437.25));
+        assert!(winner > 1612.5 && loser < 1437.25);
+    }
+}
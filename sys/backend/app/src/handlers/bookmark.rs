@@ -0,0 +1,162 @@
+use actix_web::{web, HttpRequest, HttpResponse};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::config::Config;
+use crate::db::visibility::answer_visible_to;
+use crate::handlers::answer::answers_with_details;
+use crate::models::{Answer, Bookmark, PaginationParams};
+use crate::utils;
+
+pub async fn bookmark_answer(
+    pool: web::Data<PgPool>,
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+) -> HttpResponse {
+    let answer_id = path.into_inner();
+    let user_id = match utils::get_user_id(&req) {
+        Some(id) => id,
+        None => return utils::unauthorized("User ID required"),
+    };
+
+    // Check answer exists and the user may see it
+    let answer_exists = sqlx::query_scalar::<_, bool>(&format!(
+        r#"SELECT EXISTS(SELECT 1 FROM answers a WHERE a.id = $1 AND a.status = 'active' AND {})"#,
+        answer_visible_to("a", 2)
+    ))
+    .bind(answer_id)
+    .bind(user_id)
+    .fetch_one(pool.get_ref())
+    .await
+    .unwrap_or(false);
+
+    if !answer_exists {
+        return utils::not_found("Answer not found");
+    }
+
+    let result = sqlx::query_as::<_, Bookmark>(
+        r#"
+        INSERT INTO bookmarks (user_id, answer_id)
+        VALUES ($1, $2)
+        ON CONFLICT (user_id, answer_id) DO NOTHING
+        RETURNING *
+        "#,
+    )
+    .bind(user_id)
+    .bind(answer_id)
+    .fetch_optional(pool.get_ref())
+    .await;
+
+    match result {
+        Ok(Some(bookmark)) => utils::created(bookmark),
+        Ok(None) => utils::conflict("Already bookmarked"),
+        Err(e) => {
+            tracing::error!("Failed to bookmark answer: {}", e);
+            utils::internal_error("Failed to bookmark answer")
+        }
+    }
+}
+
+pub async fn unbookmark_answer(
+    pool: web::Data<PgPool>,
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+) -> HttpResponse {
+    let answer_id = path.into_inner();
+    let user_id = match utils::get_user_id(&req) {
+        Some(id) => id,
+        None => return utils::unauthorized("User ID required"),
+    };
+
+    let result = sqlx::query(
+        r#"DELETE FROM bookmarks WHERE user_id = $1 AND answer_id = $2"#,
+    )
+    .bind(user_id)
+    .bind(answer_id)
+    .execute(pool.get_ref())
+    .await;
+
+    match result {
+        Ok(res) if res.rows_affected() > 0 => utils::no_content(),
+        Ok(_) => utils::not_found("Bookmark not found"),
+        Err(e) => {
+            tracing::error!("Failed to remove bookmark: {}", e);
+            utils::internal_error("Failed to remove bookmark")
+        }
+    }
+}
+
+pub async fn get_bookmarks(
+    pool: web::Data<PgPool>,
+    config: web::Data<Config>,
+    req: HttpRequest,
+    query: web::Query<PaginationParams>,
+) -> HttpResponse {
+    let user_id = match utils::get_user_id(&req) {
+        Some(id) => id,
+        None => return utils::unauthorized("User ID required"),
+    };
+    let (page, page_size, offset) = utils::normalize_pagination(
+        query.page,
+        query.page_size,
+        config.pagination.default_page_size,
+        config.pagination.max_page_size,
+    );
+
+    let total: (i64,) = sqlx::query_as(&format!(
+        r#"
+        SELECT COUNT(*) FROM bookmarks b
+        JOIN answers a ON a.id = b.answer_id
+        WHERE b.user_id = $1 AND a.status = 'active' AND {}
+        "#,
+        answer_visible_to("a", 1)
+    ))
+    .bind(user_id)
+    .fetch_one(pool.get_ref())
+    .await
+    .unwrap_or((0,));
+
+    // Most recently bookmarked first
+    let answers = sqlx::query_as::<_, Answer>(&format!(
+        r#"
+        SELECT a.* FROM bookmarks b
+        JOIN answers a ON a.id = b.answer_id
+        WHERE b.user_id = $1 AND a.status = 'active' AND {}
+        ORDER BY b.created_at DESC
+        LIMIT $2 OFFSET $3
+        "#,
+        answer_visible_to("a", 1)
+    ))
+    .bind(user_id)
+    .bind(page_size)
+    .bind(offset)
+    .fetch_all(pool.get_ref())
+    .await;
+
+    match answers {
+        Ok(answers) => {
+            let results = answers_with_details(pool.get_ref(), answers, Some(user_id)).await;
+            utils::paginated(results, page, page_size, total.0)
+        }
+        Err(e) => {
+            tracing::error!("Failed to fetch bookmarks: {}", e);
+            utils::internal_error("Failed to fetch bookmarks")
+        }
+    }
+}
+
+/// Whether the viewer has bookmarked the answer; always false for anonymous viewers.
+pub async fn is_bookmarked(pool: &PgPool, answer_id: Uuid, user_id: Option<Uuid>) -> bool {
+    let Some(uid) = user_id else {
+        return false;
+    };
+
+    sqlx::query_scalar::<_, bool>(
+        r#"SELECT EXISTS(SELECT 1 FROM bookmarks WHERE answer_id = $1 AND user_id = $2)"#,
+    )
+    .bind(answer_id)
+    .bind(uid)
+    .fetch_one(pool)
+    .await
+    .unwrap_or(false)
+}
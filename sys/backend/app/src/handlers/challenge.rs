@@ -1,25 +1,22 @@
 use actix_web::{web, HttpRequest, HttpResponse};
 use sqlx::PgPool;
 use uuid::Uuid;
+use validator::Validate;
 
+use crate::auth;
 use crate::config::Config;
-use crate::models::{Category, Challenge, ChallengeWithCategory, CreateChallengeRequest, PaginationParams};
+use crate::db::visibility::challenge_visible_to;
+use crate::handlers::daily;
+use crate::handlers::difficulty::DIFFICULTY_TIERS;
+use crate::handlers::editorial::featured_answers;
+use crate::models::{
+    Category, Challenge, ChallengeDetail, CreateChallengeRequest, PaginationParams, PublishChallengeRequest,
+    RateChallengeRequest,
+};
 use crate::utils;
 
-pub async fn get_daily_challenges(pool: web::Data<PgPool>) -> HttpResponse {
-    let result = sqlx::query_as::<_, Challenge>(
-        r#"
-        SELECT * FROM challenges
-        WHERE status = 'active'
-          AND (release_date IS NULL OR release_date <= CURRENT_DATE)
-        ORDER BY release_date DESC NULLS LAST, created_at DESC
-        LIMIT 5
-        "#,
-    )
-    .fetch_all(pool.get_ref())
-    .await;
-
-    match result {
+pub async fn get_daily_challenges(pool: web::Data<PgPool>, config: web::Data<Config>) -> HttpResponse {
+    match daily::todays_challenges(pool.get_ref(), &config.daily).await {
         Ok(challenges) => utils::success(challenges),
         Err(e) => {
             tracing::error!("Failed to fetch daily challenges: {}", e);
@@ -42,7 +39,7 @@ pub async fn list_challenges(
 
     // Get total count
     let total: (i64,) = sqlx::query_as(
-        r#"SELECT COUNT(*) FROM challenges WHERE status = 'active'"#,
+        r#"SELECT COUNT(*) FROM challenges WHERE status = 'active' AND organization_id IS NULL AND contest_id IS NULL"#,
     )
     .fetch_one(pool.get_ref())
     .await
@@ -51,7 +48,7 @@ pub async fn list_challenges(
     let result = sqlx::query_as::<_, Challenge>(
         r#"
         SELECT * FROM challenges
-        WHERE status = 'active'
+        WHERE status = 'active' AND organization_id IS NULL AND contest_id IS NULL
         ORDER BY release_date DESC NULLS LAST, created_at DESC
         LIMIT $1 OFFSET $2
         "#,
@@ -70,14 +67,21 @@ pub async fn list_challenges(
     }
 }
 
-pub async fn get_challenge(pool: web::Data<PgPool>, path: web::Path<Uuid>) -> HttpResponse {
+pub async fn get_challenge(
+    pool: web::Data<PgPool>,
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+) -> HttpResponse {
     let challenge_id = path.into_inner();
+    let current_user_id = utils::get_user_id(&req);
 
-    // Get challenge with category
-    let challenge = sqlx::query_as::<_, Challenge>(
-        r#"SELECT * FROM challenges WHERE id = $1 AND status = 'active'"#,
-    )
+    // Get challenge with category; private challenges are not found for outsiders
+    let challenge = sqlx::query_as::<_, Challenge>(&format!(
+        r#"SELECT * FROM challenges WHERE id = $1 AND status = 'active' AND {}"#,
+        challenge_visible_to("challenges", 2)
+    ))
     .bind(challenge_id)
+    .bind(current_user_id)
     .fetch_optional(pool.get_ref())
     .await;
 
@@ -98,7 +102,10 @@ pub async fn get_challenge(pool: web::Data<PgPool>, path: web::Path<Uuid>) -> Ht
     .await;
 
     match category {
-        Ok(category) => utils::success(ChallengeWithCategory { challenge, category }),
+        Ok(category) => {
+            let featured = featured_answers(pool.get_ref(), &challenge, current_user_id).await;
+            utils::success(ChallengeDetail { challenge, category, featured })
+        }
         Err(e) => {
             tracing::error!("Failed to fetch category: {}", e);
             utils::internal_error("Failed to fetch challenge details")
@@ -121,7 +128,7 @@ pub async fn get_challenges_by_category(
     );
 
     let total: (i64,) = sqlx::query_as(
-        r#"SELECT COUNT(*) FROM challenges WHERE category_id = $1 AND status = 'active'"#,
+        r#"SELECT COUNT(*) FROM challenges WHERE category_id = $1 AND status = 'active' AND organization_id IS NULL AND contest_id IS NULL"#,
     )
     .bind(category_id)
     .fetch_one(pool.get_ref())
@@ -131,7 +138,7 @@ pub async fn get_challenges_by_category(
     let result = sqlx::query_as::<_, Challenge>(
         r#"
         SELECT * FROM challenges
-        WHERE category_id = $1 AND status = 'active'
+        WHERE category_id = $1 AND status = 'active' AND organization_id IS NULL AND contest_id IS NULL
         ORDER BY release_date DESC NULLS LAST, created_at DESC
         LIMIT $2 OFFSET $3
         "#,
@@ -151,23 +158,216 @@ pub async fn get_challenges_by_category(
     }
 }
 
+/// Publishes a challenge directly. Other users suggest challenges through
+/// proposals instead.
 pub async fn create_challenge(
     pool: web::Data<PgPool>,
     req: HttpRequest,
     body: web::Json<CreateChallengeRequest>,
 ) -> HttpResponse {
-    // Check user authentication
     let user_id = match utils::get_user_id(&req) {
         Some(id) => id,
         None => return utils::unauthorized("User ID required"),
     };
+    if let Err(resp) = auth::require_role(pool.get_ref(), user_id, auth::EDITORIAL_ROLES).await {
+        return resp;
+    }
+
+    insert_challenge(pool.get_ref(), &body, None, None).await
+}
 
+/// Draft challenges from accepted proposals, oldest first.
+pub async fn list_draft_challenges(pool: web::Data<PgPool>, req: HttpRequest) -> HttpResponse {
+    let user_id = match utils::get_user_id(&req) {
+        Some(id) => id,
+        None => return utils::unauthorized("User ID required"),
+    };
+    if let Err(resp) = auth::require_role(pool.get_ref(), user_id, auth::EDITORIAL_ROLES).await {
+        return resp;
+    }
+
+    let result = sqlx::query_as::<_, Challenge>(
+        r#"SELECT * FROM challenges WHERE status = 'draft' ORDER BY created_at ASC"#,
+    )
+    .fetch_all(pool.get_ref())
+    .await;
+
+    match result {
+        Ok(challenges) => utils::success(challenges),
+        Err(e) => {
+            tracing::error!("Failed to fetch draft challenges: {}", e);
+            utils::internal_error("Failed to fetch draft challenges")
+        }
+    }
+}
+
+/// Moves a draft challenge into the public pool, optionally reworded first.
+pub async fn publish_challenge(
+    pool: web::Data<PgPool>,
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+    body: web::Json<PublishChallengeRequest>,
+) -> HttpResponse {
+    let challenge_id = path.into_inner();
+    let user_id = match utils::get_user_id(&req) {
+        Some(id) => id,
+        None => return utils::unauthorized("User ID required"),
+    };
+    if let Err(resp) = auth::require_role(pool.get_ref(), user_id, auth::EDITORIAL_ROLES).await {
+        return resp;
+    }
+
+    if let Err(e) = body.validate() {
+        return utils::bad_request(&e.to_string());
+    }
+    if matches!(body.char_limit, Some(limit) if limit < 1) {
+        return utils::bad_request("char_limit must be positive");
+    }
+
+    let result = sqlx::query_as::<_, Challenge>(
+        r#"
+        UPDATE challenges
+        SET status = 'active',
+            title = COALESCE($1, title),
+            description = COALESCE($2, description),
+            char_limit = COALESCE($3, char_limit),
+            release_date = COALESCE($4, release_date),
+            updated_at = NOW()
+        WHERE id = $5 AND status = 'draft'
+        RETURNING *
+        "#,
+    )
+    .bind(&body.title)
+    .bind(&body.description)
+    .bind(body.char_limit)
+    .bind(body.release_date)
+    .bind(challenge_id)
+    .fetch_optional(pool.get_ref())
+    .await;
+
+    match result {
+        Ok(Some(challenge)) => utils::success(challenge),
+        Ok(None) => utils::not_found("Draft challenge not found"),
+        Err(e) => {
+            tracing::error!("Failed to publish challenge: {}", e);
+            utils::internal_error("Failed to publish challenge")
+        }
+    }
+}
+
+/// Records the user's 1-5 rating of a challenge they answered. A public
+/// challenge whose average sinks below the configured floor, once enough
+/// users rated it, is retired from the pool unless a course step uses it.
+pub async fn rate_challenge(
+    pool: web::Data<PgPool>,
+    config: web::Data<Config>,
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+    body: web::Json<RateChallengeRequest>,
+) -> HttpResponse {
+    let challenge_id = path.into_inner();
+    let user_id = match utils::get_user_id(&req) {
+        Some(id) => id,
+        None => return utils::unauthorized("User ID required"),
+    };
+
+    if let Err(e) = body.validate() {
+        return utils::bad_request(&e.to_string());
+    }
+
+    let answered = sqlx::query_scalar::<_, bool>(&format!(
+        r#"
+        SELECT EXISTS(
+            SELECT 1 FROM challenges ch
+            JOIN answers a ON a.challenge_id = ch.id AND a.user_id = $2 AND a.status <> 'deleted'
+            WHERE ch.id = $1 AND ch.status = 'active' AND {}
+        )
+        "#,
+        challenge_visible_to("ch", 2)
+    ))
+    .bind(challenge_id)
+    .bind(user_id)
+    .fetch_one(pool.get_ref())
+    .await
+    .unwrap_or(false);
+
+    if !answered {
+        return utils::bad_request("Answer the challenge before rating it");
+    }
+
+    let result = sqlx::query(
+        r#"
+        INSERT INTO challenge_ratings (challenge_id, user_id, rating)
+        VALUES ($1, $2, $3)
+        ON CONFLICT (challenge_id, user_id) DO UPDATE SET rating = EXCLUDED.rating, updated_at = NOW()
+        "#,
+    )
+    .bind(challenge_id)
+    .bind(user_id)
+    .bind(body.rating)
+    .execute(pool.get_ref())
+    .await;
+
+    if let Err(e) = result {
+        tracing::error!("Failed to rate challenge: {}", e);
+        return utils::internal_error("Failed to rate challenge");
+    }
+
+    let result = sqlx::query_as::<_, Challenge>(
+        r#"
+        UPDATE challenges SET
+            rating_avg = agg.avg_rating,
+            rating_count = agg.n,
+            status = CASE
+                WHEN organization_id IS NULL AND contest_id IS NULL
+                    AND NOT EXISTS (SELECT 1 FROM course_steps cs WHERE cs.challenge_id = challenges.id)
+                    AND agg.n >= $2 AND agg.avg_rating < $3 THEN 'retired'
+                ELSE status
+            END,
+            updated_at = NOW()
+        FROM (
+            SELECT AVG(rating)::DOUBLE PRECISION AS avg_rating, COUNT(*)::INTEGER AS n
+            FROM challenge_ratings
+            WHERE challenge_id = $1
+        ) agg
+        WHERE challenges.id = $1
+        RETURNING challenges.*
+        "#,
+    )
+    .bind(challenge_id)
+    .bind(config.challenge_rating.min_ratings)
+    .bind(config.challenge_rating.retire_below)
+    .fetch_one(pool.get_ref())
+    .await;
+
+    match result {
+        Ok(challenge) => {
+            if challenge.status == "retired" {
+                tracing::info!("Retired challenge {} at rating {:?}", challenge.id, challenge.rating_avg);
+            }
+            utils::success(challenge)
+        }
+        Err(e) => {
+            tracing::error!("Failed to update challenge rating: {}", e);
+            utils::internal_error("Failed to rate challenge")
+        }
+    }
+}
+
+/// Validates and stores a new challenge, public or private to `organization_id`,
+/// optionally as part of a contest.
+pub async fn insert_challenge(
+    pool: &PgPool,
+    body: &CreateChallengeRequest,
+    organization_id: Option<Uuid>,
+    contest_id: Option<Uuid>,
+) -> HttpResponse {
     // Validate category exists
     let category = sqlx::query_as::<_, Category>(
         r#"SELECT * FROM categories WHERE id = $1 AND status = 'active'"#,
     )
     .bind(body.category_id)
-    .fetch_optional(pool.get_ref())
+    .fetch_optional(pool)
     .await;
 
     let category = match category {
@@ -181,10 +381,21 @@ pub async fn create_challenge(
 
     let char_limit = body.char_limit.unwrap_or(category.char_limit);
 
+    if let Some(difficulty) = &body.difficulty {
+        if !DIFFICULTY_TIERS.contains(&difficulty.as_str()) {
+            return utils::bad_request("difficulty must be easy, normal or hard");
+        }
+    }
+    if let Some(limit) = body.hard_mode_char_limit {
+        if limit < 1 || limit >= char_limit {
+            return utils::bad_request("hard_mode_char_limit must be between 1 and char_limit - 1");
+        }
+    }
+
     let result = sqlx::query_as::<_, Challenge>(
         r#"
-        INSERT INTO challenges (category_id, title, description, char_limit, release_date)
-        VALUES ($1, $2, $3, $4, $5)
+        INSERT INTO challenges (category_id, title, description, char_limit, release_date, difficulty, hard_mode_char_limit, organization_id, contest_id)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
         RETURNING *
         "#,
     )
@@ -193,7 +404,11 @@ pub async fn create_challenge(
     .bind(&body.description)
     .bind(char_limit)
     .bind(body.release_date)
-    .fetch_one(pool.get_ref())
+    .bind(&body.difficulty)
+    .bind(body.hard_mode_char_limit)
+    .bind(organization_id)
+    .bind(contest_id)
+    .fetch_one(pool)
     .await;
 
     match result {
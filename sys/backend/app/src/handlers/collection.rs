@@ -0,0 +1,427 @@
+use actix_web::{web, HttpRequest, HttpResponse};
+use sqlx::PgPool;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::db::visibility::answer_visible_to;
+use crate::handlers::answer::answers_with_details;
+use crate::models::{
+    AddCollectionItemRequest, Answer, Collection, CollectionWithItems, CreateCollectionRequest,
+    ReorderCollectionRequest, UpdateCollectionRequest,
+};
+use crate::utils;
+
+pub async fn list_collections(pool: web::Data<PgPool>, req: HttpRequest) -> HttpResponse {
+    let user_id = match utils::get_user_id(&req) {
+        Some(id) => id,
+        None => return utils::unauthorized("User ID required"),
+    };
+
+    let result = sqlx::query_as::<_, Collection>(&format!(
+        r#"
+        SELECT {} FROM collections c
+        WHERE c.user_id = $1
+        ORDER BY c.updated_at DESC
+        "#,
+        collection_columns(1)
+    ))
+    .bind(user_id)
+    .fetch_all(pool.get_ref())
+    .await;
+
+    match result {
+        Ok(collections) => utils::success(collections),
+        Err(e) => {
+            tracing::error!("Failed to fetch collections: {}", e);
+            utils::internal_error("Failed to fetch collections")
+        }
+    }
+}
+
+pub async fn create_collection(
+    pool: web::Data<PgPool>,
+    req: HttpRequest,
+    body: web::Json<CreateCollectionRequest>,
+) -> HttpResponse {
+    let user_id = match utils::get_user_id(&req) {
+        Some(id) => id,
+        None => return utils::unauthorized("User ID required"),
+    };
+
+    if let Err(e) = body.validate() {
+        return utils::bad_request(&e.to_string());
+    }
+
+    let result = sqlx::query_as::<_, Collection>(&format!(
+        r#"
+        INSERT INTO collections AS c (user_id, name, description)
+        VALUES ($1, $2, $3)
+        RETURNING {}
+        "#,
+        collection_columns(1)
+    ))
+    .bind(user_id)
+    .bind(&body.name)
+    .bind(&body.description)
+    .fetch_one(pool.get_ref())
+    .await;
+
+    match result {
+        Ok(collection) => utils::created(collection),
+        Err(e) => {
+            tracing::error!("Failed to create collection: {}", e);
+            utils::internal_error("Failed to create collection")
+        }
+    }
+}
+
+pub async fn get_collection(
+    pool: web::Data<PgPool>,
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+) -> HttpResponse {
+    let collection_id = path.into_inner();
+    let user_id = match utils::get_user_id(&req) {
+        Some(id) => id,
+        None => return utils::unauthorized("User ID required"),
+    };
+
+    let collection = match fetch_own_collection(pool.get_ref(), collection_id, user_id).await {
+        Ok(c) => c,
+        Err(resp) => return resp,
+    };
+
+    let answers = sqlx::query_as::<_, Answer>(&format!(
+        r#"
+        SELECT a.* FROM collection_items ci
+        JOIN answers a ON a.id = ci.answer_id
+        WHERE ci.collection_id = $1 AND a.status = 'active' AND {}
+        ORDER BY ci.position ASC
+        "#,
+        answer_visible_to("a", 2)
+    ))
+    .bind(collection_id)
+    .bind(user_id)
+    .fetch_all(pool.get_ref())
+    .await;
+
+    match answers {
+        Ok(answers) => {
+            let items = answers_with_details(pool.get_ref(), answers, Some(user_id)).await;
+            utils::success(CollectionWithItems { collection, items })
+        }
+        Err(e) => {
+            tracing::error!("Failed to fetch collection items: {}", e);
+            utils::internal_error("Failed to fetch collection")
+        }
+    }
+}
+
+pub async fn update_collection(
+    pool: web::Data<PgPool>,
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+    body: web::Json<UpdateCollectionRequest>,
+) -> HttpResponse {
+    let collection_id = path.into_inner();
+    let user_id = match utils::get_user_id(&req) {
+        Some(id) => id,
+        None => return utils::unauthorized("User ID required"),
+    };
+
+    if let Err(e) = body.validate() {
+        return utils::bad_request(&e.to_string());
+    }
+
+    let collection = match fetch_own_collection(pool.get_ref(), collection_id, user_id).await {
+        Ok(c) => c,
+        Err(resp) => return resp,
+    };
+
+    let name = body.name.as_ref().unwrap_or(&collection.name);
+    let description = body.description.as_ref().or(collection.description.as_ref());
+
+    let result = sqlx::query_as::<_, Collection>(&format!(
+        r#"
+        UPDATE collections c
+        SET name = $1, description = $2, updated_at = NOW()
+        WHERE c.id = $3
+        RETURNING {}
+        "#,
+        collection_columns(4)
+    ))
+    .bind(name)
+    .bind(description)
+    .bind(collection_id)
+    .bind(user_id)
+    .fetch_one(pool.get_ref())
+    .await;
+
+    match result {
+        Ok(collection) => utils::success(collection),
+        Err(e) => {
+            tracing::error!("Failed to update collection: {}", e);
+            utils::internal_error("Failed to update collection")
+        }
+    }
+}
+
+pub async fn delete_collection(
+    pool: web::Data<PgPool>,
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+) -> HttpResponse {
+    let collection_id = path.into_inner();
+    let user_id = match utils::get_user_id(&req) {
+        Some(id) => id,
+        None => return utils::unauthorized("User ID required"),
+    };
+
+    let result = sqlx::query(
+        r#"DELETE FROM collections WHERE id = $1 AND user_id = $2"#,
+    )
+    .bind(collection_id)
+    .bind(user_id)
+    .execute(pool.get_ref())
+    .await;
+
+    match result {
+        Ok(res) if res.rows_affected() > 0 => utils::no_content(),
+        Ok(_) => utils::not_found("Collection not found"),
+        Err(e) => {
+            tracing::error!("Failed to delete collection: {}", e);
+            utils::internal_error("Failed to delete collection")
+        }
+    }
+}
+
+pub async fn add_collection_item(
+    pool: web::Data<PgPool>,
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+    body: web::Json<AddCollectionItemRequest>,
+) -> HttpResponse {
+    let collection_id = path.into_inner();
+    let user_id = match utils::get_user_id(&req) {
+        Some(id) => id,
+        None => return utils::unauthorized("User ID required"),
+    };
+
+    if let Err(resp) = fetch_own_collection(pool.get_ref(), collection_id, user_id).await {
+        return resp;
+    }
+
+    let answer_exists = sqlx::query_scalar::<_, bool>(&format!(
+        r#"SELECT EXISTS(SELECT 1 FROM answers a WHERE a.id = $1 AND a.status = 'active' AND {})"#,
+        answer_visible_to("a", 2)
+    ))
+    .bind(body.answer_id)
+    .bind(user_id)
+    .fetch_one(pool.get_ref())
+    .await
+    .unwrap_or(false);
+
+    if !answer_exists {
+        return utils::not_found("Answer not found");
+    }
+
+    // New items go to the end of the collection
+    let result = sqlx::query(
+        r#"
+        INSERT INTO collection_items (collection_id, answer_id, position)
+        SELECT $1, $2, COALESCE(MAX(position), 0) + 1
+        FROM collection_items WHERE collection_id = $1
+        ON CONFLICT (collection_id, answer_id) DO NOTHING
+        "#,
+    )
+    .bind(collection_id)
+    .bind(body.answer_id)
+    .execute(pool.get_ref())
+    .await;
+
+    match result {
+        Ok(res) if res.rows_affected() > 0 => {
+            let _ = sqlx::query(
+                r#"UPDATE collections SET updated_at = NOW() WHERE id = $1"#,
+            )
+            .bind(collection_id)
+            .execute(pool.get_ref())
+            .await;
+
+            utils::no_content()
+        }
+        Ok(_) => utils::conflict("Answer is already in this collection"),
+        Err(e) => {
+            tracing::error!("Failed to add collection item: {}", e);
+            utils::internal_error("Failed to add collection item")
+        }
+    }
+}
+
+pub async fn remove_collection_item(
+    pool: web::Data<PgPool>,
+    req: HttpRequest,
+    path: web::Path<(Uuid, Uuid)>,
+) -> HttpResponse {
+    let (collection_id, answer_id) = path.into_inner();
+    let user_id = match utils::get_user_id(&req) {
+        Some(id) => id,
+        None => return utils::unauthorized("User ID required"),
+    };
+
+    if let Err(resp) = fetch_own_collection(pool.get_ref(), collection_id, user_id).await {
+        return resp;
+    }
+
+    let result = sqlx::query(
+        r#"DELETE FROM collection_items WHERE collection_id = $1 AND answer_id = $2"#,
+    )
+    .bind(collection_id)
+    .bind(answer_id)
+    .execute(pool.get_ref())
+    .await;
+
+    match result {
+        Ok(res) if res.rows_affected() > 0 => {
+            let _ = sqlx::query(
+                r#"UPDATE collections SET updated_at = NOW() WHERE id = $1"#,
+            )
+            .bind(collection_id)
+            .execute(pool.get_ref())
+            .await;
+
+            utils::no_content()
+        }
+        Ok(_) => utils::not_found("Collection item not found"),
+        Err(e) => {
+            tracing::error!("Failed to remove collection item: {}", e);
+            utils::internal_error("Failed to remove collection item")
+        }
+    }
+}
+
+pub async fn reorder_collection_items(
+    pool: web::Data<PgPool>,
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+    body: web::Json<ReorderCollectionRequest>,
+) -> HttpResponse {
+    let collection_id = path.into_inner();
+    let user_id = match utils::get_user_id(&req) {
+        Some(id) => id,
+        None => return utils::unauthorized("User ID required"),
+    };
+
+    if let Err(resp) = fetch_own_collection(pool.get_ref(), collection_id, user_id).await {
+        return resp;
+    }
+
+    // Items the user can no longer see are kept, after the visible ones
+    let current = sqlx::query_as::<_, (Uuid, bool)>(&format!(
+        r#"
+        SELECT ci.answer_id, (a.status = 'active' AND {}) AS visible
+        FROM collection_items ci
+        JOIN answers a ON a.id = ci.answer_id
+        WHERE ci.collection_id = $1
+        ORDER BY ci.position ASC
+        "#,
+        answer_visible_to("a", 2)
+    ))
+    .bind(collection_id)
+    .bind(user_id)
+    .fetch_all(pool.get_ref())
+    .await;
+
+    let current = match current {
+        Ok(items) => items,
+        Err(e) => {
+            tracing::error!("Failed to fetch collection items: {}", e);
+            return utils::internal_error("Failed to reorder collection");
+        }
+    };
+
+    // The new order must be a permutation of the items get_collection shows
+    let mut visible: Vec<Uuid> = current.iter().filter(|(_, v)| *v).map(|(id, _)| *id).collect();
+    let mut requested = body.answer_ids.clone();
+    visible.sort();
+    requested.sort();
+    if visible != requested {
+        return utils::bad_request("answer_ids must list every item in the collection exactly once");
+    }
+
+    let order: Vec<Uuid> = body
+        .answer_ids
+        .iter()
+        .copied()
+        .chain(current.iter().filter(|(_, v)| !*v).map(|(id, _)| *id))
+        .collect();
+
+    let result = sqlx::query(
+        r#"
+        UPDATE collection_items ci
+        SET position = o.position
+        FROM UNNEST($2::uuid[]) WITH ORDINALITY AS o(answer_id, position)
+        WHERE ci.collection_id = $1 AND ci.answer_id = o.answer_id
+        "#,
+    )
+    .bind(collection_id)
+    .bind(&order)
+    .execute(pool.get_ref())
+    .await;
+
+    match result {
+        Ok(_) => {
+            let _ = sqlx::query(
+                r#"UPDATE collections SET updated_at = NOW() WHERE id = $1"#,
+            )
+            .bind(collection_id)
+            .execute(pool.get_ref())
+            .await;
+
+            utils::no_content()
+        }
+        Err(e) => {
+            tracing::error!("Failed to reorder collection: {}", e);
+            utils::internal_error("Failed to reorder collection")
+        }
+    }
+}
+
+/// Loads a collection owned by `user_id`. Collections are private, so other
+/// users' collections are reported as not found.
+async fn fetch_own_collection(
+    pool: &PgPool,
+    collection_id: Uuid,
+    user_id: Uuid,
+) -> Result<Collection, HttpResponse> {
+    let collection = sqlx::query_as::<_, Collection>(&format!(
+        r#"SELECT {} FROM collections c WHERE c.id = $1 AND c.user_id = $2"#,
+        collection_columns(2)
+    ))
+    .bind(collection_id)
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await;
+
+    match collection {
+        Ok(Some(c)) => Ok(c),
+        Ok(None) => Err(utils::not_found("Collection not found")),
+        Err(e) => {
+            tracing::error!("Failed to fetch collection: {}", e);
+            Err(utils::internal_error("Failed to fetch collection"))
+        }
+    }
+}
+
+/// Collection columns with `item_count` counted over the items the owner can
+/// currently see, matching what [`get_collection`] returns.
+fn collection_columns(owner_param: usize) -> String {
+    format!(
+        r#"c.id, c.user_id, c.name, c.description,
+           (SELECT COUNT(*)::INTEGER FROM collection_items ci
+            JOIN answers a ON a.id = ci.answer_id
+            WHERE ci.collection_id = c.id AND a.status = 'active' AND {}) AS item_count,
+           c.created_at, c.updated_at"#,
+        answer_visible_to("a", owner_param)
+    )
+}
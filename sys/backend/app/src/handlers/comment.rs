@@ -2,7 +2,11 @@ use actix_web::{web, HttpRequest, HttpResponse};
 use sqlx::PgPool;
 use uuid::Uuid;
 
+use crate::auth;
 use crate::config::Config;
+use crate::content_filter::{ContentKind, FilterPipeline, Verdict};
+use crate::db::visibility::answer_visible_to;
+use crate::handlers::moderation;
 use crate::models::{
     Answer, Comment, CommentWithUser, CreateCommentRequest, PaginationParams, User, UserSummary,
 };
@@ -11,10 +15,12 @@ use crate::utils;
 pub async fn get_answer_comments(
     pool: web::Data<PgPool>,
     config: web::Data<Config>,
+    req: HttpRequest,
     path: web::Path<Uuid>,
     query: web::Query<PaginationParams>,
 ) -> HttpResponse {
     let answer_id = path.into_inner();
+    let current_user_id = utils::get_user_id(&req);
     let (page, page_size, offset) = utils::normalize_pagination(
         query.page,
         query.page_size,
@@ -22,6 +28,25 @@ pub async fn get_answer_comments(
         config.pagination.max_page_size,
     );
 
+    // Comments on answers the viewer may not see are reported as not found
+    let visible = sqlx::query_scalar::<_, bool>(&format!(
+        r#"SELECT EXISTS(SELECT 1 FROM answers a WHERE a.id = $1 AND a.status = 'active' AND {})"#,
+        answer_visible_to("a", 2)
+    ))
+    .bind(answer_id)
+    .bind(current_user_id)
+    .fetch_one(pool.get_ref())
+    .await;
+
+    match visible {
+        Ok(true) => {}
+        Ok(false) => return utils::not_found("Answer not found"),
+        Err(e) => {
+            tracing::error!("Failed to fetch answer: {}", e);
+            return utils::internal_error("Failed to fetch comments");
+        }
+    }
+
     let total: (i64,) = sqlx::query_as(
         r#"SELECT COUNT(*) FROM comments WHERE answer_id = $1 AND status = 'active'"#,
     )
@@ -78,6 +103,7 @@ pub async fn get_answer_comments(
 
 pub async fn create_comment(
     pool: web::Data<PgPool>,
+    content_filter: web::Data<FilterPipeline>,
     req: HttpRequest,
     path: web::Path<Uuid>,
     body: web::Json<CreateCommentRequest>,
@@ -88,11 +114,17 @@ pub async fn create_comment(
         None => return utils::unauthorized("User ID required"),
     };
 
-    // Check answer exists
-    let answer = sqlx::query_as::<_, Answer>(
-        r#"SELECT * FROM answers WHERE id = $1 AND status = 'active'"#,
-    )
+    if auth::is_suspended(pool.get_ref(), user_id).await {
+        return utils::forbidden("Your account is suspended");
+    }
+
+    // Check answer exists and the commenter may see it
+    let answer = sqlx::query_as::<_, Answer>(&format!(
+        r#"SELECT * FROM answers a WHERE a.id = $1 AND a.status = 'active' AND {}"#,
+        answer_visible_to("a", 2)
+    ))
     .bind(answer_id)
+    .bind(user_id)
     .fetch_optional(pool.get_ref())
     .await;
 
@@ -105,29 +137,41 @@ pub async fn create_comment(
         _ => {}
     }
 
+    let recent_posts = content_filter.recent_posts(pool.get_ref(), user_id, None).await;
+    let outcome = content_filter.check(ContentKind::Comment, &body.content, &recent_posts);
+    if outcome.verdict == Verdict::Reject {
+        return utils::unprocessable("Content rejected by filter", outcome);
+    }
+    let status = if outcome.verdict == Verdict::Hold { "pending" } else { "active" };
+
     let result = sqlx::query_as::<_, Comment>(
         r#"
-        INSERT INTO comments (answer_id, user_id, content)
-        VALUES ($1, $2, $3)
+        INSERT INTO comments (answer_id, user_id, content, status)
+        VALUES ($1, $2, $3, $4)
         RETURNING *
         "#,
     )
     .bind(answer_id)
     .bind(user_id)
     .bind(&body.content)
+    .bind(status)
     .fetch_one(pool.get_ref())
     .await;
 
-    // Update answer comment count
-    let _ = sqlx::query(
-        r#"UPDATE answers SET comment_count = comment_count + 1 WHERE id = $1"#,
-    )
-    .bind(answer_id)
-    .execute(pool.get_ref())
-    .await;
-
     match result {
         Ok(comment) => {
+            if comment.status == "active" {
+                // Update answer comment count
+                let _ = sqlx::query(
+                    r#"UPDATE answers SET comment_count = comment_count + 1 WHERE id = $1"#,
+                )
+                .bind(answer_id)
+                .execute(pool.get_ref())
+                .await;
+            } else {
+                moderation::file_system_report(pool.get_ref(), "comment", comment.id, &outcome.describe()).await;
+            }
+
             let user = sqlx::query_as::<_, User>(
                 r#"SELECT * FROM users WHERE id = $1"#,
             )
@@ -167,7 +211,7 @@ pub async fn delete_comment(
 
     // Check ownership
     let comment = sqlx::query_as::<_, Comment>(
-        r#"SELECT * FROM comments WHERE id = $1 AND status = 'active'"#,
+        r#"SELECT * FROM comments WHERE id = $1 AND status IN ('active', 'pending')"#,
     )
     .bind(comment_id)
     .fetch_optional(pool.get_ref())
@@ -194,13 +238,15 @@ pub async fn delete_comment(
     .execute(pool.get_ref())
     .await;
 
-    // Update answer comment count
-    let _ = sqlx::query(
-        r#"UPDATE answers SET comment_count = GREATEST(0, comment_count - 1) WHERE id = $1"#,
-    )
-    .bind(comment.answer_id)
-    .execute(pool.get_ref())
-    .await;
+    // Update answer comment count (held comments were never counted)
+    if comment.status == "active" {
+        let _ = sqlx::query(
+            r#"UPDATE answers SET comment_count = GREATEST(0, comment_count - 1) WHERE id = $1"#,
+        )
+        .bind(comment.answer_id)
+        .execute(pool.get_ref())
+        .await;
+    }
 
     match result {
         Ok(_) => utils::no_content(),
@@ -0,0 +1,625 @@
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+
+use actix_web::{web, HttpRequest, HttpResponse};
+use sqlx::PgPool;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::auth;
+use crate::config::{Config, ContestConfig};
+use crate::db::visibility::answer_visible_to;
+use crate::handlers::answer::answers_with_details;
+use crate::handlers::badge::{self, BadgeEvent};
+use crate::handlers::challenge::insert_challenge;
+use crate::models::{
+    Answer, Challenge, ContestDetail, ContestEntry, ContestQueryParams, ContestStanding, ContestStandings,
+    ContestVote, ContestWithPhase, CreateChallengeRequest, CreateContestRequest, PaginationParams,
+};
+use crate::utils;
+
+/// How often the background task freezes the standings of contests that ended.
+const FINALIZER_INTERVAL: Duration = Duration::from_secs(60);
+
+const CONTEST_PHASES: &[&str] = &["upcoming", "open", "voting", "closed"];
+
+/// Contest row aliased `k` plus its phase and challenge count.
+const CONTEST_COLUMNS: &str = r#"
+    k.*,
+    CASE
+        WHEN NOW() < k.starts_at THEN 'upcoming'
+        WHEN NOW() < k.entries_close_at THEN 'open'
+        WHEN NOW() < k.ends_at THEN 'voting'
+        ELSE 'closed'
+    END AS phase,
+    (SELECT COUNT(*) FROM challenges kc WHERE kc.contest_id = k.id AND kc.status = 'active') AS challenge_count
+"#;
+
+/// Answers aliased `a` that count as contest entries: each user's published,
+/// non-private attempt.
+const ENTRY_FILTER: &str = "a.status = 'active' AND a.is_published AND a.visibility <> 'private'";
+
+pub async fn list_contests(
+    pool: web::Data<PgPool>,
+    config: web::Data<Config>,
+    query: web::Query<ContestQueryParams>,
+) -> HttpResponse {
+    let (page, page_size, offset) = utils::normalize_pagination(
+        query.page,
+        query.page_size,
+        config.pagination.default_page_size,
+        config.pagination.max_page_size,
+    );
+
+    if let Some(phase) = &query.phase {
+        if !CONTEST_PHASES.contains(&phase.as_str()) {
+            return utils::bad_request("phase must be upcoming, open, voting or closed");
+        }
+    }
+
+    let total: (i64,) = sqlx::query_as(&format!(
+        r#"SELECT COUNT(*) FROM (SELECT {} FROM contests k) c WHERE ($1::text IS NULL OR c.phase = $1)"#,
+        CONTEST_COLUMNS
+    ))
+    .bind(&query.phase)
+    .fetch_one(pool.get_ref())
+    .await
+    .unwrap_or((0,));
+
+    let result = sqlx::query_as::<_, ContestWithPhase>(&format!(
+        r#"
+        SELECT * FROM (SELECT {} FROM contests k) c
+        WHERE ($1::text IS NULL OR c.phase = $1)
+        ORDER BY c.starts_at DESC
+        LIMIT $2 OFFSET $3
+        "#,
+        CONTEST_COLUMNS
+    ))
+    .bind(&query.phase)
+    .bind(page_size)
+    .bind(offset)
+    .fetch_all(pool.get_ref())
+    .await;
+
+    match result {
+        Ok(contests) => utils::paginated(contests, page, page_size, total.0),
+        Err(e) => {
+            tracing::error!("Failed to fetch contests: {}", e);
+            utils::internal_error("Failed to fetch contests")
+        }
+    }
+}
+
+pub async fn create_contest(
+    pool: web::Data<PgPool>,
+    req: HttpRequest,
+    body: web::Json<CreateContestRequest>,
+) -> HttpResponse {
+    let user_id = match utils::get_user_id(&req) {
+        Some(id) => id,
+        None => return utils::unauthorized("User ID required"),
+    };
+    if let Err(resp) = auth::require_role(pool.get_ref(), user_id, auth::ADMIN_ROLES).await {
+        return resp;
+    }
+
+    if let Err(e) = body.validate() {
+        return utils::bad_request(&e.to_string());
+    }
+    if body.starts_at >= body.entries_close_at || body.entries_close_at >= body.ends_at {
+        return utils::bad_request("starts_at, entries_close_at and ends_at must be in order");
+    }
+
+    let result = sqlx::query_as::<_, ContestWithPhase>(&format!(
+        r#"
+        WITH k AS (
+            INSERT INTO contests (title, description, starts_at, entries_close_at, ends_at, created_by)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING *
+        )
+        SELECT {} FROM k
+        "#,
+        CONTEST_COLUMNS
+    ))
+    .bind(&body.title)
+    .bind(&body.description)
+    .bind(body.starts_at)
+    .bind(body.entries_close_at)
+    .bind(body.ends_at)
+    .bind(user_id)
+    .fetch_one(pool.get_ref())
+    .await;
+
+    match result {
+        Ok(contest) => utils::created(contest),
+        Err(e) => {
+            tracing::error!("Failed to create contest: {}", e);
+            utils::internal_error("Failed to create contest")
+        }
+    }
+}
+
+/// The contest and, once it has started, its challenges. Admins see the
+/// challenges ahead of time.
+pub async fn get_contest(
+    pool: web::Data<PgPool>,
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+) -> HttpResponse {
+    let contest_id = path.into_inner();
+
+    let contest = match fetch_contest(pool.get_ref(), contest_id).await {
+        Ok(c) => c,
+        Err(resp) => return resp,
+    };
+
+    let is_admin = match utils::get_user_id(&req) {
+        Some(id) => auth::require_role(pool.get_ref(), id, auth::ADMIN_ROLES).await.is_ok(),
+        None => false,
+    };
+
+    let challenges = if contest.phase != "upcoming" || is_admin {
+        let result = sqlx::query_as::<_, Challenge>(
+            r#"SELECT * FROM challenges WHERE contest_id = $1 AND status = 'active' ORDER BY created_at ASC"#,
+        )
+        .bind(contest_id)
+        .fetch_all(pool.get_ref())
+        .await;
+
+        match result {
+            Ok(c) => c,
+            Err(e) => {
+                tracing::error!("Failed to fetch contest challenges: {}", e);
+                return utils::internal_error("Failed to fetch contest");
+            }
+        }
+    } else {
+        Vec::new()
+    };
+
+    utils::success(ContestDetail { contest, challenges })
+}
+
+/// Adds a challenge to a contest. The set is fixed once the contest starts.
+pub async fn create_contest_challenge(
+    pool: web::Data<PgPool>,
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+    body: web::Json<CreateChallengeRequest>,
+) -> HttpResponse {
+    let contest_id = path.into_inner();
+    let user_id = match utils::get_user_id(&req) {
+        Some(id) => id,
+        None => return utils::unauthorized("User ID required"),
+    };
+    if let Err(resp) = auth::require_role(pool.get_ref(), user_id, auth::ADMIN_ROLES).await {
+        return resp;
+    }
+
+    let contest = match fetch_contest(pool.get_ref(), contest_id).await {
+        Ok(c) => c,
+        Err(resp) => return resp,
+    };
+    if contest.phase != "upcoming" {
+        return utils::conflict("Challenges can only be added before the contest starts");
+    }
+
+    insert_challenge(pool.get_ref(), &body, None, Some(contest_id)).await
+}
+
+/// Entries to the contest, hidden until it stops taking entries. Sorted by
+/// votes once voting is over.
+pub async fn list_contest_entries(
+    pool: web::Data<PgPool>,
+    config: web::Data<Config>,
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+    query: web::Query<PaginationParams>,
+) -> HttpResponse {
+    let contest_id = path.into_inner();
+    let current_user_id = utils::get_user_id(&req);
+    let (page, page_size, offset) = utils::normalize_pagination(
+        query.page,
+        query.page_size,
+        config.pagination.default_page_size,
+        config.pagination.max_page_size,
+    );
+
+    let contest = match fetch_contest(pool.get_ref(), contest_id).await {
+        Ok(c) => c,
+        Err(resp) => return resp,
+    };
+    if contest.phase == "upcoming" || contest.phase == "open" {
+        return utils::forbidden("Entries are hidden until the contest closes for entries");
+    }
+
+    let total: (i64,) = sqlx::query_as(&format!(
+        r#"
+        SELECT COUNT(*) FROM answers a
+        JOIN challenges ch ON ch.id = a.challenge_id
+        WHERE ch.contest_id = $1 AND {} AND {}
+        "#,
+        ENTRY_FILTER,
+        answer_visible_to("a", 2)
+    ))
+    .bind(contest_id)
+    .bind(current_user_id)
+    .fetch_one(pool.get_ref())
+    .await
+    .unwrap_or((0,));
+
+    // Voters see entries oldest first so the order doesn't sway the vote
+    let order_by = if contest.phase == "closed" {
+        "(SELECT COUNT(*) FROM contest_votes v WHERE v.answer_id = a.id) DESC, a.created_at ASC"
+    } else {
+        "a.created_at ASC"
+    };
+
+    let answers = sqlx::query_as::<_, Answer>(&format!(
+        r#"
+        SELECT a.* FROM answers a
+        JOIN challenges ch ON ch.id = a.challenge_id
+        WHERE ch.contest_id = $1 AND {} AND {}
+        ORDER BY {}
+        LIMIT $3 OFFSET $4
+        "#,
+        ENTRY_FILTER,
+        answer_visible_to("a", 2),
+        order_by
+    ))
+    .bind(contest_id)
+    .bind(current_user_id)
+    .bind(page_size)
+    .bind(offset)
+    .fetch_all(pool.get_ref())
+    .await;
+
+    let answers = match answers {
+        Ok(a) => a,
+        Err(e) => {
+            tracing::error!("Failed to fetch contest entries: {}", e);
+            return utils::internal_error("Failed to fetch contest entries");
+        }
+    };
+
+    let ids: Vec<Uuid> = answers.iter().map(|a| a.id).collect();
+    let votes: HashMap<Uuid, i64> = sqlx::query_as::<_, (Uuid, i64)>(
+        r#"SELECT answer_id, COUNT(*) FROM contest_votes WHERE answer_id = ANY($1) GROUP BY answer_id"#,
+    )
+    .bind(&ids)
+    .fetch_all(pool.get_ref())
+    .await
+    .unwrap_or_default()
+    .into_iter()
+    .collect();
+    let my_votes: HashSet<Uuid> = sqlx::query_scalar::<_, Uuid>(
+        r#"SELECT answer_id FROM contest_votes WHERE answer_id = ANY($1) AND voter_id = $2"#,
+    )
+    .bind(&ids)
+    .bind(current_user_id)
+    .fetch_all(pool.get_ref())
+    .await
+    .unwrap_or_default()
+    .into_iter()
+    .collect();
+
+    let entries: Vec<ContestEntry> = answers_with_details(pool.get_ref(), answers, current_user_id)
+        .await
+        .into_iter()
+        .map(|answer| ContestEntry {
+            vote_count: votes.get(&answer.answer.id).copied().unwrap_or(0),
+            is_voted: my_votes.contains(&answer.answer.id),
+            answer,
+        })
+        .collect();
+
+    utils::paginated(entries, page, page_size, total.0)
+}
+
+pub async fn vote_contest_entry(
+    pool: web::Data<PgPool>,
+    req: HttpRequest,
+    path: web::Path<(Uuid, Uuid)>,
+) -> HttpResponse {
+    let (contest_id, answer_id) = path.into_inner();
+    let user_id = match utils::get_user_id(&req) {
+        Some(id) => id,
+        None => return utils::unauthorized("User ID required"),
+    };
+
+    let author_id = match fetch_votable_entry(pool.get_ref(), contest_id, answer_id, user_id).await {
+        Ok(id) => id,
+        Err(resp) => return resp,
+    };
+    if author_id == user_id {
+        return utils::bad_request("You cannot vote for your own entry");
+    }
+
+    let result = sqlx::query_as::<_, ContestVote>(
+        r#"
+        INSERT INTO contest_votes (contest_id, answer_id, voter_id)
+        VALUES ($1, $2, $3)
+        ON CONFLICT (answer_id, voter_id) DO NOTHING
+        RETURNING *
+        "#,
+    )
+    .bind(contest_id)
+    .bind(answer_id)
+    .bind(user_id)
+    .fetch_optional(pool.get_ref())
+    .await;
+
+    match result {
+        Ok(Some(vote)) => utils::created(vote),
+        Ok(None) => utils::conflict("Already voted for this entry"),
+        Err(e) => {
+            tracing::error!("Failed to vote for contest entry: {}", e);
+            utils::internal_error("Failed to vote")
+        }
+    }
+}
+
+pub async fn unvote_contest_entry(
+    pool: web::Data<PgPool>,
+    req: HttpRequest,
+    path: web::Path<(Uuid, Uuid)>,
+) -> HttpResponse {
+    let (contest_id, answer_id) = path.into_inner();
+    let user_id = match utils::get_user_id(&req) {
+        Some(id) => id,
+        None => return utils::unauthorized("User ID required"),
+    };
+
+    if let Err(resp) = fetch_votable_entry(pool.get_ref(), contest_id, answer_id, user_id).await {
+        return resp;
+    }
+
+    let result = sqlx::query(r#"DELETE FROM contest_votes WHERE answer_id = $1 AND voter_id = $2"#)
+        .bind(answer_id)
+        .bind(user_id)
+        .execute(pool.get_ref())
+        .await;
+
+    match result {
+        Ok(r) if r.rows_affected() == 0 => utils::not_found("Vote not found"),
+        Ok(_) => utils::no_content(),
+        Err(e) => {
+            tracing::error!("Failed to remove contest vote: {}", e);
+            utils::internal_error("Failed to remove vote")
+        }
+    }
+}
+
+/// Provisional standings while voting is open; the frozen final standings
+/// once the contest has ended.
+pub async fn get_contest_standings(
+    pool: web::Data<PgPool>,
+    config: web::Data<Config>,
+    path: web::Path<Uuid>,
+) -> HttpResponse {
+    let contest_id = path.into_inner();
+
+    if let Err(e) = finalize_due_contests(pool.get_ref(), &config.contest).await {
+        tracing::error!("Failed to finalize contests: {}", e);
+    }
+
+    let contest = match fetch_contest(pool.get_ref(), contest_id).await {
+        Ok(c) => c,
+        Err(resp) => return resp,
+    };
+    if contest.phase == "upcoming" || contest.phase == "open" {
+        return utils::forbidden("Standings are hidden until the contest closes for entries");
+    }
+
+    let is_final = contest.contest.finalized_at.is_some();
+    let result = if is_final {
+        sqlx::query_as::<_, ContestStanding>(
+            r#"
+            SELECT s.rank, s.user_id, u.name AS user_name, u.avatar AS user_avatar,
+                s.total_score, s.ai_score, s.vote_count, s.entry_count
+            FROM contest_standings s
+            JOIN users u ON u.id = s.user_id
+            WHERE s.contest_id = $1
+            ORDER BY s.rank ASC, u.name ASC
+            "#,
+        )
+        .bind(contest_id)
+        .fetch_all(pool.get_ref())
+        .await
+    } else {
+        sqlx::query_as::<_, ContestStanding>(&format!(
+            r#"
+            SELECT st.*, u.name AS user_name, u.avatar AS user_avatar
+            FROM ({}) st
+            JOIN users u ON u.id = st.user_id
+            ORDER BY st.rank ASC, u.name ASC
+            "#,
+            standings_query()
+        ))
+        .bind(contest_id)
+        .bind(config.contest.ai_weight)
+        .fetch_all(pool.get_ref())
+        .await
+    };
+
+    match result {
+        Ok(standings) => utils::success(ContestStandings { is_final, standings }),
+        Err(e) => {
+            tracing::error!("Failed to fetch contest standings: {}", e);
+            utils::internal_error("Failed to fetch contest standings")
+        }
+    }
+}
+
+/// Rejects a submission or edit to a contest challenge outside its entry
+/// window. Challenges outside contests always pass.
+pub async fn ensure_entries_open(pool: &PgPool, challenge_id: Uuid) -> Result<(), HttpResponse> {
+    let open = sqlx::query_scalar::<_, bool>(
+        r#"
+        SELECT k.starts_at <= NOW() AND NOW() < k.entries_close_at
+        FROM challenges ch
+        JOIN contests k ON k.id = ch.contest_id
+        WHERE ch.id = $1
+        "#,
+    )
+    .bind(challenge_id)
+    .fetch_optional(pool)
+    .await;
+
+    match open {
+        Ok(Some(false)) => Err(utils::forbidden("This contest is not accepting entries")),
+        Ok(_) => Ok(()),
+        Err(e) => {
+            tracing::error!("Failed to check contest window: {}", e);
+            Err(utils::internal_error("Failed to check contest"))
+        }
+    }
+}
+
+/// Freezes the standings of every contest whose voting has ended and awards
+/// the winners' badges. Each contest is finalized exactly once.
+pub async fn finalize_due_contests(pool: &PgPool, config: &ContestConfig) -> Result<(), sqlx::Error> {
+    let due = sqlx::query_scalar::<_, Uuid>(
+        r#"SELECT id FROM contests WHERE finalized_at IS NULL AND ends_at <= NOW()"#,
+    )
+    .fetch_all(pool)
+    .await?;
+
+    for contest_id in due {
+        let mut tx = pool.begin().await?;
+
+        let claimed = sqlx::query(
+            r#"UPDATE contests SET finalized_at = NOW() WHERE id = $1 AND finalized_at IS NULL"#,
+        )
+        .bind(contest_id)
+        .execute(&mut *tx)
+        .await?;
+        if claimed.rows_affected() == 0 {
+            continue;
+        }
+
+        let placed = sqlx::query_scalar::<_, Uuid>(&format!(
+            r#"
+            INSERT INTO contest_standings (contest_id, user_id, rank, total_score, ai_score, vote_count, entry_count)
+            SELECT $1, st.user_id, st.rank, st.total_score, st.ai_score, st.vote_count, st.entry_count
+            FROM ({}) st
+            RETURNING user_id
+            "#,
+            standings_query()
+        ))
+        .bind(contest_id)
+        .bind(config.ai_weight)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        for user_id in placed {
+            badge::evaluate_badges(pool, user_id, BadgeEvent::ContestFinalized).await;
+        }
+    }
+
+    Ok(())
+}
+
+/// Keeps standings frozen on time even when nobody looks at the contest.
+pub fn spawn_finalizer(pool: PgPool, config: ContestConfig) {
+    actix_web::rt::spawn(async move {
+        let mut interval = actix_web::rt::time::interval(FINALIZER_INTERVAL);
+        loop {
+            interval.tick().await;
+            if let Err(e) = finalize_due_contests(&pool, &config).await {
+                tracing::error!("Failed to finalize contests: {}", e);
+            }
+        }
+    });
+}
+
+async fn fetch_contest(pool: &PgPool, contest_id: Uuid) -> Result<ContestWithPhase, HttpResponse> {
+    let result = sqlx::query_as::<_, ContestWithPhase>(&format!(
+        r#"SELECT {} FROM contests k WHERE k.id = $1"#,
+        CONTEST_COLUMNS
+    ))
+    .bind(contest_id)
+    .fetch_optional(pool)
+    .await;
+
+    match result {
+        Ok(Some(c)) => Ok(c),
+        Ok(None) => Err(utils::not_found("Contest not found")),
+        Err(e) => {
+            tracing::error!("Failed to fetch contest: {}", e);
+            Err(utils::internal_error("Failed to fetch contest"))
+        }
+    }
+}
+
+/// Checks the contest is in its voting window and the answer is one of its
+/// entries the user can see, returning the entry's author.
+async fn fetch_votable_entry(
+    pool: &PgPool,
+    contest_id: Uuid,
+    answer_id: Uuid,
+    user_id: Uuid,
+) -> Result<Uuid, HttpResponse> {
+    let contest = fetch_contest(pool, contest_id).await?;
+    if contest.phase != "voting" {
+        return Err(utils::bad_request("Voting is not open for this contest"));
+    }
+
+    let author = sqlx::query_scalar::<_, Uuid>(&format!(
+        r#"
+        SELECT a.user_id FROM answers a
+        JOIN challenges ch ON ch.id = a.challenge_id
+        WHERE a.id = $1 AND ch.contest_id = $2 AND {} AND {}
+        "#,
+        ENTRY_FILTER,
+        answer_visible_to("a", 3)
+    ))
+    .bind(answer_id)
+    .bind(contest_id)
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await;
+
+    match author {
+        Ok(Some(id)) => Ok(id),
+        Ok(None) => Err(utils::not_found("Entry not found")),
+        Err(e) => {
+            tracing::error!("Failed to fetch contest entry: {}", e);
+            Err(utils::internal_error("Failed to fetch contest entry"))
+        }
+    }
+}
+
+/// Per-user standings for the contest bound at `$1`, with the AI weight at
+/// `$2`. An entry scores its AI score blended with its votes scaled so the
+/// most-voted entry gets 100; a user's total sums their entries across the
+/// contest's challenges.
+fn standings_query() -> String {
+    format!(
+        r#"
+        WITH entries AS (
+            SELECT a.user_id, COALESCE(a.score, 0) AS ai_score,
+                (SELECT COUNT(*) FROM contest_votes v WHERE v.answer_id = a.id) AS votes
+            FROM answers a
+            JOIN challenges ch ON ch.id = a.challenge_id
+            WHERE ch.contest_id = $1 AND {}
+        ),
+        scored AS (
+            SELECT e.*,
+                $2::DOUBLE PRECISION * e.ai_score + (1 - $2::DOUBLE PRECISION) * 100.0 * e.votes / GREATEST((SELECT MAX(votes) FROM entries), 1) AS entry_score
+            FROM entries e
+        )
+        SELECT (RANK() OVER (ORDER BY SUM(s.entry_score) DESC))::INTEGER AS rank,
+            s.user_id,
+            SUM(s.entry_score)::DOUBLE PRECISION AS total_score,
+            SUM(s.ai_score)::INTEGER AS ai_score,
+            SUM(s.votes)::INTEGER AS vote_count,
+            COUNT(*)::INTEGER AS entry_count
+        FROM scored s
+        GROUP BY s.user_id
+        "#,
+        ENTRY_FILTER
+    )
+}
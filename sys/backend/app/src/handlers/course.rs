@@ -0,0 +1,511 @@
+use std::collections::HashSet;
+
+use actix_web::{web, HttpRequest, HttpResponse};
+use chrono::{DateTime, Utc};
+use sqlx::{FromRow, PgPool};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::auth;
+use crate::models::{
+    Challenge, Course, CourseCertificate, CourseDetail, CourseEnrollment, CourseResume, CourseStep,
+    CourseStepProgress, CourseSummary, CreateCourseRequest, EnrollmentWithCourse,
+};
+use crate::utils;
+
+/// Values stored in course_steps.unlock_rule
+const UNLOCK_SEQUENTIAL: &str = "sequential"; // after the previous step is completed
+const UNLOCK_OPEN: &str = "open"; // from the start
+
+#[derive(FromRow)]
+struct StepRow {
+    #[sqlx(flatten)]
+    step: CourseStep,
+    best_score: Option<i32>,
+}
+
+pub async fn list_courses(pool: web::Data<PgPool>) -> HttpResponse {
+    let result = sqlx::query_as::<_, CourseSummary>(
+        r#"
+        SELECT c.*, COUNT(s.id) AS step_count
+        FROM courses c
+        LEFT JOIN course_steps s ON s.course_id = c.id
+        WHERE c.status = 'active'
+        GROUP BY c.id
+        ORDER BY c.created_at DESC
+        "#,
+    )
+    .fetch_all(pool.get_ref())
+    .await;
+
+    match result {
+        Ok(courses) => utils::success(courses),
+        Err(e) => {
+            tracing::error!("Failed to fetch courses: {}", e);
+            utils::internal_error("Failed to fetch courses")
+        }
+    }
+}
+
+pub async fn create_course(
+    pool: web::Data<PgPool>,
+    req: HttpRequest,
+    body: web::Json<CreateCourseRequest>,
+) -> HttpResponse {
+    let user_id = match utils::get_user_id(&req) {
+        Some(id) => id,
+        None => return utils::unauthorized("User ID required"),
+    };
+    if let Err(resp) = auth::require_role(pool.get_ref(), user_id, auth::ADMIN_ROLES).await {
+        return resp;
+    }
+
+    if let Err(e) = body.validate() {
+        return utils::bad_request(&e.to_string());
+    }
+
+    let mut seen = HashSet::new();
+    for step in &body.steps {
+        if !seen.insert(step.challenge_id) {
+            return utils::bad_request("A challenge can appear only once in a course");
+        }
+        if let Some(rule) = &step.unlock_rule {
+            if rule != UNLOCK_SEQUENTIAL && rule != UNLOCK_OPEN {
+                return utils::bad_request("unlock_rule must be sequential or open");
+            }
+        }
+        if step.pass_score.is_some_and(|s| !(0..=100).contains(&s)) {
+            return utils::bad_request("pass_score must be between 0 and 100");
+        }
+    }
+
+    let challenge_ids: Vec<Uuid> = body.steps.iter().map(|s| s.challenge_id).collect();
+    let found = sqlx::query_scalar::<_, i64>(
+        r#"SELECT COUNT(*) FROM challenges WHERE id = ANY($1) AND status = 'active' AND organization_id IS NULL AND contest_id IS NULL"#,
+    )
+    .bind(&challenge_ids)
+    .fetch_one(pool.get_ref())
+    .await
+    .unwrap_or(0);
+
+    if found != challenge_ids.len() as i64 {
+        return utils::bad_request("Challenge not found");
+    }
+
+    let result: Result<Course, sqlx::Error> = async {
+        let mut tx = pool.begin().await?;
+
+        let course = sqlx::query_as::<_, Course>(
+            r#"
+            INSERT INTO courses (title, description, category_id, created_by)
+            VALUES ($1, $2, $3, $4)
+            RETURNING *
+            "#,
+        )
+        .bind(&body.title)
+        .bind(&body.description)
+        .bind(body.category_id)
+        .bind(user_id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        for (index, step) in body.steps.iter().enumerate() {
+            sqlx::query(
+                r#"
+                INSERT INTO course_steps (course_id, challenge_id, position, unlock_rule, pass_score)
+                VALUES ($1, $2, $3, $4, $5)
+                "#,
+            )
+            .bind(course.id)
+            .bind(step.challenge_id)
+            .bind(index as i32 + 1)
+            .bind(step.unlock_rule.as_deref().unwrap_or(UNLOCK_SEQUENTIAL))
+            .bind(step.pass_score)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(course)
+    }
+    .await;
+
+    match result {
+        Ok(course) => utils::created(course),
+        Err(e) => {
+            tracing::error!("Failed to create course: {}", e);
+            utils::internal_error("Failed to create course")
+        }
+    }
+}
+
+pub async fn get_course(
+    pool: web::Data<PgPool>,
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+) -> HttpResponse {
+    let course_id = path.into_inner();
+    let current_user_id = utils::get_user_id(&req);
+
+    let course = match fetch_course(pool.get_ref(), course_id).await {
+        Ok(c) => c,
+        Err(resp) => return resp,
+    };
+
+    let steps = match step_progress(pool.get_ref(), course_id, current_user_id).await {
+        Ok(s) => s,
+        Err(e) => {
+            tracing::error!("Failed to fetch course steps: {}", e);
+            return utils::internal_error("Failed to fetch course");
+        }
+    };
+
+    let enrollment = match current_user_id {
+        Some(uid) => fetch_enrollment(pool.get_ref(), course_id, uid).await.unwrap_or(None),
+        None => None,
+    };
+
+    utils::success(CourseDetail {
+        course,
+        steps,
+        enrollment,
+    })
+}
+
+pub async fn enroll_course(
+    pool: web::Data<PgPool>,
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+) -> HttpResponse {
+    let course_id = path.into_inner();
+    let user_id = match utils::get_user_id(&req) {
+        Some(id) => id,
+        None => return utils::unauthorized("User ID required"),
+    };
+
+    if let Err(resp) = fetch_course(pool.get_ref(), course_id).await {
+        return resp;
+    }
+
+    let result = sqlx::query_as::<_, CourseEnrollment>(
+        r#"
+        INSERT INTO course_enrollments (course_id, user_id)
+        VALUES ($1, $2)
+        ON CONFLICT (course_id, user_id) DO NOTHING
+        RETURNING *
+        "#,
+    )
+    .bind(course_id)
+    .bind(user_id)
+    .fetch_optional(pool.get_ref())
+    .await;
+
+    match result {
+        Ok(Some(_)) => {
+            // Challenges answered before enrolling count toward the course
+            refresh_course_progress(pool.get_ref(), user_id).await;
+            match fetch_enrollment(pool.get_ref(), course_id, user_id).await {
+                Ok(Some(enrollment)) => utils::created(enrollment),
+                _ => utils::internal_error("Failed to enroll"),
+            }
+        }
+        Ok(None) => utils::conflict("Already enrolled"),
+        Err(e) => {
+            tracing::error!("Failed to enroll: {}", e);
+            utils::internal_error("Failed to enroll")
+        }
+    }
+}
+
+/// Where to pick up an enrolled course: the first unlocked step not yet completed.
+pub async fn resume_course(
+    pool: web::Data<PgPool>,
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+) -> HttpResponse {
+    let course_id = path.into_inner();
+    let user_id = match utils::get_user_id(&req) {
+        Some(id) => id,
+        None => return utils::unauthorized("User ID required"),
+    };
+
+    let enrollment = match fetch_enrollment(pool.get_ref(), course_id, user_id).await {
+        Ok(Some(e)) => e,
+        Ok(None) => return utils::not_found("Not enrolled in this course"),
+        Err(e) => {
+            tracing::error!("Failed to fetch enrollment: {}", e);
+            return utils::internal_error("Failed to resume course");
+        }
+    };
+
+    let steps = match step_progress(pool.get_ref(), course_id, Some(user_id)).await {
+        Ok(s) => s,
+        Err(e) => {
+            tracing::error!("Failed to fetch course steps: {}", e);
+            return utils::internal_error("Failed to resume course");
+        }
+    };
+
+    let next_step = steps.into_iter().find(|s| s.unlocked && !s.completed);
+
+    utils::success(CourseResume {
+        enrollment,
+        next_step,
+    })
+}
+
+pub async fn list_enrollments(pool: web::Data<PgPool>, req: HttpRequest) -> HttpResponse {
+    let user_id = match utils::get_user_id(&req) {
+        Some(id) => id,
+        None => return utils::unauthorized("User ID required"),
+    };
+
+    let result = sqlx::query_as::<_, EnrollmentWithCourse>(
+        r#"
+        SELECT e.*, c.title AS course_title,
+            (SELECT COUNT(*) FROM course_steps s WHERE s.course_id = c.id) AS step_count
+        FROM course_enrollments e
+        JOIN courses c ON c.id = e.course_id
+        WHERE e.user_id = $1
+        ORDER BY e.completed_at IS NOT NULL, e.updated_at DESC
+        "#,
+    )
+    .bind(user_id)
+    .fetch_all(pool.get_ref())
+    .await;
+
+    match result {
+        Ok(enrollments) => utils::success(enrollments),
+        Err(e) => {
+            tracing::error!("Failed to fetch enrollments: {}", e);
+            utils::internal_error("Failed to fetch enrollments")
+        }
+    }
+}
+
+pub async fn list_certificates(pool: web::Data<PgPool>, req: HttpRequest) -> HttpResponse {
+    let user_id = match utils::get_user_id(&req) {
+        Some(id) => id,
+        None => return utils::unauthorized("User ID required"),
+    };
+
+    let result = sqlx::query_as::<_, CourseCertificate>(
+        r#"SELECT * FROM course_certificates WHERE user_id = $1 ORDER BY issued_at DESC"#,
+    )
+    .bind(user_id)
+    .fetch_all(pool.get_ref())
+    .await;
+
+    match result {
+        Ok(certificates) => utils::success(certificates),
+        Err(e) => {
+            tracing::error!("Failed to fetch certificates: {}", e);
+            utils::internal_error("Failed to fetch certificates")
+        }
+    }
+}
+
+/// Certificates are public records so they can be shared and verified by id.
+pub async fn get_certificate(pool: web::Data<PgPool>, path: web::Path<Uuid>) -> HttpResponse {
+    let certificate_id = path.into_inner();
+
+    let result = sqlx::query_as::<_, CourseCertificate>(
+        r#"SELECT * FROM course_certificates WHERE id = $1"#,
+    )
+    .bind(certificate_id)
+    .fetch_optional(pool.get_ref())
+    .await;
+
+    match result {
+        Ok(Some(certificate)) => utils::success(certificate),
+        Ok(None) => utils::not_found("Certificate not found"),
+        Err(e) => {
+            tracing::error!("Failed to fetch certificate: {}", e);
+            utils::internal_error("Failed to fetch certificate")
+        }
+    }
+}
+
+/// Recounts completed steps for the user's unfinished courses and issues a
+/// certificate for any course now complete. Called after answers and scores change.
+pub async fn refresh_course_progress(pool: &PgPool, user_id: Uuid) {
+    let enrollments = sqlx::query_as::<_, CourseEnrollment>(
+        r#"SELECT * FROM course_enrollments WHERE user_id = $1 AND completed_at IS NULL"#,
+    )
+    .bind(user_id)
+    .fetch_all(pool)
+    .await
+    .unwrap_or_default();
+
+    for enrollment in enrollments {
+        let steps = match step_progress(pool, enrollment.course_id, Some(user_id)).await {
+            Ok(s) => s,
+            Err(e) => {
+                tracing::error!("Failed to fetch course steps: {}", e);
+                continue;
+            }
+        };
+
+        let completed_steps = steps.iter().filter(|s| s.completed).count() as i32;
+        let finished = !steps.is_empty() && completed_steps as usize == steps.len();
+        if completed_steps == enrollment.completed_steps && !finished {
+            continue;
+        }
+
+        let _ = sqlx::query(
+            r#"
+            UPDATE course_enrollments
+            SET completed_steps = $1,
+                completed_at = CASE WHEN $2 THEN NOW() END,
+                updated_at = NOW()
+            WHERE id = $3
+            "#,
+        )
+        .bind(completed_steps)
+        .bind(finished)
+        .bind(enrollment.id)
+        .execute(pool)
+        .await;
+
+        if finished {
+            let scores: Vec<i32> = steps.iter().filter_map(|s| s.best_score).collect();
+            let average_score = if scores.is_empty() {
+                None
+            } else {
+                Some(scores.iter().map(|&s| f64::from(s)).sum::<f64>() / scores.len() as f64)
+            };
+
+            let result = sqlx::query(
+                r#"
+                INSERT INTO course_certificates (course_id, user_id, course_title, average_score)
+                SELECT id, $2, title, $3 FROM courses WHERE id = $1
+                ON CONFLICT (course_id, user_id) DO NOTHING
+                "#,
+            )
+            .bind(enrollment.course_id)
+            .bind(user_id)
+            .bind(average_score)
+            .execute(pool)
+            .await;
+
+            if let Err(e) = result {
+                tracing::error!("Failed to issue certificate: {}", e);
+            }
+        }
+    }
+}
+
+/// Steps in order with the user's progress. A step is completed by an answer
+/// to its challenge (scoring at least `pass_score`, when set) given once the
+/// step was unlocked, so answering ahead of a sequential step never counts.
+async fn step_progress(
+    pool: &PgPool,
+    course_id: Uuid,
+    user_id: Option<Uuid>,
+) -> Result<Vec<CourseStepProgress>, sqlx::Error> {
+    let rows = sqlx::query_as::<_, StepRow>(
+        r#"
+        SELECT s.*, done.best_score
+        FROM course_steps s
+        LEFT JOIN LATERAL (
+            SELECT MAX(a.score) AS best_score
+            FROM answers a
+            WHERE a.challenge_id = s.challenge_id AND a.user_id = $2 AND a.status <> 'deleted'
+        ) done ON TRUE
+        WHERE s.course_id = $1
+        ORDER BY s.position ASC
+        "#,
+    )
+    .bind(course_id)
+    .bind(user_id)
+    .fetch_all(pool)
+    .await?;
+
+    let challenge_ids: Vec<Uuid> = rows.iter().map(|r| r.step.challenge_id).collect();
+    let challenges = sqlx::query_as::<_, Challenge>(
+        r#"SELECT * FROM challenges WHERE id = ANY($1)"#,
+    )
+    .bind(&challenge_ids)
+    .fetch_all(pool)
+    .await?;
+
+    let attempts = sqlx::query_as::<_, (Uuid, DateTime<Utc>, Option<i32>)>(
+        r#"
+        SELECT challenge_id, created_at, score FROM answers
+        WHERE user_id = $1 AND challenge_id = ANY($2) AND status <> 'deleted'
+        ORDER BY created_at ASC
+        "#,
+    )
+    .bind(user_id)
+    .bind(&challenge_ids)
+    .fetch_all(pool)
+    .await?;
+
+    let mut steps: Vec<CourseStepProgress> = Vec::new();
+    // When the previous step was completed, which is when a sequential step unlocks
+    let mut previous_completed_at = Some(DateTime::<Utc>::MIN_UTC);
+    for row in rows {
+        let challenge = match challenges.iter().find(|c| c.id == row.step.challenge_id) {
+            Some(c) => c.clone(),
+            None => continue,
+        };
+        let unlocked_at = if row.step.unlock_rule == UNLOCK_OPEN {
+            Some(DateTime::<Utc>::MIN_UTC)
+        } else {
+            previous_completed_at
+        };
+        let completed_at = unlocked_at.and_then(|since| {
+            attempts
+                .iter()
+                .find(|(challenge_id, created_at, score)| {
+                    *challenge_id == row.step.challenge_id
+                        && *created_at >= since
+                        && row.step.pass_score.is_none_or(|pass| score.is_some_and(|s| s >= pass))
+                })
+                .map(|(_, created_at, _)| *created_at)
+        });
+        previous_completed_at = completed_at;
+
+        steps.push(CourseStepProgress {
+            step: row.step,
+            challenge,
+            best_score: row.best_score,
+            completed: completed_at.is_some(),
+            unlocked: unlocked_at.is_some(),
+        });
+    }
+
+    Ok(steps)
+}
+
+async fn fetch_course(pool: &PgPool, course_id: Uuid) -> Result<Course, HttpResponse> {
+    let course = sqlx::query_as::<_, Course>(
+        r#"SELECT * FROM courses WHERE id = $1 AND status = 'active'"#,
+    )
+    .bind(course_id)
+    .fetch_optional(pool)
+    .await;
+
+    match course {
+        Ok(Some(c)) => Ok(c),
+        Ok(None) => Err(utils::not_found("Course not found")),
+        Err(e) => {
+            tracing::error!("Failed to fetch course: {}", e);
+            Err(utils::internal_error("Failed to fetch course"))
+        }
+    }
+}
+
+async fn fetch_enrollment(
+    pool: &PgPool,
+    course_id: Uuid,
+    user_id: Uuid,
+) -> Result<Option<CourseEnrollment>, sqlx::Error> {
+    sqlx::query_as::<_, CourseEnrollment>(
+        r#"SELECT * FROM course_enrollments WHERE course_id = $1 AND user_id = $2"#,
+    )
+    .bind(course_id)
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await
+}
@@ -0,0 +1,168 @@
+use std::time::Duration;
+
+use actix_web::{web, HttpRequest, HttpResponse};
+use chrono::NaiveDate;
+use sqlx::PgPool;
+
+use crate::auth;
+use crate::config::{Config, DailyConfig};
+use crate::models::{Challenge, DailyScheduleEntry};
+use crate::utils;
+
+/// How often the background task tops up the schedule.
+const SCHEDULER_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Upcoming daily sets, for checking the rotation ahead of time.
+pub async fn get_daily_schedule(
+    pool: web::Data<PgPool>,
+    config: web::Data<Config>,
+    req: HttpRequest,
+) -> HttpResponse {
+    let user_id = match utils::get_user_id(&req) {
+        Some(id) => id,
+        None => return utils::unauthorized("User ID required"),
+    };
+    if let Err(resp) = auth::require_role(pool.get_ref(), user_id, auth::ADMIN_ROLES).await {
+        return resp;
+    }
+
+    if let Err(e) = ensure_schedule(pool.get_ref(), &config.daily).await {
+        tracing::error!("Failed to generate daily schedule: {}", e);
+        return utils::internal_error("Failed to fetch daily schedule");
+    }
+
+    let result = sqlx::query_as::<_, DailyScheduleEntry>(
+        r#"
+        SELECT d.schedule_date, ch.*
+        FROM daily_challenges d
+        JOIN challenges ch ON ch.id = d.challenge_id
+        JOIN categories c ON c.id = d.category_id
+        WHERE d.schedule_date >= (NOW() AT TIME ZONE $1)::date
+        ORDER BY d.schedule_date ASC, c.sort_order ASC
+        "#,
+    )
+    .bind(&config.daily.timezone)
+    .fetch_all(pool.get_ref())
+    .await;
+
+    match result {
+        Ok(entries) => utils::success(entries),
+        Err(e) => {
+            tracing::error!("Failed to fetch daily schedule: {}", e);
+            utils::internal_error("Failed to fetch daily schedule")
+        }
+    }
+}
+
+/// Today's set in the configured timezone, one challenge per active category.
+/// Generates the day on demand if the background task hasn't yet; gaps in a
+/// day that already exists are left for the scheduler to repair.
+pub async fn todays_challenges(pool: &PgPool, config: &DailyConfig) -> Result<Vec<Challenge>, sqlx::Error> {
+    let challenges = fetch_todays_challenges(pool, config).await?;
+    if !challenges.is_empty() {
+        return Ok(challenges);
+    }
+
+    let scheduled = sqlx::query_scalar::<_, bool>(
+        r#"SELECT EXISTS(SELECT 1 FROM daily_challenges WHERE schedule_date = (NOW() AT TIME ZONE $1)::date)"#,
+    )
+    .bind(&config.timezone)
+    .fetch_one(pool)
+    .await?;
+
+    if scheduled {
+        return Ok(challenges);
+    }
+
+    ensure_schedule(pool, config).await?;
+    fetch_todays_challenges(pool, config).await
+}
+
+/// Fills in any missing days from today through the configured horizon.
+/// Entries whose challenge has since been retired are replaced.
+pub async fn ensure_schedule(pool: &PgPool, config: &DailyConfig) -> Result<(), sqlx::Error> {
+    let today = sqlx::query_scalar::<_, NaiveDate>(r#"SELECT (NOW() AT TIME ZONE $1)::date"#)
+        .bind(&config.timezone)
+        .fetch_one(pool)
+        .await?;
+
+    sqlx::query(
+        r#"
+        DELETE FROM daily_challenges d
+        USING challenges ch
+        WHERE ch.id = d.challenge_id AND d.schedule_date >= $1
+          AND (ch.status <> 'active' OR ch.organization_id IS NOT NULL OR ch.contest_id IS NOT NULL)
+        "#,
+    )
+    .bind(today)
+    .execute(pool)
+    .await?;
+
+    for offset in 0..config.schedule_days.max(1) {
+        let date = today + chrono::Duration::days(i64::from(offset));
+
+        // A challenge released for this very date wins; otherwise the oldest
+        // never-scheduled one. When the pool runs dry, the challenge that
+        // was shown longest ago comes back around.
+        sqlx::query(
+            r#"
+            INSERT INTO daily_challenges (schedule_date, category_id, challenge_id)
+            SELECT $1, c.id, pick.id
+            FROM categories c
+            CROSS JOIN LATERAL (
+                SELECT ch.id
+                FROM challenges ch
+                LEFT JOIN LATERAL (
+                    SELECT MAX(d.schedule_date) AS last_date
+                    FROM daily_challenges d
+                    WHERE d.challenge_id = ch.id AND d.schedule_date < $1
+                ) used ON TRUE
+                WHERE ch.category_id = c.id AND ch.status = 'active' AND ch.organization_id IS NULL AND ch.contest_id IS NULL
+                  AND (ch.release_date IS NULL OR ch.release_date <= $1)
+                ORDER BY
+                    (ch.release_date = $1) IS TRUE DESC,
+                    used.last_date ASC NULLS FIRST,
+                    ch.release_date ASC NULLS LAST,
+                    ch.created_at ASC
+                LIMIT 1
+            ) pick
+            WHERE c.status = 'active'
+            ON CONFLICT (schedule_date, category_id) DO NOTHING
+            "#,
+        )
+        .bind(date)
+        .execute(pool)
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// Keeps the schedule generated ahead of time so day rollover never waits on it.
+pub fn spawn_scheduler(pool: PgPool, config: DailyConfig) {
+    actix_web::rt::spawn(async move {
+        let mut interval = actix_web::rt::time::interval(SCHEDULER_INTERVAL);
+        loop {
+            interval.tick().await;
+            if let Err(e) = ensure_schedule(&pool, &config).await {
+                tracing::error!("Failed to generate daily schedule: {}", e);
+            }
+        }
+    });
+}
+
+async fn fetch_todays_challenges(pool: &PgPool, config: &DailyConfig) -> Result<Vec<Challenge>, sqlx::Error> {
+    sqlx::query_as::<_, Challenge>(
+        r#"
+        SELECT ch.* FROM daily_challenges d
+        JOIN challenges ch ON ch.id = d.challenge_id
+        JOIN categories c ON c.id = d.category_id
+        WHERE d.schedule_date = (NOW() AT TIME ZONE $1)::date
+          AND c.status = 'active' AND ch.status = 'active'
+        ORDER BY c.sort_order ASC
+        "#,
+    )
+    .bind(&config.timezone)
+    .fetch_all(pool)
+    .await
+}
@@ -0,0 +1,261 @@
+use actix_web::{web, HttpRequest, HttpResponse};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::auth;
+use crate::config::Config;
+use crate::models::{AdaptiveChallenge, AdaptiveQueryParams, Challenge, UpdateDifficultyRequest};
+use crate::utils;
+
+/// Difficulty tiers, easiest first.
+pub const DIFFICULTY_TIERS: &[&str] = &["easy", "normal", "hard"];
+
+/// Hard mode defaults to this share of the normal char limit.
+const HARD_MODE_LIMIT_PERCENT: i32 = 60;
+
+/// Scored answers a challenge needs before its tier is derived from them.
+const DERIVED_MIN_SAMPLES: i64 = 5;
+/// Average scores at or above this make a challenge easy, below HARD_AVERAGE_SCORE hard.
+const EASY_AVERAGE_SCORE: f64 = 80.0;
+const HARD_AVERAGE_SCORE: f64 = 60.0;
+
+/// Recent scored answers per category that make up a user's skill score.
+const SKILL_WINDOW: i64 = 10;
+/// Skill scores at or above these move the user up a tier, and finally into hard mode.
+const NORMAL_SKILL_SCORE: f64 = 60.0;
+const HARD_SKILL_SCORE: f64 = 80.0;
+const HARD_MODE_SKILL_SCORE: f64 = 90.0;
+
+/// SQL rank of a challenge's effective tier (0 easy .. 2 hard).
+const TIER_RANK: &str = r#"
+    CASE COALESCE(ch.difficulty, ch.derived_difficulty)
+        WHEN 'easy' THEN 0 WHEN 'hard' THEN 2 ELSE 1
+    END
+"#;
+
+/// Sets the manual tier and hard-mode limit. A null difficulty falls back to the derived tier.
+pub async fn update_challenge_difficulty(
+    pool: web::Data<PgPool>,
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+    body: web::Json<UpdateDifficultyRequest>,
+) -> HttpResponse {
+    let challenge_id = path.into_inner();
+    let user_id = match utils::get_user_id(&req) {
+        Some(id) => id,
+        None => return utils::unauthorized("User ID required"),
+    };
+    if let Err(resp) = auth::require_role(pool.get_ref(), user_id, auth::ADMIN_ROLES).await {
+        return resp;
+    }
+
+    if let Some(difficulty) = &body.difficulty {
+        if !DIFFICULTY_TIERS.contains(&difficulty.as_str()) {
+            return utils::bad_request("difficulty must be easy, normal or hard");
+        }
+    }
+
+    let challenge = sqlx::query_as::<_, Challenge>(
+        r#"SELECT * FROM challenges WHERE id = $1 AND status = 'active'"#,
+    )
+    .bind(challenge_id)
+    .fetch_optional(pool.get_ref())
+    .await;
+
+    let challenge = match challenge {
+        Ok(Some(c)) => c,
+        Ok(None) => return utils::not_found("Challenge not found"),
+        Err(e) => {
+            tracing::error!("Failed to fetch challenge: {}", e);
+            return utils::internal_error("Failed to update difficulty");
+        }
+    };
+
+    if let Some(Some(limit)) = body.hard_mode_char_limit {
+        if limit < 1 || limit >= challenge.char_limit {
+            return utils::bad_request("hard_mode_char_limit must be between 1 and char_limit - 1");
+        }
+    }
+
+    let result = sqlx::query_as::<_, Challenge>(
+        r#"
+        UPDATE challenges
+        SET difficulty = $1,
+            hard_mode_char_limit = CASE WHEN $2 THEN $3 ELSE hard_mode_char_limit END,
+            updated_at = NOW()
+        WHERE id = $4
+        RETURNING *
+        "#,
+    )
+    .bind(&body.difficulty)
+    .bind(body.hard_mode_char_limit.is_some())
+    .bind(body.hard_mode_char_limit.flatten())
+    .bind(challenge_id)
+    .fetch_one(pool.get_ref())
+    .await;
+
+    match result {
+        Ok(challenge) => utils::success(challenge),
+        Err(e) => {
+            tracing::error!("Failed to update difficulty: {}", e);
+            utils::internal_error("Failed to update difficulty")
+        }
+    }
+}
+
+/// One challenge per category pitched at the user's level. The tier rises with
+/// the average of their recent scores in that category, and top scorers are
+/// served the hard-mode limit. Anonymous users get normal challenges.
+pub async fn get_adaptive_challenges(
+    pool: web::Data<PgPool>,
+    config: web::Data<Config>,
+    req: HttpRequest,
+    query: web::Query<AdaptiveQueryParams>,
+) -> HttpResponse {
+    let current_user_id = utils::get_user_id(&req);
+
+    let categories = sqlx::query_as::<_, (Uuid, Option<f64>)>(
+        r#"
+        SELECT c.id, skill.score
+        FROM categories c
+        LEFT JOIN (
+            SELECT category_id, AVG(score)::DOUBLE PRECISION AS score
+            FROM (
+                SELECT ch.category_id, a.score,
+                    ROW_NUMBER() OVER (PARTITION BY ch.category_id ORDER BY a.created_at DESC) AS rn
+                FROM answers a
+                JOIN challenges ch ON ch.id = a.challenge_id
+                WHERE a.user_id = $1 AND a.status <> 'deleted' AND a.score IS NOT NULL
+            ) recent
+            WHERE rn <= $2
+            GROUP BY category_id
+        ) skill ON skill.category_id = c.id
+        WHERE c.status = 'active' AND ($3::uuid IS NULL OR c.id = $3)
+        ORDER BY c.sort_order ASC
+        "#,
+    )
+    .bind(current_user_id)
+    .bind(SKILL_WINDOW)
+    .bind(query.category_id)
+    .fetch_all(pool.get_ref())
+    .await;
+
+    let categories = match categories {
+        Ok(c) => c,
+        Err(e) => {
+            tracing::error!("Failed to fetch skill scores: {}", e);
+            return utils::internal_error("Failed to fetch challenges");
+        }
+    };
+
+    let mut results: Vec<AdaptiveChallenge> = Vec::new();
+    for (category_id, skill_score) in categories {
+        let target = target_tier(skill_score);
+
+        // Closest tier to the target first, unanswered before answered
+        let challenge = sqlx::query_as::<_, Challenge>(&format!(
+            r#"
+            SELECT ch.* FROM challenges ch
+            WHERE ch.category_id = $1 AND ch.status = 'active' AND ch.organization_id IS NULL AND ch.contest_id IS NULL
+              AND (ch.release_date IS NULL OR ch.release_date <= (NOW() AT TIME ZONE $4)::date)
+            ORDER BY
+                ABS(({}) - $2),
+                ($3::uuid IS NOT NULL AND EXISTS (
+                    SELECT 1 FROM answers a
+                    WHERE a.challenge_id = ch.id AND a.user_id = $3 AND a.status <> 'deleted'
+                )) ASC,
+                ch.release_date DESC NULLS LAST, ch.created_at DESC
+            LIMIT 1
+            "#,
+            TIER_RANK
+        ))
+        .bind(category_id)
+        .bind(target as i32)
+        .bind(current_user_id)
+        .bind(&config.daily.timezone)
+        .fetch_optional(pool.get_ref())
+        .await;
+
+        match challenge {
+            Ok(Some(challenge)) => {
+                let hard_mode = skill_score.is_some_and(|s| s >= HARD_MODE_SKILL_SCORE);
+                let effective_char_limit = if hard_mode {
+                    hard_mode_char_limit(&challenge)
+                } else {
+                    challenge.char_limit
+                };
+                results.push(AdaptiveChallenge {
+                    effective_difficulty: effective_difficulty(&challenge).to_string(),
+                    hard_mode,
+                    effective_char_limit,
+                    skill_score,
+                    challenge,
+                });
+            }
+            Ok(None) => {}
+            Err(e) => {
+                tracing::error!("Failed to fetch adaptive challenge: {}", e);
+                return utils::internal_error("Failed to fetch challenges");
+            }
+        }
+    }
+
+    utils::success(results)
+}
+
+/// Char limit for hard-mode answers: the configured one, or a share of the normal limit.
+pub fn hard_mode_char_limit(challenge: &Challenge) -> i32 {
+    challenge
+        .hard_mode_char_limit
+        .unwrap_or((challenge.char_limit * HARD_MODE_LIMIT_PERCENT / 100).max(1))
+}
+
+pub fn effective_difficulty(challenge: &Challenge) -> &str {
+    challenge
+        .difficulty
+        .as_deref()
+        .unwrap_or(&challenge.derived_difficulty)
+}
+
+/// Re-derives tiers from the average of normal-mode scores, for one challenge
+/// or all of them. Challenges with too few scores stay normal.
+pub async fn refresh_derived_difficulty(pool: &PgPool, challenge_id: Option<Uuid>) {
+    let result = sqlx::query(
+        r#"
+        UPDATE challenges ch
+        SET derived_difficulty = CASE
+            WHEN s.samples < $1 THEN 'normal'
+            WHEN s.average >= $2 THEN 'easy'
+            WHEN s.average < $3 THEN 'hard'
+            ELSE 'normal'
+        END
+        FROM (
+            SELECT challenge_id, COUNT(*) AS samples, AVG(score)::DOUBLE PRECISION AS average
+            FROM answers
+            WHERE status <> 'deleted' AND score IS NOT NULL AND NOT hard_mode
+            GROUP BY challenge_id
+        ) s
+        WHERE s.challenge_id = ch.id AND ($4::uuid IS NULL OR ch.id = $4)
+        "#,
+    )
+    .bind(DERIVED_MIN_SAMPLES)
+    .bind(EASY_AVERAGE_SCORE)
+    .bind(HARD_AVERAGE_SCORE)
+    .bind(challenge_id)
+    .execute(pool)
+    .await;
+
+    if let Err(e) = result {
+        tracing::error!("Failed to derive challenge difficulty: {}", e);
+    }
+}
+
+/// Tier index to serve for a skill score; no scores yet means normal.
+fn target_tier(skill_score: Option<f64>) -> usize {
+    match skill_score {
+        Some(s) if s >= HARD_SKILL_SCORE => 2,
+        Some(s) if s >= NORMAL_SKILL_SCORE => 1,
+        Some(_) => 0,
+        None => 1,
+    }
+}
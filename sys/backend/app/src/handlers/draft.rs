@@ -0,0 +1,288 @@
+use actix_web::{http::StatusCode, web, HttpRequest, HttpResponse};
+use sqlx::PgPool;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::config::Config;
+use crate::content_filter::FilterPipeline;
+use crate::db::visibility::challenge_visible_to;
+use crate::handlers::answer::{submit_answer, AnswerSubmission};
+use crate::models::{Draft, DraftWithCount, PublishDraftRequest, SaveDraftRequest};
+use crate::utils;
+
+pub async fn list_drafts(pool: web::Data<PgPool>, req: HttpRequest) -> HttpResponse {
+    let user_id = match utils::get_user_id(&req) {
+        Some(id) => id,
+        None => return utils::unauthorized("User ID required"),
+    };
+
+    let drafts = sqlx::query_as::<_, Draft>(
+        r#"
+        SELECT * FROM drafts
+        WHERE user_id = $1
+        ORDER BY updated_at DESC
+        "#,
+    )
+    .bind(user_id)
+    .fetch_all(pool.get_ref())
+    .await;
+
+    let drafts = match drafts {
+        Ok(d) => d,
+        Err(e) => {
+            tracing::error!("Failed to fetch drafts: {}", e);
+            return utils::internal_error("Failed to fetch drafts");
+        }
+    };
+
+    let mut results: Vec<DraftWithCount> = Vec::new();
+    for draft in drafts {
+        if let Some(char_limit) = fetch_char_limit(pool.get_ref(), draft.challenge_id, user_id).await {
+            results.push(with_count(draft, char_limit));
+        }
+    }
+
+    utils::success(results)
+}
+
+pub async fn get_draft(
+    pool: web::Data<PgPool>,
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+) -> HttpResponse {
+    let challenge_id = path.into_inner();
+    let user_id = match utils::get_user_id(&req) {
+        Some(id) => id,
+        None => return utils::unauthorized("User ID required"),
+    };
+
+    let char_limit = match fetch_char_limit(pool.get_ref(), challenge_id, user_id).await {
+        Some(limit) => limit,
+        None => return utils::not_found("Challenge not found"),
+    };
+
+    match fetch_draft(pool.get_ref(), user_id, challenge_id).await {
+        Ok(Some(draft)) => utils::success(with_count(draft, char_limit)),
+        Ok(None) => utils::not_found("Draft not found"),
+        Err(e) => {
+            tracing::error!("Failed to fetch draft: {}", e);
+            utils::internal_error("Failed to fetch draft")
+        }
+    }
+}
+
+/// Autosaves a draft. Creating one takes no version; updating one must send the
+/// version it was based on, and a stale version gets a 409 with the current draft.
+/// Over-limit content is saved and flagged rather than rejected.
+pub async fn save_draft(
+    pool: web::Data<PgPool>,
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+    body: web::Json<SaveDraftRequest>,
+) -> HttpResponse {
+    let challenge_id = path.into_inner();
+    let user_id = match utils::get_user_id(&req) {
+        Some(id) => id,
+        None => return utils::unauthorized("User ID required"),
+    };
+
+    if let Err(e) = body.validate() {
+        return utils::bad_request(&e.to_string());
+    }
+
+    let char_limit = match fetch_char_limit(pool.get_ref(), challenge_id, user_id).await {
+        Some(limit) => limit,
+        None => return utils::not_found("Challenge not found"),
+    };
+
+    let result = match body.version {
+        None => {
+            sqlx::query_as::<_, Draft>(
+                r#"
+                INSERT INTO drafts (user_id, challenge_id, content)
+                VALUES ($1, $2, $3)
+                ON CONFLICT (user_id, challenge_id) DO NOTHING
+                RETURNING *
+                "#,
+            )
+            .bind(user_id)
+            .bind(challenge_id)
+            .bind(&body.content)
+            .fetch_optional(pool.get_ref())
+            .await
+        }
+        Some(version) => {
+            sqlx::query_as::<_, Draft>(
+                r#"
+                UPDATE drafts
+                SET content = $1, version = version + 1, updated_at = NOW()
+                WHERE user_id = $2 AND challenge_id = $3 AND version = $4
+                RETURNING *
+                "#,
+            )
+            .bind(&body.content)
+            .bind(user_id)
+            .bind(challenge_id)
+            .bind(version)
+            .fetch_optional(pool.get_ref())
+            .await
+        }
+    };
+
+    match result {
+        Ok(Some(draft)) => utils::success(with_count(draft, char_limit)),
+        Ok(None) => match fetch_draft(pool.get_ref(), user_id, challenge_id).await {
+            Ok(Some(current)) => utils::conflict_with(
+                "Draft was changed on another device",
+                with_count(current, char_limit),
+            ),
+            Ok(None) => utils::not_found("Draft not found"),
+            Err(e) => {
+                tracing::error!("Failed to fetch draft: {}", e);
+                utils::internal_error("Failed to save draft")
+            }
+        },
+        Err(e) => {
+            tracing::error!("Failed to save draft: {}", e);
+            utils::internal_error("Failed to save draft")
+        }
+    }
+}
+
+pub async fn delete_draft(
+    pool: web::Data<PgPool>,
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+) -> HttpResponse {
+    let challenge_id = path.into_inner();
+    let user_id = match utils::get_user_id(&req) {
+        Some(id) => id,
+        None => return utils::unauthorized("User ID required"),
+    };
+
+    let result = sqlx::query(
+        r#"DELETE FROM drafts WHERE user_id = $1 AND challenge_id = $2"#,
+    )
+    .bind(user_id)
+    .bind(challenge_id)
+    .execute(pool.get_ref())
+    .await;
+
+    match result {
+        Ok(res) if res.rows_affected() > 0 => utils::no_content(),
+        Ok(_) => utils::not_found("Draft not found"),
+        Err(e) => {
+            tracing::error!("Failed to delete draft: {}", e);
+            utils::internal_error("Failed to delete draft")
+        }
+    }
+}
+
+/// Submits the draft as a new attempt and removes it once the answer is stored.
+/// The client must send the version it last saw; a stale version gets a 409
+/// with the current draft, as with [`save_draft`].
+pub async fn publish_draft(
+    pool: web::Data<PgPool>,
+    config: web::Data<Config>,
+    content_filter: web::Data<FilterPipeline>,
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+    body: web::Json<PublishDraftRequest>,
+) -> HttpResponse {
+    let challenge_id = path.into_inner();
+    let user_id = match utils::get_user_id(&req) {
+        Some(id) => id,
+        None => return utils::unauthorized("User ID required"),
+    };
+
+    let draft = match fetch_draft(pool.get_ref(), user_id, challenge_id).await {
+        Ok(Some(d)) => d,
+        Ok(None) => return utils::not_found("Draft not found"),
+        Err(e) => {
+            tracing::error!("Failed to fetch draft: {}", e);
+            return utils::internal_error("Failed to publish draft");
+        }
+    };
+
+    if body.version != draft.version {
+        let char_limit = fetch_char_limit(pool.get_ref(), challenge_id, user_id).await.unwrap_or(0);
+        return utils::conflict_with(
+            "Draft was changed on another device",
+            with_count(draft, char_limit),
+        );
+    }
+
+    if draft.content.trim().is_empty() {
+        return utils::bad_request("Draft is empty");
+    }
+
+    let submission = AnswerSubmission {
+        content: &draft.content,
+        visibility: body.visibility.as_deref(),
+        hard_mode: body.hard_mode.unwrap_or(false),
+        session_id: body.session_id,
+    };
+    let response = submit_answer(
+        pool.get_ref(),
+        &content_filter,
+        &config,
+        user_id,
+        challenge_id,
+        submission,
+    )
+    .await;
+
+    if response.status() == StatusCode::CREATED {
+        // Keep the draft if another device saved over it meanwhile
+        let _ = sqlx::query(
+            r#"DELETE FROM drafts WHERE id = $1 AND version = $2"#,
+        )
+        .bind(draft.id)
+        .bind(draft.version)
+        .execute(pool.get_ref())
+        .await;
+    }
+
+    response
+}
+
+async fn fetch_draft(
+    pool: &PgPool,
+    user_id: Uuid,
+    challenge_id: Uuid,
+) -> Result<Option<Draft>, sqlx::Error> {
+    sqlx::query_as::<_, Draft>(
+        r#"SELECT * FROM drafts WHERE user_id = $1 AND challenge_id = $2"#,
+    )
+    .bind(user_id)
+    .bind(challenge_id)
+    .fetch_optional(pool)
+    .await
+}
+
+/// The char limit of an active challenge the user can see, or `None` when
+/// they cannot answer it.
+async fn fetch_char_limit(pool: &PgPool, challenge_id: Uuid, user_id: Uuid) -> Option<i32> {
+    sqlx::query_scalar::<_, i32>(&format!(
+        r#"SELECT c.char_limit FROM challenges c WHERE c.id = $1 AND c.status = 'active' AND {}"#,
+        challenge_visible_to("c", 2)
+    ))
+    .bind(challenge_id)
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await
+    .ok()
+    .flatten()
+}
+
+/// Counts characters the same way answer submission does.
+fn with_count(draft: Draft, char_limit: i32) -> DraftWithCount {
+    let char_count = draft.content.chars().count() as i32;
+    DraftWithCount {
+        draft,
+        char_count,
+        char_limit,
+        remaining: char_limit - char_count,
+        over_limit: char_count > char_limit,
+    }
+}
@@ -0,0 +1,269 @@
+use std::collections::HashMap;
+
+use actix_web::{web, HttpRequest, HttpResponse};
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::auth;
+use crate::db::visibility::{answer_listed_for, answer_visible_to};
+use crate::handlers::answer::answers_with_details;
+use crate::models::{Answer, Challenge, EditorialPick, FeaturedAnswer, PickAnswerRequest, SetExampleAnswerRequest};
+use crate::utils;
+
+/// Most picks shown in a challenge's featured section.
+const FEATURED_LIMIT: i64 = 10;
+
+/// Marks an answer as an お手本 pick for its challenge. Only answers anyone
+/// can see qualify, so the featured section never exposes restricted ones.
+pub async fn pick_answer(
+    pool: web::Data<PgPool>,
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+    body: web::Json<PickAnswerRequest>,
+) -> HttpResponse {
+    let answer_id = path.into_inner();
+    let user_id = match utils::get_user_id(&req) {
+        Some(id) => id,
+        None => return utils::unauthorized("User ID required"),
+    };
+    if let Err(resp) = auth::require_role(pool.get_ref(), user_id, auth::EDITORIAL_ROLES).await {
+        return resp;
+    }
+
+    if let Err(e) = body.validate() {
+        return utils::bad_request(&e.to_string());
+    }
+
+    // Checked as an anonymous viewer: public, published attempts only
+    let challenge_id = sqlx::query_scalar::<_, Uuid>(&format!(
+        r#"SELECT a.challenge_id FROM answers a WHERE a.id = $1 AND a.status = 'active' AND {}"#,
+        answer_listed_for("a", 2)
+    ))
+    .bind(answer_id)
+    .bind(None::<Uuid>)
+    .fetch_optional(pool.get_ref())
+    .await;
+
+    let challenge_id = match challenge_id {
+        Ok(Some(id)) => id,
+        Ok(None) => return utils::not_found("Answer not found"),
+        Err(e) => {
+            tracing::error!("Failed to fetch answer: {}", e);
+            return utils::internal_error("Failed to pick answer");
+        }
+    };
+
+    let result = sqlx::query_as::<_, EditorialPick>(
+        r#"
+        INSERT INTO editorial_picks (challenge_id, answer_id, picked_by, note)
+        VALUES ($1, $2, $3, $4)
+        ON CONFLICT (answer_id) DO NOTHING
+        RETURNING *
+        "#,
+    )
+    .bind(challenge_id)
+    .bind(answer_id)
+    .bind(user_id)
+    .bind(&body.note)
+    .fetch_optional(pool.get_ref())
+    .await;
+
+    match result {
+        Ok(Some(pick)) => utils::created(pick),
+        Ok(None) => utils::conflict("Answer already picked"),
+        Err(e) => {
+            tracing::error!("Failed to pick answer: {}", e);
+            utils::internal_error("Failed to pick answer")
+        }
+    }
+}
+
+/// Removes the pick, and the answer's use as its challenge's example answer.
+pub async fn unpick_answer(
+    pool: web::Data<PgPool>,
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+) -> HttpResponse {
+    let answer_id = path.into_inner();
+    let user_id = match utils::get_user_id(&req) {
+        Some(id) => id,
+        None => return utils::unauthorized("User ID required"),
+    };
+    if let Err(resp) = auth::require_role(pool.get_ref(), user_id, auth::EDITORIAL_ROLES).await {
+        return resp;
+    }
+
+    let result: Result<bool, sqlx::Error> = async {
+        let mut tx = pool.begin().await?;
+
+        let removed = sqlx::query(r#"DELETE FROM editorial_picks WHERE answer_id = $1"#)
+            .bind(answer_id)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query(r#"UPDATE challenges SET example_answer_id = NULL, updated_at = NOW() WHERE example_answer_id = $1"#)
+            .bind(answer_id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        Ok(removed.rows_affected() > 0)
+    }
+    .await;
+
+    match result {
+        Ok(true) => utils::no_content(),
+        Ok(false) => utils::not_found("Pick not found"),
+        Err(e) => {
+            tracing::error!("Failed to remove pick: {}", e);
+            utils::internal_error("Failed to remove pick")
+        }
+    }
+}
+
+/// Sets which of the challenge's picks stands in for the AI's example answer
+/// in feedback, or clears it.
+pub async fn set_example_answer(
+    pool: web::Data<PgPool>,
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+    body: web::Json<SetExampleAnswerRequest>,
+) -> HttpResponse {
+    let challenge_id = path.into_inner();
+    let user_id = match utils::get_user_id(&req) {
+        Some(id) => id,
+        None => return utils::unauthorized("User ID required"),
+    };
+    if let Err(resp) = auth::require_role(pool.get_ref(), user_id, auth::EDITORIAL_ROLES).await {
+        return resp;
+    }
+
+    if let Some(answer_id) = body.answer_id {
+        let picked = sqlx::query_scalar::<_, bool>(
+            r#"SELECT EXISTS(SELECT 1 FROM editorial_picks WHERE answer_id = $1 AND challenge_id = $2)"#,
+        )
+        .bind(answer_id)
+        .bind(challenge_id)
+        .fetch_one(pool.get_ref())
+        .await
+        .unwrap_or(false);
+
+        if !picked {
+            return utils::bad_request("Only answers picked for this challenge can be its example answer");
+        }
+    }
+
+    let result = sqlx::query_as::<_, Challenge>(
+        r#"
+        UPDATE challenges SET example_answer_id = $1, updated_at = NOW()
+        WHERE id = $2 AND status = 'active'
+        RETURNING *
+        "#,
+    )
+    .bind(body.answer_id)
+    .bind(challenge_id)
+    .fetch_optional(pool.get_ref())
+    .await;
+
+    match result {
+        Ok(Some(challenge)) => utils::success(challenge),
+        Ok(None) => utils::not_found("Challenge not found"),
+        Err(e) => {
+            tracing::error!("Failed to set example answer: {}", e);
+            utils::internal_error("Failed to set example answer")
+        }
+    }
+}
+
+/// The challenge's picks the viewer can see, its example answer first, then
+/// the most recently picked.
+pub async fn featured_answers(
+    pool: &PgPool,
+    challenge: &Challenge,
+    current_user_id: Option<Uuid>,
+) -> Vec<FeaturedAnswer> {
+    let picks = sqlx::query_as::<_, (Uuid, Option<String>, DateTime<Utc>)>(&format!(
+        r#"
+        SELECT p.answer_id, p.note, p.created_at
+        FROM editorial_picks p
+        JOIN answers a ON a.id = p.answer_id
+        WHERE p.challenge_id = $1 AND a.status = 'active' AND {}
+        ORDER BY (p.answer_id = $3) IS TRUE DESC, p.created_at DESC
+        LIMIT $4
+        "#,
+        answer_visible_to("a", 2)
+    ))
+    .bind(challenge.id)
+    .bind(current_user_id)
+    .bind(challenge.example_answer_id)
+    .bind(FEATURED_LIMIT)
+    .fetch_all(pool)
+    .await
+    .unwrap_or_default();
+
+    let ids: Vec<Uuid> = picks.iter().map(|(id, _, _)| *id).collect();
+    let answers = sqlx::query_as::<_, Answer>(r#"SELECT * FROM answers WHERE id = ANY($1)"#)
+        .bind(&ids)
+        .fetch_all(pool)
+        .await
+        .unwrap_or_default();
+
+    let mut details: HashMap<Uuid, _> = answers_with_details(pool, answers, current_user_id)
+        .await
+        .into_iter()
+        .map(|d| (d.answer.id, d))
+        .collect();
+
+    picks
+        .into_iter()
+        .filter_map(|(answer_id, note, picked_at)| {
+            details.remove(&answer_id).map(|answer| FeaturedAnswer {
+                answer,
+                note,
+                picked_at,
+                is_example: challenge.example_answer_id == Some(answer_id),
+            })
+        })
+        .collect()
+}
+
+/// Swaps in each challenge's curated example answer for the AI's
+/// `example_answer` in the answers' stored feedback.
+pub async fn apply_curated_examples(pool: &PgPool, answers: &mut [Answer]) {
+    let challenge_ids: Vec<Uuid> = answers
+        .iter()
+        .filter(|a| a.ai_feedback.is_some())
+        .map(|a| a.challenge_id)
+        .collect();
+    if challenge_ids.is_empty() {
+        return;
+    }
+
+    // Checked as an anonymous viewer, the same way picks are
+    let examples: HashMap<Uuid, String> = sqlx::query_as::<_, (Uuid, String)>(&format!(
+        r#"
+        SELECT ch.id, a.content
+        FROM challenges ch
+        JOIN answers a ON a.id = ch.example_answer_id
+        WHERE ch.id = ANY($1) AND a.status = 'active' AND {}
+        "#,
+        answer_listed_for("a", 2)
+    ))
+    .bind(&challenge_ids)
+    .bind(None::<Uuid>)
+    .fetch_all(pool)
+    .await
+    .unwrap_or_default()
+    .into_iter()
+    .collect();
+
+    for answer in answers.iter_mut() {
+        if let (Some(example), Some(serde_json::Value::Object(feedback))) =
+            (examples.get(&answer.challenge_id), answer.ai_feedback.as_mut())
+        {
+            feedback.insert("example_answer".to_string(), serde_json::Value::String(example.clone()));
+        }
+    }
+}
@@ -3,7 +3,10 @@ use sqlx::PgPool;
 use uuid::Uuid;
 
 use crate::config::Config;
-use crate::models::{Follow, PaginationParams, User, UserSummary};
+use crate::db::visibility::answer_listed_for;
+use crate::models::{
+    Follow, PaginationParams, SuggestionQueryParams, User, UserSuggestion, UserSummary,
+};
 use crate::utils;
 
 pub async fn follow_user(
@@ -23,35 +26,43 @@ pub async fn follow_user(
     }
 
     // Check target user exists
-    let user_exists = sqlx::query_scalar::<_, bool>(
-        r#"SELECT EXISTS(SELECT 1 FROM users WHERE id = $1 AND status = 'active')"#,
+    let is_private = sqlx::query_scalar::<_, bool>(
+        r#"SELECT is_private FROM users WHERE id = $1 AND status = 'active'"#,
     )
     .bind(following_id)
-    .fetch_one(pool.get_ref())
-    .await
-    .unwrap_or(false);
+    .fetch_optional(pool.get_ref())
+    .await;
 
-    if !user_exists {
-        return utils::not_found("User not found");
-    }
+    let is_private = match is_private {
+        Ok(Some(p)) => p,
+        Ok(None) => return utils::not_found("User not found"),
+        Err(e) => {
+            tracing::error!("Failed to fetch user: {}", e);
+            return utils::internal_error("Failed to follow user");
+        }
+    };
+
+    // Following a private account creates a request the owner must approve
+    let status = if is_private { "pending" } else { "accepted" };
 
     // Create follow
     let result = sqlx::query_as::<_, Follow>(
         r#"
-        INSERT INTO follows (follower_id, following_id)
-        VALUES ($1, $2)
+        INSERT INTO follows (follower_id, following_id, status)
+        VALUES ($1, $2, $3)
         ON CONFLICT (follower_id, following_id) DO NOTHING
         RETURNING *
         "#,
     )
     .bind(follower_id)
     .bind(following_id)
+    .bind(status)
     .fetch_optional(pool.get_ref())
     .await;
 
     match result {
         Ok(Some(follow)) => utils::created(follow),
-        Ok(None) => utils::conflict("Already following"),
+        Ok(None) => utils::conflict("Already following or requested"),
         Err(e) => {
             tracing::error!("Failed to follow user: {}", e);
             utils::internal_error("Failed to follow user")
@@ -103,7 +114,7 @@ pub async fn get_followers(
     );
 
     let total: (i64,) = sqlx::query_as(
-        r#"SELECT COUNT(*) FROM follows WHERE following_id = $1"#,
+        r#"SELECT COUNT(*) FROM follows WHERE following_id = $1 AND status = 'accepted'"#,
     )
     .bind(user_id)
     .fetch_one(pool.get_ref())
@@ -114,7 +125,7 @@ pub async fn get_followers(
         r#"
         SELECT u.* FROM users u
         JOIN follows f ON u.id = f.follower_id
-        WHERE f.following_id = $1 AND u.status = 'active'
+        WHERE f.following_id = $1 AND f.status = 'accepted' AND u.status = 'active'
         ORDER BY f.created_at DESC
         LIMIT $2 OFFSET $3
         "#,
@@ -159,7 +170,7 @@ pub async fn get_following(
     );
 
     let total: (i64,) = sqlx::query_as(
-        r#"SELECT COUNT(*) FROM follows WHERE follower_id = $1"#,
+        r#"SELECT COUNT(*) FROM follows WHERE follower_id = $1 AND status = 'accepted'"#,
     )
     .bind(user_id)
     .fetch_one(pool.get_ref())
@@ -170,7 +181,7 @@ pub async fn get_following(
         r#"
         SELECT u.* FROM users u
         JOIN follows f ON u.id = f.following_id
-        WHERE f.follower_id = $1 AND u.status = 'active'
+        WHERE f.follower_id = $1 AND f.status = 'accepted' AND u.status = 'active'
         ORDER BY f.created_at DESC
         LIMIT $2 OFFSET $3
         "#,
@@ -199,3 +210,221 @@ pub async fn get_following(
         }
     }
 }
+
+pub async fn get_follow_requests(
+    pool: web::Data<PgPool>,
+    config: web::Data<Config>,
+    req: HttpRequest,
+    query: web::Query<PaginationParams>,
+) -> HttpResponse {
+    let user_id = match utils::get_user_id(&req) {
+        Some(id) => id,
+        None => return utils::unauthorized("User ID required"),
+    };
+    let (page, page_size, offset) = utils::normalize_pagination(
+        query.page,
+        query.page_size,
+        config.pagination.default_page_size,
+        config.pagination.max_page_size,
+    );
+
+    let total: (i64,) = sqlx::query_as(
+        r#"SELECT COUNT(*) FROM follows WHERE following_id = $1 AND status = 'pending'"#,
+    )
+    .bind(user_id)
+    .fetch_one(pool.get_ref())
+    .await
+    .unwrap_or((0,));
+
+    // Oldest requests first
+    let requesters = sqlx::query_as::<_, User>(
+        r#"
+        SELECT u.* FROM users u
+        JOIN follows f ON u.id = f.follower_id
+        WHERE f.following_id = $1 AND f.status = 'pending' AND u.status = 'active'
+        ORDER BY f.created_at ASC
+        LIMIT $2 OFFSET $3
+        "#,
+    )
+    .bind(user_id)
+    .bind(page_size)
+    .bind(offset)
+    .fetch_all(pool.get_ref())
+    .await;
+
+    match requesters {
+        Ok(users) => {
+            let summaries: Vec<UserSummary> = users
+                .into_iter()
+                .map(|u| UserSummary {
+                    id: u.id,
+                    name: u.name,
+                    avatar: u.avatar,
+                })
+                .collect();
+            utils::paginated(summaries, page, page_size, total.0)
+        }
+        Err(e) => {
+            tracing::error!("Failed to fetch follow requests: {}", e);
+            utils::internal_error("Failed to fetch follow requests")
+        }
+    }
+}
+
+pub async fn approve_follow_request(
+    pool: web::Data<PgPool>,
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+) -> HttpResponse {
+    let follower_id = path.into_inner();
+    let user_id = match utils::get_user_id(&req) {
+        Some(id) => id,
+        None => return utils::unauthorized("User ID required"),
+    };
+
+    let result = sqlx::query_as::<_, Follow>(
+        r#"
+        UPDATE follows SET status = 'accepted'
+        WHERE follower_id = $1 AND following_id = $2 AND status = 'pending'
+        RETURNING *
+        "#,
+    )
+    .bind(follower_id)
+    .bind(user_id)
+    .fetch_optional(pool.get_ref())
+    .await;
+
+    match result {
+        Ok(Some(follow)) => utils::success(follow),
+        Ok(None) => utils::not_found("Follow request not found"),
+        Err(e) => {
+            tracing::error!("Failed to approve follow request: {}", e);
+            utils::internal_error("Failed to approve follow request")
+        }
+    }
+}
+
+pub async fn decline_follow_request(
+    pool: web::Data<PgPool>,
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+) -> HttpResponse {
+    let follower_id = path.into_inner();
+    let user_id = match utils::get_user_id(&req) {
+        Some(id) => id,
+        None => return utils::unauthorized("User ID required"),
+    };
+
+    let result = sqlx::query(
+        r#"DELETE FROM follows WHERE follower_id = $1 AND following_id = $2 AND status = 'pending'"#,
+    )
+    .bind(follower_id)
+    .bind(user_id)
+    .execute(pool.get_ref())
+    .await;
+
+    match result {
+        Ok(res) if res.rows_affected() > 0 => utils::no_content(),
+        Ok(_) => utils::not_found("Follow request not found"),
+        Err(e) => {
+            tracing::error!("Failed to decline follow request: {}", e);
+            utils::internal_error("Failed to decline follow request")
+        }
+    }
+}
+
+// Suggestion weights: a follow from someone you follow counts most, then a
+// shared category. High-rated answers are not part of the score; they only
+// order candidates with equal scores, and let strangers be suggested at all.
+const HIGH_RATED_SCORE: i32 = 85;
+const MAX_HIGH_RATED_BONUS: i64 = 5;
+
+pub async fn get_follow_suggestions(
+    pool: web::Data<PgPool>,
+    config: web::Data<Config>,
+    req: HttpRequest,
+    query: web::Query<SuggestionQueryParams>,
+) -> HttpResponse {
+    let user_id = match utils::get_user_id(&req) {
+        Some(id) => id,
+        None => return utils::unauthorized("User ID required"),
+    };
+    let limit = query
+        .limit
+        .unwrap_or(config.pagination.default_page_size)
+        .clamp(1, config.pagination.max_page_size);
+
+    // Candidates are active users the caller does not follow or request yet.
+    // Only listed answers count, so hidden attempts and their scores stay private.
+    let result = sqlx::query_as::<_, UserSuggestion>(&format!(
+        r#"
+        WITH my_following AS (
+            SELECT following_id, status FROM follows WHERE follower_id = $1
+        ),
+        my_categories AS (
+            SELECT DISTINCT c.category_id
+            FROM answers a
+            JOIN challenges c ON c.id = a.challenge_id
+            WHERE a.user_id = $1 AND a.status = 'active'
+        ),
+        mutual AS (
+            SELECT following_id AS user_id, COUNT(*) AS n
+            FROM follows
+            WHERE status = 'accepted'
+              AND follower_id IN (SELECT following_id FROM my_following WHERE status = 'accepted')
+            GROUP BY following_id
+        ),
+        shared AS (
+            SELECT a.user_id, COUNT(DISTINCT c.category_id) AS n
+            FROM answers a
+            JOIN challenges c ON c.id = a.challenge_id
+            WHERE a.status = 'active' AND c.category_id IN (SELECT category_id FROM my_categories)
+              AND {listed}
+            GROUP BY a.user_id
+        ),
+        rated AS (
+            SELECT a.user_id, COUNT(*) AS n
+            FROM answers a
+            WHERE a.status = 'active' AND a.score >= $2 AND {listed}
+            GROUP BY a.user_id
+        ),
+        scored AS (
+            SELECT
+                u.id, u.name, u.avatar, u.bio, u.total_likes,
+                COALESCE(m.n, 0) AS mutual_follow_count,
+                COALESCE(s.n, 0) AS shared_category_count,
+                COALESCE(r.n, 0) AS high_rated_answer_count,
+                LEAST(COALESCE(r.n, 0), $3) AS rated_bonus,
+                (COALESCE(m.n, 0) * 3 + COALESCE(s.n, 0) * 2)::FLOAT8 AS score
+            FROM users u
+            LEFT JOIN mutual m ON m.user_id = u.id
+            LEFT JOIN shared s ON s.user_id = u.id
+            LEFT JOIN rated r ON r.user_id = u.id
+            WHERE u.status = 'active'
+              AND u.id <> $1
+              AND u.id NOT IN (SELECT following_id FROM my_following)
+        )
+        SELECT id, name, avatar, bio, mutual_follow_count, shared_category_count,
+               high_rated_answer_count, score
+        FROM scored
+        WHERE score > 0 OR rated_bonus > 0
+        ORDER BY score DESC, rated_bonus DESC, total_likes DESC
+        LIMIT $4
+        "#,
+        listed = answer_listed_for("a", 1)
+    ))
+    .bind(user_id)
+    .bind(HIGH_RATED_SCORE)
+    .bind(MAX_HIGH_RATED_BONUS)
+    .bind(limit)
+    .fetch_all(pool.get_ref())
+    .await;
+
+    match result {
+        Ok(suggestions) => utils::success(suggestions),
+        Err(e) => {
+            tracing::error!("Failed to fetch follow suggestions: {}", e);
+            utils::internal_error("Failed to fetch follow suggestions")
+        }
+    }
+}
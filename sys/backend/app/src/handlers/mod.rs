@@ -1,19 +1,63 @@
 pub mod answer;
+pub mod attempt;
+pub mod badge;
+pub mod battle;
+pub mod bookmark;
 pub mod category;
 pub mod challenge;
+pub mod collection;
 pub mod comment;
+pub mod contest;
+pub mod course;
+pub mod daily;
+pub mod difficulty;
+pub mod draft;
+pub mod editorial;
 pub mod follow;
 pub mod health;
 pub mod like;
+pub mod moderation;
+pub mod organization;
+pub mod peer_review;
+pub mod proposal;
 pub mod ranking;
+pub mod reaction;
+pub mod recommendation;
+pub mod review;
+pub mod session;
+pub mod stats;
+pub mod streak;
 pub mod user;
+pub mod xp;
 
 pub use answer::*;
+pub use attempt::*;
+pub use badge::*;
+pub use battle::*;
+pub use bookmark::*;
 pub use category::*;
 pub use challenge::*;
+pub use collection::*;
 pub use comment::*;
+pub use contest::*;
+pub use course::*;
+pub use daily::*;
+pub use difficulty::*;
+pub use draft::*;
+pub use editorial::*;
 pub use follow::*;
 pub use health::*;
 pub use like::*;
+pub use moderation::*;
+pub use organization::*;
+pub use peer_review::*;
+pub use proposal::*;
 pub use ranking::*;
+pub use reaction::*;
+pub use recommendation::*;
+pub use review::*;
+pub use session::*;
+pub use stats::*;
+pub use streak::*;
 pub use user::*;
+pub use xp::*;
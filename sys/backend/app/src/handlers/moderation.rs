@@ -0,0 +1,790 @@
+use actix_web::{web, HttpRequest, HttpResponse};
+use sqlx::PgPool;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::auth;
+use crate::config::Config;
+use crate::handlers::answer::{adjust_answer_count, counts_toward_total};
+use crate::models::{
+    CreateReportRequest, ModerationAction, ModerationActionRequest, ModerationQueryParams,
+    ModerationQueueItem, PaginationParams, Report, UserWarning,
+};
+use crate::utils;
+
+const TARGET_TYPES: &[&str] = &["answer", "comment", "user"];
+const REPORT_REASONS: &[&str] = &[
+    "spam",
+    "harassment",
+    "hate",
+    "sexual",
+    "personal_info",
+    "impersonation",
+    "other",
+];
+
+pub async fn create_report(
+    pool: web::Data<PgPool>,
+    config: web::Data<Config>,
+    req: HttpRequest,
+    body: web::Json<CreateReportRequest>,
+) -> HttpResponse {
+    let reporter_id = match utils::get_user_id(&req) {
+        Some(id) => id,
+        None => return utils::unauthorized("User ID required"),
+    };
+
+    if let Err(e) = body.validate() {
+        return utils::bad_request(&e.to_string());
+    }
+    if !TARGET_TYPES.contains(&body.target_type.as_str()) {
+        return utils::bad_request("Invalid target type");
+    }
+    if !REPORT_REASONS.contains(&body.reason.as_str()) {
+        return utils::bad_request("Invalid report reason");
+    }
+
+    if auth::is_suspended(pool.get_ref(), reporter_id).await {
+        return utils::forbidden("Your account is suspended");
+    }
+
+    // Only content that is currently visible can be reported
+    let owner = match fetch_target_owner(pool.get_ref(), &body.target_type, body.target_id).await {
+        Ok(Some((owner_id, status))) if status == "active" => owner_id,
+        Ok(_) => return utils::not_found("Report target not found"),
+        Err(e) => {
+            tracing::error!("Failed to fetch report target: {}", e);
+            return utils::internal_error("Failed to create report");
+        }
+    };
+
+    if owner == reporter_id {
+        return utils::bad_request("You cannot report your own content");
+    }
+
+    let result = sqlx::query_as::<_, Report>(
+        r#"
+        INSERT INTO reports (reporter_id, target_type, target_id, reason, detail)
+        VALUES ($1, $2, $3, $4, $5)
+        ON CONFLICT (reporter_id, target_type, target_id) WHERE status = 'open' DO NOTHING
+        RETURNING *
+        "#,
+    )
+    .bind(reporter_id)
+    .bind(&body.target_type)
+    .bind(body.target_id)
+    .bind(&body.reason)
+    .bind(&body.detail)
+    .fetch_optional(pool.get_ref())
+    .await;
+
+    let report = match result {
+        Ok(Some(report)) => report,
+        Ok(None) => return utils::conflict("Already reported"),
+        Err(e) => {
+            tracing::error!("Failed to create report: {}", e);
+            return utils::internal_error("Failed to create report");
+        }
+    };
+
+    // Hide content automatically once enough distinct users have reported it
+    if report.target_type != "user" {
+        let open_reports = sqlx::query_scalar::<_, i64>(
+            r#"
+            SELECT COUNT(DISTINCT reporter_id) FROM reports
+            WHERE target_type = $1 AND target_id = $2 AND status = 'open'
+            "#,
+        )
+        .bind(&report.target_type)
+        .bind(report.target_id)
+        .fetch_one(pool.get_ref())
+        .await
+        .unwrap_or(0);
+
+        if open_reports >= config.moderation.auto_hide_threshold
+            && hide_content(pool.get_ref(), &report.target_type, report.target_id).await
+        {
+            log_action(
+                pool.get_ref(),
+                None,
+                &report.target_type,
+                report.target_id,
+                "hide",
+                Some(&format!("Automatically hidden after {} reports", open_reports)),
+            )
+            .await;
+        }
+    }
+
+    utils::created(report)
+}
+
+pub async fn get_moderation_queue(
+    pool: web::Data<PgPool>,
+    config: web::Data<Config>,
+    req: HttpRequest,
+    query: web::Query<ModerationQueryParams>,
+) -> HttpResponse {
+    let moderator_id = match utils::get_user_id(&req) {
+        Some(id) => id,
+        None => return utils::unauthorized("User ID required"),
+    };
+    if let Err(resp) = auth::require_role(pool.get_ref(), moderator_id, auth::MODERATION_ROLES).await {
+        return resp;
+    }
+
+    let (page, page_size, offset) = utils::normalize_pagination(
+        query.page,
+        query.page_size,
+        config.pagination.default_page_size,
+        config.pagination.max_page_size,
+    );
+
+    let total: (i64,) = sqlx::query_as(
+        r#"
+        SELECT COUNT(DISTINCT (target_type, target_id)) FROM reports
+        WHERE status = 'open' AND ($1::text IS NULL OR target_type = $1)
+        "#,
+    )
+    .bind(&query.target_type)
+    .fetch_one(pool.get_ref())
+    .await
+    .unwrap_or((0,));
+
+    // One entry per reported target, most reported first
+    let result = sqlx::query_as::<_, ModerationQueueItem>(
+        r#"
+        SELECT
+            r.target_type,
+            r.target_id,
+            COALESCE(a.status, c.status, u.status) AS target_status,
+            COALESCE(a.content, c.content, u.name) AS content,
+            COALESCE(a.user_id, c.user_id, u.id) AS author_id,
+            COUNT(*) AS report_count,
+            ARRAY_AGG(DISTINCT r.reason)::text[] AS reasons,
+            (
+                SELECT COUNT(*) FROM user_warnings w
+                WHERE w.user_id = COALESCE(a.user_id, c.user_id, u.id)
+            ) AS author_warning_count,
+            MIN(r.created_at) AS first_reported_at,
+            MAX(r.created_at) AS last_reported_at
+        FROM reports r
+        LEFT JOIN answers a ON r.target_type = 'answer' AND a.id = r.target_id
+        LEFT JOIN comments c ON r.target_type = 'comment' AND c.id = r.target_id
+        LEFT JOIN users u ON r.target_type = 'user' AND u.id = r.target_id
+        WHERE r.status = 'open' AND ($1::text IS NULL OR r.target_type = $1)
+        GROUP BY r.target_type, r.target_id, a.id, c.id, u.id
+        ORDER BY COUNT(*) DESC, MAX(r.created_at) DESC
+        LIMIT $2 OFFSET $3
+        "#,
+    )
+    .bind(&query.target_type)
+    .bind(page_size)
+    .bind(offset)
+    .fetch_all(pool.get_ref())
+    .await;
+
+    match result {
+        Ok(items) => utils::paginated(items, page, page_size, total.0),
+        Err(e) => {
+            tracing::error!("Failed to fetch moderation queue: {}", e);
+            utils::internal_error("Failed to fetch moderation queue")
+        }
+    }
+}
+
+pub async fn list_reports(
+    pool: web::Data<PgPool>,
+    config: web::Data<Config>,
+    req: HttpRequest,
+    query: web::Query<ModerationQueryParams>,
+) -> HttpResponse {
+    let moderator_id = match utils::get_user_id(&req) {
+        Some(id) => id,
+        None => return utils::unauthorized("User ID required"),
+    };
+    if let Err(resp) = auth::require_role(pool.get_ref(), moderator_id, auth::MODERATION_ROLES).await {
+        return resp;
+    }
+
+    let (page, page_size, offset) = utils::normalize_pagination(
+        query.page,
+        query.page_size,
+        config.pagination.default_page_size,
+        config.pagination.max_page_size,
+    );
+    let status = query.status.as_deref().unwrap_or("open");
+
+    let total: (i64,) = sqlx::query_as(
+        r#"
+        SELECT COUNT(*) FROM reports
+        WHERE status = $1
+          AND ($2::text IS NULL OR target_type = $2)
+          AND ($3::uuid IS NULL OR target_id = $3)
+        "#,
+    )
+    .bind(status)
+    .bind(&query.target_type)
+    .bind(query.target_id)
+    .fetch_one(pool.get_ref())
+    .await
+    .unwrap_or((0,));
+
+    let result = sqlx::query_as::<_, Report>(
+        r#"
+        SELECT * FROM reports
+        WHERE status = $1
+          AND ($2::text IS NULL OR target_type = $2)
+          AND ($3::uuid IS NULL OR target_id = $3)
+        ORDER BY created_at DESC
+        LIMIT $4 OFFSET $5
+        "#,
+    )
+    .bind(status)
+    .bind(&query.target_type)
+    .bind(query.target_id)
+    .bind(page_size)
+    .bind(offset)
+    .fetch_all(pool.get_ref())
+    .await;
+
+    match result {
+        Ok(reports) => utils::paginated(reports, page, page_size, total.0),
+        Err(e) => {
+            tracing::error!("Failed to fetch reports: {}", e);
+            utils::internal_error("Failed to fetch reports")
+        }
+    }
+}
+
+pub async fn list_moderation_actions(
+    pool: web::Data<PgPool>,
+    config: web::Data<Config>,
+    req: HttpRequest,
+    query: web::Query<ModerationQueryParams>,
+) -> HttpResponse {
+    let moderator_id = match utils::get_user_id(&req) {
+        Some(id) => id,
+        None => return utils::unauthorized("User ID required"),
+    };
+    if let Err(resp) = auth::require_role(pool.get_ref(), moderator_id, auth::MODERATION_ROLES).await {
+        return resp;
+    }
+
+    let (page, page_size, offset) = utils::normalize_pagination(
+        query.page,
+        query.page_size,
+        config.pagination.default_page_size,
+        config.pagination.max_page_size,
+    );
+
+    let total: (i64,) = sqlx::query_as(
+        r#"
+        SELECT COUNT(*) FROM moderation_actions
+        WHERE ($1::text IS NULL OR target_type = $1)
+          AND ($2::uuid IS NULL OR target_id = $2)
+        "#,
+    )
+    .bind(&query.target_type)
+    .bind(query.target_id)
+    .fetch_one(pool.get_ref())
+    .await
+    .unwrap_or((0,));
+
+    let result = sqlx::query_as::<_, ModerationAction>(
+        r#"
+        SELECT * FROM moderation_actions
+        WHERE ($1::text IS NULL OR target_type = $1)
+          AND ($2::uuid IS NULL OR target_id = $2)
+        ORDER BY created_at DESC
+        LIMIT $3 OFFSET $4
+        "#,
+    )
+    .bind(&query.target_type)
+    .bind(query.target_id)
+    .bind(page_size)
+    .bind(offset)
+    .fetch_all(pool.get_ref())
+    .await;
+
+    match result {
+        Ok(actions) => utils::paginated(actions, page, page_size, total.0),
+        Err(e) => {
+            tracing::error!("Failed to fetch moderation actions: {}", e);
+            utils::internal_error("Failed to fetch moderation actions")
+        }
+    }
+}
+
+pub async fn take_moderation_action(
+    pool: web::Data<PgPool>,
+    req: HttpRequest,
+    body: web::Json<ModerationActionRequest>,
+) -> HttpResponse {
+    let moderator_id = match utils::get_user_id(&req) {
+        Some(id) => id,
+        None => return utils::unauthorized("User ID required"),
+    };
+    if let Err(resp) = auth::require_role(pool.get_ref(), moderator_id, auth::MODERATION_ROLES).await {
+        return resp;
+    }
+
+    if let Err(e) = body.validate() {
+        return utils::bad_request(&e.to_string());
+    }
+    if !TARGET_TYPES.contains(&body.target_type.as_str()) {
+        return utils::bad_request("Invalid target type");
+    }
+
+    let target_type = body.target_type.as_str();
+    let target_id = body.target_id;
+
+    match fetch_target_owner(pool.get_ref(), target_type, target_id).await {
+        Ok(Some(_)) => {}
+        Ok(None) => return utils::not_found("Moderation target not found"),
+        Err(e) => {
+            tracing::error!("Failed to fetch moderation target: {}", e);
+            return utils::internal_error("Failed to apply moderation action");
+        }
+    }
+
+    // Each action either upholds the open reports or dismisses them
+    let report_status = match (body.action.as_str(), target_type) {
+        ("hide", "answer" | "comment") => {
+            if !hide_content(pool.get_ref(), target_type, target_id).await {
+                return utils::conflict("Content is already hidden or deleted");
+            }
+            "resolved"
+        }
+        ("restore", _) => {
+            if !restore_target(pool.get_ref(), target_type, target_id).await {
+                return utils::conflict("Target is not hidden, held or suspended");
+            }
+            "dismissed"
+        }
+        ("warn", "user") => {
+            let reason = match body.reason.as_deref().map(str::trim) {
+                Some(reason) if !reason.is_empty() => reason,
+                _ => return utils::bad_request("A reason is required to warn a user"),
+            };
+
+            let result = sqlx::query(
+                r#"INSERT INTO user_warnings (user_id, moderator_id, reason) VALUES ($1, $2, $3)"#,
+            )
+            .bind(target_id)
+            .bind(moderator_id)
+            .bind(reason)
+            .execute(pool.get_ref())
+            .await;
+
+            if let Err(e) = result {
+                tracing::error!("Failed to warn user: {}", e);
+                return utils::internal_error("Failed to apply moderation action");
+            }
+            "resolved"
+        }
+        ("suspend", "user") => {
+            if target_id == moderator_id {
+                return utils::bad_request("You cannot suspend yourself");
+            }
+            let role = sqlx::query_scalar::<_, String>(r#"SELECT role FROM users WHERE id = $1"#)
+                .bind(target_id)
+                .fetch_one(pool.get_ref())
+                .await;
+            match role {
+                Ok(role) if auth::MODERATION_ROLES.contains(&role.as_str()) => {
+                    return utils::forbidden("Moderators and admins cannot be suspended");
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    tracing::error!("Failed to fetch user role: {}", e);
+                    return utils::internal_error("Failed to apply moderation action");
+                }
+            }
+
+            let result = sqlx::query(
+                r#"UPDATE users SET status = 'suspended', updated_at = NOW() WHERE id = $1 AND status = 'active'"#,
+            )
+            .bind(target_id)
+            .execute(pool.get_ref())
+            .await;
+
+            match result {
+                Ok(res) if res.rows_affected() > 0 => {}
+                Ok(_) => return utils::conflict("User is not active"),
+                Err(e) => {
+                    tracing::error!("Failed to suspend user: {}", e);
+                    return utils::internal_error("Failed to apply moderation action");
+                }
+            }
+            "resolved"
+        }
+        ("dismiss", _) => "dismissed",
+        ("hide" | "warn" | "suspend", _) => {
+            return utils::bad_request("Action is not applicable to this target type");
+        }
+        _ => return utils::bad_request("Invalid moderation action"),
+    };
+
+    let _ = sqlx::query(
+        r#"
+        UPDATE reports SET status = $1, resolved_by = $2, resolved_at = NOW()
+        WHERE target_type = $3 AND target_id = $4 AND status = 'open'
+        "#,
+    )
+    .bind(report_status)
+    .bind(moderator_id)
+    .bind(target_type)
+    .bind(target_id)
+    .execute(pool.get_ref())
+    .await;
+
+    match log_action(
+        pool.get_ref(),
+        Some(moderator_id),
+        target_type,
+        target_id,
+        &body.action,
+        body.reason.as_deref(),
+    )
+    .await
+    {
+        Some(action) => utils::created(action),
+        None => utils::internal_error("Failed to record moderation action"),
+    }
+}
+
+/// Warnings issued to the current user, newest first.
+pub async fn list_my_warnings(
+    pool: web::Data<PgPool>,
+    config: web::Data<Config>,
+    req: HttpRequest,
+    query: web::Query<PaginationParams>,
+) -> HttpResponse {
+    let user_id = match utils::get_user_id(&req) {
+        Some(id) => id,
+        None => return utils::unauthorized("User ID required"),
+    };
+
+    list_warnings_for(pool.get_ref(), config.get_ref(), user_id, query.page, query.page_size).await
+}
+
+/// A user's warning history, for moderators deciding on further action.
+pub async fn list_user_warnings(
+    pool: web::Data<PgPool>,
+    config: web::Data<Config>,
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+    query: web::Query<PaginationParams>,
+) -> HttpResponse {
+    let moderator_id = match utils::get_user_id(&req) {
+        Some(id) => id,
+        None => return utils::unauthorized("User ID required"),
+    };
+    if let Err(resp) = auth::require_role(pool.get_ref(), moderator_id, auth::MODERATION_ROLES).await {
+        return resp;
+    }
+
+    list_warnings_for(pool.get_ref(), config.get_ref(), path.into_inner(), query.page, query.page_size).await
+}
+
+pub async fn acknowledge_warning(
+    pool: web::Data<PgPool>,
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+) -> HttpResponse {
+    let warning_id = path.into_inner();
+    let user_id = match utils::get_user_id(&req) {
+        Some(id) => id,
+        None => return utils::unauthorized("User ID required"),
+    };
+
+    let result = sqlx::query_as::<_, UserWarning>(
+        r#"
+        UPDATE user_warnings SET acknowledged_at = COALESCE(acknowledged_at, NOW())
+        WHERE id = $1 AND user_id = $2
+        RETURNING *
+        "#,
+    )
+    .bind(warning_id)
+    .bind(user_id)
+    .fetch_optional(pool.get_ref())
+    .await;
+
+    match result {
+        Ok(Some(warning)) => utils::success(warning),
+        Ok(None) => utils::not_found("Warning not found"),
+        Err(e) => {
+            tracing::error!("Failed to acknowledge warning: {}", e);
+            utils::internal_error("Failed to acknowledge warning")
+        }
+    }
+}
+
+async fn list_warnings_for(
+    pool: &PgPool,
+    config: &Config,
+    user_id: Uuid,
+    page: Option<i64>,
+    page_size: Option<i64>,
+) -> HttpResponse {
+    let (page, page_size, offset) = utils::normalize_pagination(
+        page,
+        page_size,
+        config.pagination.default_page_size,
+        config.pagination.max_page_size,
+    );
+
+    let total: (i64,) = sqlx::query_as(r#"SELECT COUNT(*) FROM user_warnings WHERE user_id = $1"#)
+        .bind(user_id)
+        .fetch_one(pool)
+        .await
+        .unwrap_or((0,));
+
+    let result = sqlx::query_as::<_, UserWarning>(
+        r#"
+        SELECT * FROM user_warnings
+        WHERE user_id = $1
+        ORDER BY created_at DESC
+        LIMIT $2 OFFSET $3
+        "#,
+    )
+    .bind(user_id)
+    .bind(page_size)
+    .bind(offset)
+    .fetch_all(pool)
+    .await;
+
+    match result {
+        Ok(warnings) => utils::paginated(warnings, page, page_size, total.0),
+        Err(e) => {
+            tracing::error!("Failed to fetch warnings: {}", e);
+            utils::internal_error("Failed to fetch warnings")
+        }
+    }
+}
+
+/// Queues content flagged by the content filter for moderator review. A target
+/// keeps a single open system report, carrying the latest filter reasons.
+pub async fn file_system_report(pool: &PgPool, target_type: &str, target_id: Uuid, detail: &str) {
+    let result = sqlx::query(
+        r#"
+        INSERT INTO reports (reporter_id, target_type, target_id, reason, detail)
+        VALUES (NULL, $1, $2, 'filter', $3)
+        ON CONFLICT (target_type, target_id) WHERE reporter_id IS NULL AND status = 'open'
+        DO UPDATE SET detail = EXCLUDED.detail
+        "#,
+    )
+    .bind(target_type)
+    .bind(target_id)
+    .bind(detail)
+    .execute(pool)
+    .await;
+
+    if let Err(e) = result {
+        tracing::error!("Failed to file system report: {}", e);
+    }
+}
+
+/// Returns the author and current status of a reportable target.
+async fn fetch_target_owner(
+    pool: &PgPool,
+    target_type: &str,
+    target_id: Uuid,
+) -> Result<Option<(Uuid, String)>, sqlx::Error> {
+    let sql = match target_type {
+        "answer" => r#"SELECT user_id, status FROM answers WHERE id = $1"#,
+        "comment" => r#"SELECT user_id, status FROM comments WHERE id = $1"#,
+        _ => r#"SELECT id, status FROM users WHERE id = $1"#,
+    };
+
+    sqlx::query_as::<_, (Uuid, String)>(sql)
+        .bind(target_id)
+        .fetch_optional(pool)
+        .await
+}
+
+/// Hides a visible or held answer or comment and keeps the parent counters in sync.
+/// Returns false when there was nothing to hide.
+async fn hide_content(pool: &PgPool, target_type: &str, target_id: Uuid) -> bool {
+    match target_type {
+        "answer" => {
+            // Held (pending), private and unpublished answers were never counted
+            let hidden = sqlx::query_as::<_, (Uuid, String, String, bool)>(
+                r#"
+                UPDATE answers t SET status = 'hidden', updated_at = NOW()
+                FROM (SELECT id, status FROM answers WHERE id = $1 FOR UPDATE) prev
+                WHERE t.id = prev.id AND prev.status IN ('active', 'pending')
+                RETURNING t.challenge_id, prev.status, t.visibility, t.is_published
+                "#,
+            )
+            .bind(target_id)
+            .fetch_optional(pool)
+            .await;
+
+            match hidden {
+                Ok(Some((challenge_id, prev_status, visibility, is_published))) => {
+                    if counts_toward_total(&prev_status, &visibility, is_published) {
+                        adjust_answer_count(pool, challenge_id, -1).await;
+                    }
+                    true
+                }
+                Ok(None) => false,
+                Err(e) => {
+                    tracing::error!("Failed to hide answer: {}", e);
+                    false
+                }
+            }
+        }
+        "comment" => {
+            // Held (pending) content was never counted, so only active content adjusts counters
+            let hidden = sqlx::query_as::<_, (Uuid, String)>(
+                r#"
+                UPDATE comments t SET status = 'hidden', updated_at = NOW()
+                FROM (SELECT id, status FROM comments WHERE id = $1 FOR UPDATE) prev
+                WHERE t.id = prev.id AND prev.status IN ('active', 'pending')
+                RETURNING t.answer_id, prev.status
+                "#,
+            )
+            .bind(target_id)
+            .fetch_optional(pool)
+            .await;
+
+            match hidden {
+                Ok(Some((answer_id, prev_status))) => {
+                    if prev_status != "active" {
+                        return true;
+                    }
+                    let _ = sqlx::query(
+                        r#"UPDATE answers SET comment_count = GREATEST(0, comment_count - 1) WHERE id = $1"#,
+                    )
+                    .bind(answer_id)
+                    .execute(pool)
+                    .await;
+                    true
+                }
+                Ok(None) => false,
+                Err(e) => {
+                    tracing::error!("Failed to hide comment: {}", e);
+                    false
+                }
+            }
+        }
+        _ => false,
+    }
+}
+
+/// Reverts a hide or suspension, or approves held content.
+/// Returns false when there was nothing to restore.
+async fn restore_target(pool: &PgPool, target_type: &str, target_id: Uuid) -> bool {
+    match target_type {
+        "answer" => {
+            let restored = sqlx::query_as::<_, (Uuid, String, bool)>(
+                r#"UPDATE answers SET status = 'active', updated_at = NOW() WHERE id = $1 AND status IN ('hidden', 'pending') RETURNING challenge_id, visibility, is_published"#,
+            )
+            .bind(target_id)
+            .fetch_optional(pool)
+            .await;
+
+            match restored {
+                Ok(Some((challenge_id, visibility, is_published))) => {
+                    if counts_toward_total("active", &visibility, is_published) {
+                        adjust_answer_count(pool, challenge_id, 1).await;
+                    }
+                    true
+                }
+                Ok(None) => false,
+                Err(e) => {
+                    tracing::error!("Failed to restore answer: {}", e);
+                    false
+                }
+            }
+        }
+        "comment" => {
+            let answer_id = sqlx::query_scalar::<_, Uuid>(
+                r#"UPDATE comments SET status = 'active', updated_at = NOW() WHERE id = $1 AND status IN ('hidden', 'pending') RETURNING answer_id"#,
+            )
+            .bind(target_id)
+            .fetch_optional(pool)
+            .await;
+
+            match answer_id {
+                Ok(Some(answer_id)) => {
+                    let _ = sqlx::query(
+                        r#"UPDATE answers SET comment_count = comment_count + 1 WHERE id = $1"#,
+                    )
+                    .bind(answer_id)
+                    .execute(pool)
+                    .await;
+                    true
+                }
+                Ok(None) => false,
+                Err(e) => {
+                    tracing::error!("Failed to restore comment: {}", e);
+                    false
+                }
+            }
+        }
+        _ => {
+            let result = sqlx::query(
+                r#"UPDATE users SET status = 'active', updated_at = NOW() WHERE id = $1 AND status = 'suspended'"#,
+            )
+            .bind(target_id)
+            .execute(pool)
+            .await;
+
+            match result {
+                Ok(res) if res.rows_affected() > 0 => true,
+                // Held profiles stay live, so approving one only settles the filter's report
+                Ok(_) => sqlx::query_scalar::<_, bool>(
+                    r#"
+                    SELECT EXISTS(
+                        SELECT 1 FROM reports
+                        WHERE target_type = 'user' AND target_id = $1 AND reporter_id IS NULL AND status = 'open'
+                    )
+                    "#,
+                )
+                .bind(target_id)
+                .fetch_one(pool)
+                .await
+                .unwrap_or(false),
+                Err(e) => {
+                    tracing::error!("Failed to restore user: {}", e);
+                    false
+                }
+            }
+        }
+    }
+}
+
+async fn log_action(
+    pool: &PgPool,
+    moderator_id: Option<Uuid>,
+    target_type: &str,
+    target_id: Uuid,
+    action: &str,
+    reason: Option<&str>,
+) -> Option<ModerationAction> {
+    let result = sqlx::query_as::<_, ModerationAction>(
+        r#"
+        INSERT INTO moderation_actions (moderator_id, target_type, target_id, action, reason)
+        VALUES ($1, $2, $3, $4, $5)
+        RETURNING *
+        "#,
+    )
+    .bind(moderator_id)
+    .bind(target_type)
+    .bind(target_id)
+    .bind(action)
+    .bind(reason)
+    .fetch_one(pool)
+    .await;
+
+    match result {
+        Ok(action) => Some(action),
+        Err(e) => {
+            tracing::error!("Failed to log moderation action: {}", e);
+            None
+        }
+    }
+}
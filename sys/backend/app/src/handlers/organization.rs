@@ -0,0 +1,896 @@
+use actix_web::{web, HttpRequest, HttpResponse};
+use sqlx::PgPool;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::handlers::challenge::insert_challenge;
+use crate::models::{
+    AddGroupMemberRequest, AddOrgMemberRequest, Assignment, AssignmentSubmission,
+    AssignmentWithStatus, Challenge, CreateAssignmentRequest, CreateChallengeRequest,
+    CreateOrgGroupRequest, CreateOrganizationRequest, OrgGroup, OrgGroupWithCount,
+    OrgLeaderboardEntry, OrgLeaderboardQueryParams, Organization, OrganizationMember,
+    OrganizationWithRole,
+};
+use crate::utils;
+
+// Values stored in organization_members.role
+pub const ORG_ROLE_OWNER: &str = "owner";
+pub const ORG_ROLE_INSTRUCTOR: &str = "instructor";
+pub const ORG_ROLE_LEARNER: &str = "learner";
+
+const ORG_ROLES: &[&str] = &[ORG_ROLE_OWNER, ORG_ROLE_INSTRUCTOR, ORG_ROLE_LEARNER];
+
+/// Roles allowed to manage groups, private challenges and assignments.
+const ORG_STAFF_ROLES: &[&str] = &[ORG_ROLE_OWNER, ORG_ROLE_INSTRUCTOR];
+
+/// Every role may read the organization.
+const ORG_MEMBER_ROLES: &[&str] = ORG_ROLES;
+
+/// SQL status of the submission row `sub` against assignment row `asg`.
+const SUBMISSION_STATUS: &str = r#"
+    CASE
+        WHEN sub.submitted_at IS NULL AND NOW() > asg.deadline_at THEN 'missing'
+        WHEN sub.submitted_at IS NULL THEN 'pending'
+        WHEN sub.submitted_at > asg.deadline_at THEN 'late'
+        ELSE 'submitted'
+    END
+"#;
+
+pub async fn create_organization(
+    pool: web::Data<PgPool>,
+    req: HttpRequest,
+    body: web::Json<CreateOrganizationRequest>,
+) -> HttpResponse {
+    let user_id = match utils::get_user_id(&req) {
+        Some(id) => id,
+        None => return utils::unauthorized("User ID required"),
+    };
+
+    if let Err(e) = body.validate() {
+        return utils::bad_request(&e.to_string());
+    }
+
+    // The creator becomes the first owner
+    let result: Result<Organization, sqlx::Error> = async {
+        let mut tx = pool.begin().await?;
+
+        let organization = sqlx::query_as::<_, Organization>(
+            r#"
+            INSERT INTO organizations (name, description, created_by)
+            VALUES ($1, $2, $3)
+            RETURNING *
+            "#,
+        )
+        .bind(&body.name)
+        .bind(&body.description)
+        .bind(user_id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r#"INSERT INTO organization_members (organization_id, user_id, role) VALUES ($1, $2, $3)"#,
+        )
+        .bind(organization.id)
+        .bind(user_id)
+        .bind(ORG_ROLE_OWNER)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(organization)
+    }
+    .await;
+
+    match result {
+        Ok(organization) => utils::created(organization),
+        Err(e) => {
+            tracing::error!("Failed to create organization: {}", e);
+            utils::internal_error("Failed to create organization")
+        }
+    }
+}
+
+/// Organizations the user belongs to, with their role in each.
+pub async fn list_organizations(pool: web::Data<PgPool>, req: HttpRequest) -> HttpResponse {
+    let user_id = match utils::get_user_id(&req) {
+        Some(id) => id,
+        None => return utils::unauthorized("User ID required"),
+    };
+
+    let result = sqlx::query_as::<_, OrganizationWithRole>(
+        r#"
+        SELECT o.*, m.role,
+            (SELECT COUNT(*) FROM organization_members om WHERE om.organization_id = o.id) AS member_count
+        FROM organizations o
+        JOIN organization_members m ON m.organization_id = o.id
+        WHERE m.user_id = $1
+        ORDER BY o.name ASC
+        "#,
+    )
+    .bind(user_id)
+    .fetch_all(pool.get_ref())
+    .await;
+
+    match result {
+        Ok(organizations) => utils::success(organizations),
+        Err(e) => {
+            tracing::error!("Failed to fetch organizations: {}", e);
+            utils::internal_error("Failed to fetch organizations")
+        }
+    }
+}
+
+pub async fn get_organization(
+    pool: web::Data<PgPool>,
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+) -> HttpResponse {
+    let organization_id = path.into_inner();
+    let user_id = match utils::get_user_id(&req) {
+        Some(id) => id,
+        None => return utils::unauthorized("User ID required"),
+    };
+
+    let result = sqlx::query_as::<_, OrganizationWithRole>(
+        r#"
+        SELECT o.*, m.role,
+            (SELECT COUNT(*) FROM organization_members om WHERE om.organization_id = o.id) AS member_count
+        FROM organizations o
+        JOIN organization_members m ON m.organization_id = o.id
+        WHERE o.id = $1 AND m.user_id = $2
+        "#,
+    )
+    .bind(organization_id)
+    .bind(user_id)
+    .fetch_optional(pool.get_ref())
+    .await;
+
+    match result {
+        Ok(Some(organization)) => utils::success(organization),
+        Ok(None) => utils::not_found("Organization not found"),
+        Err(e) => {
+            tracing::error!("Failed to fetch organization: {}", e);
+            utils::internal_error("Failed to fetch organization")
+        }
+    }
+}
+
+pub async fn list_org_members(
+    pool: web::Data<PgPool>,
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+) -> HttpResponse {
+    let organization_id = path.into_inner();
+    let user_id = match utils::get_user_id(&req) {
+        Some(id) => id,
+        None => return utils::unauthorized("User ID required"),
+    };
+    if let Err(resp) = require_org_role(pool.get_ref(), organization_id, user_id, ORG_MEMBER_ROLES).await {
+        return resp;
+    }
+
+    let result = sqlx::query_as::<_, OrganizationMember>(
+        r#"
+        SELECT m.user_id, u.name, u.avatar, m.role, m.joined_at
+        FROM organization_members m
+        JOIN users u ON u.id = m.user_id
+        WHERE m.organization_id = $1
+        ORDER BY CASE m.role WHEN 'owner' THEN 0 WHEN 'instructor' THEN 1 ELSE 2 END, u.name ASC
+        "#,
+    )
+    .bind(organization_id)
+    .fetch_all(pool.get_ref())
+    .await;
+
+    match result {
+        Ok(members) => utils::success(members),
+        Err(e) => {
+            tracing::error!("Failed to fetch organization members: {}", e);
+            utils::internal_error("Failed to fetch organization members")
+        }
+    }
+}
+
+/// Adds a member or changes an existing member's role. Owners only.
+pub async fn add_org_member(
+    pool: web::Data<PgPool>,
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+    body: web::Json<AddOrgMemberRequest>,
+) -> HttpResponse {
+    let organization_id = path.into_inner();
+    let user_id = match utils::get_user_id(&req) {
+        Some(id) => id,
+        None => return utils::unauthorized("User ID required"),
+    };
+    if let Err(resp) = require_org_role(pool.get_ref(), organization_id, user_id, &[ORG_ROLE_OWNER]).await {
+        return resp;
+    }
+
+    let role = body.role.as_deref().unwrap_or(ORG_ROLE_LEARNER);
+    if !ORG_ROLES.contains(&role) {
+        return utils::bad_request("role must be owner, instructor or learner");
+    }
+
+    let user_exists = sqlx::query_scalar::<_, bool>(
+        r#"SELECT EXISTS(SELECT 1 FROM users WHERE id = $1 AND status = 'active')"#,
+    )
+    .bind(body.user_id)
+    .fetch_one(pool.get_ref())
+    .await
+    .unwrap_or(false);
+
+    if !user_exists {
+        return utils::not_found("User not found");
+    }
+
+    if role != ORG_ROLE_OWNER && is_last_owner(pool.get_ref(), organization_id, body.user_id).await {
+        return utils::bad_request("An organization needs at least one owner");
+    }
+
+    let result = sqlx::query(
+        r#"
+        INSERT INTO organization_members (organization_id, user_id, role)
+        VALUES ($1, $2, $3)
+        ON CONFLICT (organization_id, user_id) DO UPDATE SET role = EXCLUDED.role
+        "#,
+    )
+    .bind(organization_id)
+    .bind(body.user_id)
+    .bind(role)
+    .execute(pool.get_ref())
+    .await;
+
+    if let Err(e) = result {
+        tracing::error!("Failed to add organization member: {}", e);
+        return utils::internal_error("Failed to add organization member");
+    }
+
+    let member = sqlx::query_as::<_, OrganizationMember>(
+        r#"
+        SELECT m.user_id, u.name, u.avatar, m.role, m.joined_at
+        FROM organization_members m
+        JOIN users u ON u.id = m.user_id
+        WHERE m.organization_id = $1 AND m.user_id = $2
+        "#,
+    )
+    .bind(organization_id)
+    .bind(body.user_id)
+    .fetch_one(pool.get_ref())
+    .await;
+
+    match member {
+        Ok(member) => utils::success(member),
+        Err(e) => {
+            tracing::error!("Failed to fetch organization member: {}", e);
+            utils::internal_error("Failed to add organization member")
+        }
+    }
+}
+
+/// Removes a member and their group memberships. Owners may remove anyone;
+/// other members may only leave themselves.
+pub async fn remove_org_member(
+    pool: web::Data<PgPool>,
+    req: HttpRequest,
+    path: web::Path<(Uuid, Uuid)>,
+) -> HttpResponse {
+    let (organization_id, member_id) = path.into_inner();
+    let user_id = match utils::get_user_id(&req) {
+        Some(id) => id,
+        None => return utils::unauthorized("User ID required"),
+    };
+    let roles: &[&str] = if member_id == user_id { ORG_MEMBER_ROLES } else { &[ORG_ROLE_OWNER] };
+    if let Err(resp) = require_org_role(pool.get_ref(), organization_id, user_id, roles).await {
+        return resp;
+    }
+
+    if is_last_owner(pool.get_ref(), organization_id, member_id).await {
+        return utils::bad_request("An organization needs at least one owner");
+    }
+
+    let result: Result<u64, sqlx::Error> = async {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query(
+            r#"
+            DELETE FROM org_group_members gm
+            USING org_groups g
+            WHERE g.id = gm.group_id AND g.organization_id = $1 AND gm.user_id = $2
+            "#,
+        )
+        .bind(organization_id)
+        .bind(member_id)
+        .execute(&mut *tx)
+        .await?;
+
+        let removed = sqlx::query(
+            r#"DELETE FROM organization_members WHERE organization_id = $1 AND user_id = $2"#,
+        )
+        .bind(organization_id)
+        .bind(member_id)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+        tx.commit().await?;
+        Ok(removed)
+    }
+    .await;
+
+    match result {
+        Ok(0) => utils::not_found("Member not found"),
+        Ok(_) => utils::no_content(),
+        Err(e) => {
+            tracing::error!("Failed to remove organization member: {}", e);
+            utils::internal_error("Failed to remove organization member")
+        }
+    }
+}
+
+pub async fn list_org_groups(
+    pool: web::Data<PgPool>,
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+) -> HttpResponse {
+    let organization_id = path.into_inner();
+    let user_id = match utils::get_user_id(&req) {
+        Some(id) => id,
+        None => return utils::unauthorized("User ID required"),
+    };
+    if let Err(resp) = require_org_role(pool.get_ref(), organization_id, user_id, ORG_MEMBER_ROLES).await {
+        return resp;
+    }
+
+    let result = sqlx::query_as::<_, OrgGroupWithCount>(
+        r#"
+        SELECT g.*, (SELECT COUNT(*) FROM org_group_members gm WHERE gm.group_id = g.id) AS member_count
+        FROM org_groups g
+        WHERE g.organization_id = $1
+        ORDER BY g.name ASC
+        "#,
+    )
+    .bind(organization_id)
+    .fetch_all(pool.get_ref())
+    .await;
+
+    match result {
+        Ok(groups) => utils::success(groups),
+        Err(e) => {
+            tracing::error!("Failed to fetch groups: {}", e);
+            utils::internal_error("Failed to fetch groups")
+        }
+    }
+}
+
+pub async fn create_org_group(
+    pool: web::Data<PgPool>,
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+    body: web::Json<CreateOrgGroupRequest>,
+) -> HttpResponse {
+    let organization_id = path.into_inner();
+    let user_id = match utils::get_user_id(&req) {
+        Some(id) => id,
+        None => return utils::unauthorized("User ID required"),
+    };
+    if let Err(resp) = require_org_role(pool.get_ref(), organization_id, user_id, ORG_STAFF_ROLES).await {
+        return resp;
+    }
+
+    if let Err(e) = body.validate() {
+        return utils::bad_request(&e.to_string());
+    }
+
+    let result = sqlx::query_as::<_, OrgGroup>(
+        r#"
+        INSERT INTO org_groups (organization_id, name)
+        VALUES ($1, $2)
+        ON CONFLICT (organization_id, name) DO NOTHING
+        RETURNING *
+        "#,
+    )
+    .bind(organization_id)
+    .bind(&body.name)
+    .fetch_optional(pool.get_ref())
+    .await;
+
+    match result {
+        Ok(Some(group)) => utils::created(group),
+        Ok(None) => utils::conflict("Group name already taken"),
+        Err(e) => {
+            tracing::error!("Failed to create group: {}", e);
+            utils::internal_error("Failed to create group")
+        }
+    }
+}
+
+pub async fn add_group_member(
+    pool: web::Data<PgPool>,
+    req: HttpRequest,
+    path: web::Path<(Uuid, Uuid)>,
+    body: web::Json<AddGroupMemberRequest>,
+) -> HttpResponse {
+    let (organization_id, group_id) = path.into_inner();
+    let user_id = match utils::get_user_id(&req) {
+        Some(id) => id,
+        None => return utils::unauthorized("User ID required"),
+    };
+    if let Err(resp) = require_org_role(pool.get_ref(), organization_id, user_id, ORG_STAFF_ROLES).await {
+        return resp;
+    }
+    if let Err(resp) = fetch_group(pool.get_ref(), organization_id, group_id).await {
+        return resp;
+    }
+
+    let is_member = sqlx::query_scalar::<_, bool>(
+        r#"SELECT EXISTS(SELECT 1 FROM organization_members WHERE organization_id = $1 AND user_id = $2)"#,
+    )
+    .bind(organization_id)
+    .bind(body.user_id)
+    .fetch_one(pool.get_ref())
+    .await
+    .unwrap_or(false);
+
+    if !is_member {
+        return utils::bad_request("User is not a member of this organization");
+    }
+
+    let result = sqlx::query(
+        r#"
+        INSERT INTO org_group_members (group_id, user_id)
+        VALUES ($1, $2)
+        ON CONFLICT (group_id, user_id) DO NOTHING
+        "#,
+    )
+    .bind(group_id)
+    .bind(body.user_id)
+    .execute(pool.get_ref())
+    .await;
+
+    match result {
+        Ok(r) if r.rows_affected() == 0 => utils::conflict("Already in group"),
+        Ok(_) => utils::no_content(),
+        Err(e) => {
+            tracing::error!("Failed to add group member: {}", e);
+            utils::internal_error("Failed to add group member")
+        }
+    }
+}
+
+pub async fn remove_group_member(
+    pool: web::Data<PgPool>,
+    req: HttpRequest,
+    path: web::Path<(Uuid, Uuid, Uuid)>,
+) -> HttpResponse {
+    let (organization_id, group_id, member_id) = path.into_inner();
+    let user_id = match utils::get_user_id(&req) {
+        Some(id) => id,
+        None => return utils::unauthorized("User ID required"),
+    };
+    if let Err(resp) = require_org_role(pool.get_ref(), organization_id, user_id, ORG_STAFF_ROLES).await {
+        return resp;
+    }
+    if let Err(resp) = fetch_group(pool.get_ref(), organization_id, group_id).await {
+        return resp;
+    }
+
+    let result = sqlx::query(r#"DELETE FROM org_group_members WHERE group_id = $1 AND user_id = $2"#)
+        .bind(group_id)
+        .bind(member_id)
+        .execute(pool.get_ref())
+        .await;
+
+    match result {
+        Ok(r) if r.rows_affected() == 0 => utils::not_found("Member not found"),
+        Ok(_) => utils::no_content(),
+        Err(e) => {
+            tracing::error!("Failed to remove group member: {}", e);
+            utils::internal_error("Failed to remove group member")
+        }
+    }
+}
+
+/// The organization's private challenges.
+pub async fn list_org_challenges(
+    pool: web::Data<PgPool>,
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+) -> HttpResponse {
+    let organization_id = path.into_inner();
+    let user_id = match utils::get_user_id(&req) {
+        Some(id) => id,
+        None => return utils::unauthorized("User ID required"),
+    };
+    if let Err(resp) = require_org_role(pool.get_ref(), organization_id, user_id, ORG_MEMBER_ROLES).await {
+        return resp;
+    }
+
+    let result = sqlx::query_as::<_, Challenge>(
+        r#"
+        SELECT * FROM challenges
+        WHERE organization_id = $1 AND status = 'active'
+        ORDER BY created_at DESC
+        "#,
+    )
+    .bind(organization_id)
+    .fetch_all(pool.get_ref())
+    .await;
+
+    match result {
+        Ok(challenges) => utils::success(challenges),
+        Err(e) => {
+            tracing::error!("Failed to fetch challenges: {}", e);
+            utils::internal_error("Failed to fetch challenges")
+        }
+    }
+}
+
+/// Creates a challenge only members of the organization can see and answer.
+pub async fn create_org_challenge(
+    pool: web::Data<PgPool>,
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+    body: web::Json<CreateChallengeRequest>,
+) -> HttpResponse {
+    let organization_id = path.into_inner();
+    let user_id = match utils::get_user_id(&req) {
+        Some(id) => id,
+        None => return utils::unauthorized("User ID required"),
+    };
+    if let Err(resp) = require_org_role(pool.get_ref(), organization_id, user_id, ORG_STAFF_ROLES).await {
+        return resp;
+    }
+
+    insert_challenge(pool.get_ref(), &body, Some(organization_id), None).await
+}
+
+/// Staff see every assignment; learners see those of their groups. Each
+/// carries the viewer's own submission status when they are in the group.
+pub async fn list_assignments(
+    pool: web::Data<PgPool>,
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+) -> HttpResponse {
+    let organization_id = path.into_inner();
+    let user_id = match utils::get_user_id(&req) {
+        Some(id) => id,
+        None => return utils::unauthorized("User ID required"),
+    };
+    let role = match require_org_role(pool.get_ref(), organization_id, user_id, ORG_MEMBER_ROLES).await {
+        Ok(r) => r,
+        Err(resp) => return resp,
+    };
+
+    let result = sqlx::query_as::<_, AssignmentWithStatus>(&format!(
+        r#"
+        SELECT asg.*, ch.title AS challenge_title, g.name AS group_name,
+            CASE WHEN gm.user_id IS NULL THEN NULL ELSE {} END AS status
+        FROM assignments asg
+        JOIN challenges ch ON ch.id = asg.challenge_id
+        JOIN org_groups g ON g.id = asg.group_id
+        LEFT JOIN org_group_members gm ON gm.group_id = asg.group_id AND gm.user_id = $2
+        LEFT JOIN LATERAL ({}) sub ON TRUE
+        WHERE asg.organization_id = $1 AND ($3 OR gm.user_id IS NOT NULL)
+        ORDER BY asg.deadline_at ASC
+        "#,
+        SUBMISSION_STATUS,
+        submission_for("$2")
+    ))
+    .bind(organization_id)
+    .bind(user_id)
+    .bind(ORG_STAFF_ROLES.contains(&role.as_str()))
+    .fetch_all(pool.get_ref())
+    .await;
+
+    match result {
+        Ok(assignments) => utils::success(assignments),
+        Err(e) => {
+            tracing::error!("Failed to fetch assignments: {}", e);
+            utils::internal_error("Failed to fetch assignments")
+        }
+    }
+}
+
+pub async fn create_assignment(
+    pool: web::Data<PgPool>,
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+    body: web::Json<CreateAssignmentRequest>,
+) -> HttpResponse {
+    let organization_id = path.into_inner();
+    let user_id = match utils::get_user_id(&req) {
+        Some(id) => id,
+        None => return utils::unauthorized("User ID required"),
+    };
+    if let Err(resp) = require_org_role(pool.get_ref(), organization_id, user_id, ORG_STAFF_ROLES).await {
+        return resp;
+    }
+    if let Err(resp) = fetch_group(pool.get_ref(), organization_id, body.group_id).await {
+        return resp;
+    }
+
+    if body.deadline_at <= chrono::Utc::now() {
+        return utils::bad_request("deadline_at must be in the future");
+    }
+
+    // Public challenges can be assigned too, but not another organization's
+    let challenge_ok = sqlx::query_scalar::<_, bool>(
+        r#"
+        SELECT EXISTS(
+            SELECT 1 FROM challenges
+            WHERE id = $1 AND status = 'active' AND contest_id IS NULL AND (organization_id IS NULL OR organization_id = $2)
+        )
+        "#,
+    )
+    .bind(body.challenge_id)
+    .bind(organization_id)
+    .fetch_one(pool.get_ref())
+    .await
+    .unwrap_or(false);
+
+    if !challenge_ok {
+        return utils::bad_request("Challenge not found");
+    }
+
+    let result = sqlx::query_as::<_, Assignment>(
+        r#"
+        INSERT INTO assignments (organization_id, group_id, challenge_id, deadline_at, created_by)
+        VALUES ($1, $2, $3, $4, $5)
+        ON CONFLICT (group_id, challenge_id) DO NOTHING
+        RETURNING *
+        "#,
+    )
+    .bind(organization_id)
+    .bind(body.group_id)
+    .bind(body.challenge_id)
+    .bind(body.deadline_at)
+    .bind(user_id)
+    .fetch_optional(pool.get_ref())
+    .await;
+
+    match result {
+        Ok(Some(assignment)) => utils::created(assignment),
+        Ok(None) => utils::conflict("Challenge already assigned to this group"),
+        Err(e) => {
+            tracing::error!("Failed to create assignment: {}", e);
+            utils::internal_error("Failed to create assignment")
+        }
+    }
+}
+
+/// Submission status of every learner in the assignment's group. Staff only.
+pub async fn get_assignment_submissions(
+    pool: web::Data<PgPool>,
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+) -> HttpResponse {
+    let assignment_id = path.into_inner();
+    let user_id = match utils::get_user_id(&req) {
+        Some(id) => id,
+        None => return utils::unauthorized("User ID required"),
+    };
+
+    let assignment = match fetch_staff_assignment(pool.get_ref(), assignment_id, user_id).await {
+        Ok(a) => a,
+        Err(resp) => return resp,
+    };
+
+    match assignment_submissions(pool.get_ref(), assignment.id).await {
+        Ok(submissions) => utils::success(submissions),
+        Err(e) => {
+            tracing::error!("Failed to fetch submissions: {}", e);
+            utils::internal_error("Failed to fetch submissions")
+        }
+    }
+}
+
+/// Fetches an assignment the user is staff for.
+pub async fn fetch_staff_assignment(
+    pool: &PgPool,
+    assignment_id: Uuid,
+    user_id: Uuid,
+) -> Result<Assignment, HttpResponse> {
+    let assignment = sqlx::query_as::<_, Assignment>(r#"SELECT * FROM assignments WHERE id = $1"#)
+        .bind(assignment_id)
+        .fetch_optional(pool)
+        .await;
+
+    let assignment = match assignment {
+        Ok(Some(a)) => a,
+        Ok(None) => return Err(utils::not_found("Assignment not found")),
+        Err(e) => {
+            tracing::error!("Failed to fetch assignment: {}", e);
+            return Err(utils::internal_error("Failed to fetch assignment"));
+        }
+    };
+
+    require_org_role(pool, assignment.organization_id, user_id, ORG_STAFF_ROLES).await?;
+    Ok(assignment)
+}
+
+/// Submission of every learner in the assignment's group, submitters first.
+pub async fn assignment_submissions(
+    pool: &PgPool,
+    assignment_id: Uuid,
+) -> Result<Vec<AssignmentSubmission>, sqlx::Error> {
+    sqlx::query_as::<_, AssignmentSubmission>(&format!(
+        r#"
+        SELECT u.id AS user_id, u.name, u.avatar, {} AS status,
+            sub.answer_id, sub.submitted_at, sub.score, sub.human_score
+        FROM assignments asg
+        JOIN org_group_members gm ON gm.group_id = asg.group_id
+        JOIN users u ON u.id = gm.user_id
+        LEFT JOIN LATERAL ({}) sub ON TRUE
+        WHERE asg.id = $1
+        ORDER BY sub.submitted_at ASC NULLS LAST, u.name ASC
+        "#,
+        SUBMISSION_STATUS,
+        submission_for("gm.user_id")
+    ))
+    .bind(assignment_id)
+    .fetch_all(pool)
+    .await
+}
+
+/// Learners ranked by average score on the organization's private challenges.
+/// Only members can see it, and none of it shows up in public rankings.
+pub async fn get_org_leaderboard(
+    pool: web::Data<PgPool>,
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+    query: web::Query<OrgLeaderboardQueryParams>,
+) -> HttpResponse {
+    let organization_id = path.into_inner();
+    let user_id = match utils::get_user_id(&req) {
+        Some(id) => id,
+        None => return utils::unauthorized("User ID required"),
+    };
+    if let Err(resp) = require_org_role(pool.get_ref(), organization_id, user_id, ORG_MEMBER_ROLES).await {
+        return resp;
+    }
+
+    let since = match query.period.as_deref().unwrap_or("all") {
+        "day" => "NOW() - INTERVAL '1 day'",
+        "week" => "NOW() - INTERVAL '7 days'",
+        "all" => "'-infinity'::timestamptz",
+        _ => return utils::bad_request("period must be day, week or all"),
+    };
+
+    let result = sqlx::query_as::<_, OrgLeaderboardEntry>(&format!(
+        r#"
+        SELECT u.id AS user_id, u.name, u.avatar,
+            COUNT(a.id) AS answer_count,
+            AVG(a.score)::DOUBLE PRECISION AS average_score,
+            COALESCE(SUM(a.like_count), 0)::BIGINT AS like_count
+        FROM organization_members m
+        JOIN users u ON u.id = m.user_id
+        LEFT JOIN answers a ON a.user_id = m.user_id
+            AND a.status = 'active' AND a.is_published AND a.created_at > {}
+            AND a.challenge_id IN (SELECT id FROM challenges WHERE organization_id = $1)
+        WHERE m.organization_id = $1 AND m.role = $2
+        GROUP BY u.id, u.name, u.avatar
+        ORDER BY average_score DESC NULLS LAST, like_count DESC, answer_count DESC
+        "#,
+        since
+    ))
+    .bind(organization_id)
+    .bind(ORG_ROLE_LEARNER)
+    .fetch_all(pool.get_ref())
+    .await;
+
+    match result {
+        Ok(entries) => utils::success(entries),
+        Err(e) => {
+            tracing::error!("Failed to fetch organization leaderboard: {}", e);
+            utils::internal_error("Failed to fetch organization leaderboard")
+        }
+    }
+}
+
+/// Ensures the user belongs to the organization with one of `roles` and
+/// returns their role. Outsiders get a not found so private organizations
+/// don't reveal themselves.
+async fn require_org_role(
+    pool: &PgPool,
+    organization_id: Uuid,
+    user_id: Uuid,
+    roles: &[&str],
+) -> Result<String, HttpResponse> {
+    let role = sqlx::query_scalar::<_, String>(
+        r#"SELECT role FROM organization_members WHERE organization_id = $1 AND user_id = $2"#,
+    )
+    .bind(organization_id)
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await;
+
+    match role {
+        Ok(Some(role)) if roles.contains(&role.as_str()) => Ok(role),
+        Ok(Some(_)) => Err(utils::forbidden("Insufficient permissions")),
+        Ok(None) => Err(utils::not_found("Organization not found")),
+        Err(e) => {
+            tracing::error!("Failed to fetch organization role: {}", e);
+            Err(utils::internal_error("Failed to verify permissions"))
+        }
+    }
+}
+
+/// Whether `staff_id` is an owner or instructor of an organization the
+/// learner belongs to, and the challenge is that organization's own or
+/// assigned within it. Staff never instruct themselves.
+pub async fn instructs(pool: &PgPool, staff_id: Uuid, learner_id: Uuid, challenge_id: Uuid) -> bool {
+    sqlx::query_scalar::<_, bool>(
+        r#"
+        SELECT EXISTS(
+            SELECT 1
+            FROM organization_members staff
+            JOIN organization_members learner
+                ON learner.organization_id = staff.organization_id AND learner.user_id = $2
+            JOIN challenges ch ON ch.id = $3
+            WHERE staff.user_id = $1 AND staff.user_id <> $2 AND staff.role = ANY($4)
+              AND (ch.organization_id = staff.organization_id OR EXISTS (
+                  SELECT 1 FROM assignments asg
+                  WHERE asg.organization_id = staff.organization_id AND asg.challenge_id = ch.id
+              ))
+        )
+        "#,
+    )
+    .bind(staff_id)
+    .bind(learner_id)
+    .bind(challenge_id)
+    .bind(ORG_STAFF_ROLES)
+    .fetch_one(pool)
+    .await
+    .unwrap_or(false)
+}
+
+/// Whether the user is the organization's only owner.
+async fn is_last_owner(pool: &PgPool, organization_id: Uuid, user_id: Uuid) -> bool {
+    sqlx::query_scalar::<_, bool>(
+        r#"
+        SELECT COALESCE(BOOL_AND(user_id = $2), FALSE)
+        FROM organization_members
+        WHERE organization_id = $1 AND role = 'owner'
+        "#,
+    )
+    .bind(organization_id)
+    .bind(user_id)
+    .fetch_one(pool)
+    .await
+    .unwrap_or(false)
+}
+
+async fn fetch_group(pool: &PgPool, organization_id: Uuid, group_id: Uuid) -> Result<OrgGroup, HttpResponse> {
+    let group = sqlx::query_as::<_, OrgGroup>(
+        r#"SELECT * FROM org_groups WHERE id = $1 AND organization_id = $2"#,
+    )
+    .bind(group_id)
+    .bind(organization_id)
+    .fetch_optional(pool)
+    .await;
+
+    match group {
+        Ok(Some(g)) => Ok(g),
+        Ok(None) => Err(utils::not_found("Group not found")),
+        Err(e) => {
+            tracing::error!("Failed to fetch group: {}", e);
+            Err(utils::internal_error("Failed to fetch group"))
+        }
+    }
+}
+
+/// Lateral subquery with the user's attempts at the assignment row `asg`'s
+/// challenge: first submission time, best AI and human scores and the
+/// published attempt.
+fn submission_for(user_expr: &str) -> String {
+    format!(
+        r#"
+        SELECT MIN(a.created_at) AS submitted_at, MAX(a.score) AS score, MAX(a.human_score) AS human_score,
+            (ARRAY_AGG(a.id ORDER BY a.is_published DESC, a.created_at DESC))[1] AS answer_id
+        FROM answers a
+        WHERE a.challenge_id = asg.challenge_id AND a.user_id = {} AND a.status <> 'deleted'
+        "#,
+        user_expr
+    )
+}
@@ -0,0 +1,339 @@
+use actix_web::{web, HttpRequest, HttpResponse};
+use sqlx::PgPool;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::config::{Config, PeerReviewConfig};
+use crate::handlers::review::{challenge_rubric, score_on_rubric};
+use crate::models::{PeerReviewQueryParams, PeerReviewTask, SubmitReviewRequest};
+use crate::utils;
+
+/// Reliability never drops below this, so every completed review still counts a little.
+const RELIABILITY_FLOOR: f64 = 0.2;
+/// Average distance from consensus (in score points) at which reliability reaches zero
+/// before the floor applies.
+const RELIABILITY_SCALE: f64 = 50.0;
+
+const TASK_COLUMNS: &str = r#"
+    p.id, a.challenge_id, ch.title AS challenge_title, a.content,
+    p.scores, p.total_score, p.feedback, p.assigned_at, p.completed_at
+"#;
+
+/// Tops up the user's peer review tasks for a challenge they answered, e.g.
+/// when too few other answers existed at submission time.
+pub async fn request_peer_reviews(
+    pool: web::Data<PgPool>,
+    config: web::Data<Config>,
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+) -> HttpResponse {
+    let challenge_id = path.into_inner();
+    let user_id = match utils::get_user_id(&req) {
+        Some(id) => id,
+        None => return utils::unauthorized("User ID required"),
+    };
+
+    let answered = sqlx::query_scalar::<_, bool>(
+        r#"SELECT EXISTS(SELECT 1 FROM answers WHERE challenge_id = $1 AND user_id = $2 AND status <> 'deleted')"#,
+    )
+    .bind(challenge_id)
+    .bind(user_id)
+    .fetch_one(pool.get_ref())
+    .await
+    .unwrap_or(false);
+
+    if !answered {
+        return utils::bad_request("Answer the challenge before reviewing others");
+    }
+
+    match challenge_rubric(pool.get_ref(), challenge_id).await {
+        Ok(r) if r.is_empty() => return utils::bad_request("Category has no rubric"),
+        Ok(_) => {}
+        Err(e) => {
+            tracing::error!("Failed to fetch rubric: {}", e);
+            return utils::internal_error("Failed to assign peer reviews");
+        }
+    }
+
+    assign_peer_reviews(pool.get_ref(), &config.peer_review, user_id, challenge_id).await;
+
+    let result = sqlx::query_as::<_, PeerReviewTask>(&format!(
+        r#"
+        SELECT {} FROM peer_reviews p
+        JOIN answers a ON a.id = p.answer_id
+        JOIN challenges ch ON ch.id = a.challenge_id
+        WHERE p.reviewer_id = $1 AND a.challenge_id = $2 AND a.status = 'active'
+        ORDER BY p.assigned_at ASC
+        "#,
+        TASK_COLUMNS
+    ))
+    .bind(user_id)
+    .bind(challenge_id)
+    .fetch_all(pool.get_ref())
+    .await;
+
+    match result {
+        Ok(tasks) => utils::success(tasks),
+        Err(e) => {
+            tracing::error!("Failed to fetch peer reviews: {}", e);
+            utils::internal_error("Failed to fetch peer reviews")
+        }
+    }
+}
+
+pub async fn list_peer_reviews(
+    pool: web::Data<PgPool>,
+    req: HttpRequest,
+    query: web::Query<PeerReviewQueryParams>,
+) -> HttpResponse {
+    let user_id = match utils::get_user_id(&req) {
+        Some(id) => id,
+        None => return utils::unauthorized("User ID required"),
+    };
+
+    let filter = match query.status.as_deref().unwrap_or("pending") {
+        "pending" => "AND p.completed_at IS NULL",
+        "completed" => "AND p.completed_at IS NOT NULL",
+        "all" => "",
+        _ => return utils::bad_request("status must be pending, completed or all"),
+    };
+
+    let result = sqlx::query_as::<_, PeerReviewTask>(&format!(
+        r#"
+        SELECT {} FROM peer_reviews p
+        JOIN answers a ON a.id = p.answer_id
+        JOIN challenges ch ON ch.id = a.challenge_id
+        WHERE p.reviewer_id = $1 AND a.status = 'active' {}
+        ORDER BY p.assigned_at ASC
+        "#,
+        TASK_COLUMNS, filter
+    ))
+    .bind(user_id)
+    .fetch_all(pool.get_ref())
+    .await;
+
+    match result {
+        Ok(tasks) => utils::success(tasks),
+        Err(e) => {
+            tracing::error!("Failed to fetch peer reviews: {}", e);
+            utils::internal_error("Failed to fetch peer reviews")
+        }
+    }
+}
+
+/// Scores an assigned answer on the rubric, then refreshes the answer's peer
+/// score and its reviewers' reliability.
+pub async fn submit_peer_review(
+    pool: web::Data<PgPool>,
+    config: web::Data<Config>,
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+    body: web::Json<SubmitReviewRequest>,
+) -> HttpResponse {
+    let task_id = path.into_inner();
+    let user_id = match utils::get_user_id(&req) {
+        Some(id) => id,
+        None => return utils::unauthorized("User ID required"),
+    };
+
+    if let Err(e) = body.validate() {
+        return utils::bad_request(&e.to_string());
+    }
+
+    let task = sqlx::query_as::<_, (Uuid, Uuid, bool)>(
+        r#"
+        SELECT p.answer_id, a.challenge_id, p.completed_at IS NOT NULL
+        FROM peer_reviews p
+        JOIN answers a ON a.id = p.answer_id
+        WHERE p.id = $1 AND p.reviewer_id = $2 AND a.status = 'active'
+        "#,
+    )
+    .bind(task_id)
+    .bind(user_id)
+    .fetch_optional(pool.get_ref())
+    .await;
+
+    let (answer_id, challenge_id) = match task {
+        Ok(Some((_, _, true))) => return utils::conflict("Review already submitted"),
+        Ok(Some((answer_id, challenge_id, false))) => (answer_id, challenge_id),
+        Ok(None) => return utils::not_found("Peer review not found"),
+        Err(e) => {
+            tracing::error!("Failed to fetch peer review: {}", e);
+            return utils::internal_error("Failed to submit peer review");
+        }
+    };
+
+    let rubric = match challenge_rubric(pool.get_ref(), challenge_id).await {
+        Ok(r) if r.is_empty() => return utils::bad_request("Category has no rubric"),
+        Ok(r) => r,
+        Err(e) => {
+            tracing::error!("Failed to fetch rubric: {}", e);
+            return utils::internal_error("Failed to submit peer review");
+        }
+    };
+
+    let (scores, total_score) = match score_on_rubric(&rubric, &body.scores) {
+        Ok(r) => r,
+        Err(message) => return utils::bad_request(&message),
+    };
+    let scores = match serde_json::to_value(&scores) {
+        Ok(v) => v,
+        Err(e) => {
+            tracing::error!("Failed to serialize review scores: {}", e);
+            return utils::internal_error("Failed to submit peer review");
+        }
+    };
+
+    let result = sqlx::query(
+        r#"
+        UPDATE peer_reviews
+        SET scores = $1, total_score = $2, feedback = $3, completed_at = NOW()
+        WHERE id = $4 AND completed_at IS NULL
+        "#,
+    )
+    .bind(&scores)
+    .bind(total_score)
+    .bind(&body.feedback)
+    .bind(task_id)
+    .execute(pool.get_ref())
+    .await;
+
+    match result {
+        Ok(r) if r.rows_affected() == 0 => return utils::conflict("Review already submitted"),
+        Ok(_) => {}
+        Err(e) => {
+            tracing::error!("Failed to submit peer review: {}", e);
+            return utils::internal_error("Failed to submit peer review");
+        }
+    }
+
+    refresh_peer_score(pool.get_ref(), &config.peer_review, answer_id).await;
+
+    let task = sqlx::query_as::<_, PeerReviewTask>(&format!(
+        r#"
+        SELECT {} FROM peer_reviews p
+        JOIN answers a ON a.id = p.answer_id
+        JOIN challenges ch ON ch.id = a.challenge_id
+        WHERE p.id = $1
+        "#,
+        TASK_COLUMNS
+    ))
+    .bind(task_id)
+    .fetch_one(pool.get_ref())
+    .await;
+
+    match task {
+        Ok(task) => utils::success(task),
+        Err(e) => {
+            tracing::error!("Failed to fetch peer review: {}", e);
+            utils::internal_error("Failed to submit peer review")
+        }
+    }
+}
+
+/// Hands the user other people's answers to the challenge until they have
+/// `review_count` tasks for it. Answers with the fewest reviewers go first.
+/// Users never get their own answers, nor answers from anyone they follow or
+/// who follows them, and only public answers from public accounts are shared.
+/// Contest entries wait until the contest closes for entries. Does nothing
+/// when the challenge's category has no rubric.
+pub async fn assign_peer_reviews(pool: &PgPool, config: &PeerReviewConfig, user_id: Uuid, challenge_id: Uuid) {
+    let result = sqlx::query(
+        r#"
+        INSERT INTO peer_reviews (answer_id, reviewer_id)
+        SELECT a.id, $1
+        FROM answers a
+        WHERE a.challenge_id = $2 AND a.status = 'active' AND a.is_published
+          AND a.visibility = 'public' AND a.user_id <> $1
+          AND NOT EXISTS (SELECT 1 FROM users au WHERE au.id = a.user_id AND au.is_private)
+          AND NOT EXISTS (
+              SELECT 1 FROM follows f
+              WHERE (f.follower_id = $1 AND f.following_id = a.user_id)
+                 OR (f.follower_id = a.user_id AND f.following_id = $1)
+          )
+          AND NOT EXISTS (SELECT 1 FROM peer_reviews p WHERE p.answer_id = a.id AND p.reviewer_id = $1)
+          AND NOT EXISTS (
+              SELECT 1 FROM challenges kc
+              JOIN contests k ON k.id = kc.contest_id
+              WHERE kc.id = a.challenge_id AND k.entries_close_at > NOW()
+          )
+          AND EXISTS (
+              SELECT 1 FROM rubric_criteria r
+              JOIN challenges ch ON ch.category_id = r.category_id
+              WHERE ch.id = $2
+          )
+        ORDER BY (SELECT COUNT(*) FROM peer_reviews p WHERE p.answer_id = a.id) ASC, RANDOM()
+        LIMIT GREATEST($3 - (
+            SELECT COUNT(*) FROM peer_reviews p
+            JOIN answers pa ON pa.id = p.answer_id
+            WHERE p.reviewer_id = $1 AND pa.challenge_id = $2
+        ), 0)
+        ON CONFLICT (answer_id, reviewer_id) DO NOTHING
+        "#,
+    )
+    .bind(user_id)
+    .bind(challenge_id)
+    .bind(config.review_count)
+    .execute(pool)
+    .await;
+
+    if let Err(e) = result {
+        tracing::error!("Failed to assign peer reviews: {}", e);
+    }
+}
+
+/// Recomputes the answer's peer score as the reliability-weighted average of
+/// its completed reviews. Once it has enough reviews to count as consensus,
+/// each of its reviewers' reliability is re-derived from how far their
+/// scores sit from the consensus across all answers they reviewed.
+async fn refresh_peer_score(pool: &PgPool, config: &PeerReviewConfig, answer_id: Uuid) {
+    let result = sqlx::query(
+        r#"
+        UPDATE answers SET peer_score = agg.score, peer_review_count = agg.n
+        FROM (
+            SELECT ROUND(SUM(p.total_score * u.peer_reliability) / NULLIF(SUM(u.peer_reliability), 0))::INTEGER AS score,
+                COUNT(*)::INTEGER AS n
+            FROM peer_reviews p
+            JOIN users u ON u.id = p.reviewer_id
+            WHERE p.answer_id = $1 AND p.completed_at IS NOT NULL
+        ) agg
+        WHERE answers.id = $1
+        "#,
+    )
+    .bind(answer_id)
+    .execute(pool)
+    .await;
+
+    if let Err(e) = result {
+        tracing::error!("Failed to refresh peer score: {}", e);
+        return;
+    }
+
+    let result = sqlx::query(
+        r#"
+        UPDATE users SET peer_reliability = r.reliability
+        FROM (
+            SELECT p.reviewer_id,
+                GREATEST($2, 1 - AVG(ABS(p.total_score - a.peer_score))::DOUBLE PRECISION / $3) AS reliability
+            FROM peer_reviews p
+            JOIN answers a ON a.id = p.answer_id
+            WHERE p.completed_at IS NOT NULL AND a.peer_review_count >= $4
+              AND p.reviewer_id IN (
+                  SELECT reviewer_id FROM peer_reviews WHERE answer_id = $1 AND completed_at IS NOT NULL
+              )
+            GROUP BY p.reviewer_id
+        ) r
+        WHERE users.id = r.reviewer_id
+        "#,
+    )
+    .bind(answer_id)
+    .bind(RELIABILITY_FLOOR)
+    .bind(RELIABILITY_SCALE)
+    .bind(config.min_reviews)
+    .execute(pool)
+    .await;
+
+    if let Err(e) = result {
+        tracing::error!("Failed to refresh reviewer reliability: {}", e);
+    }
+}
@@ -0,0 +1,378 @@
+use actix_web::{web, HttpRequest, HttpResponse};
+use sqlx::PgPool;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::auth;
+use crate::config::Config;
+use crate::content_filter::{ContentKind, FilterPipeline, Verdict};
+use crate::models::{
+    Challenge, ChallengeProposal, CreateProposalRequest, ProposalQueryParams, ProposalWithAuthor,
+    ReviewProposalRequest,
+};
+use crate::utils;
+
+const PROPOSAL_STATUSES: &[&str] = &["open", "accepted", "rejected"];
+
+/// Proposal row aliased `p` with its author's name and whether the viewer
+/// bound at `$1` upvoted it.
+const PROPOSAL_COLUMNS: &str = r#"
+    p.*, u.name AS author_name,
+    EXISTS(SELECT 1 FROM proposal_upvotes pu WHERE pu.proposal_id = p.id AND pu.user_id = $1) AS is_upvoted
+"#;
+
+pub async fn list_proposals(
+    pool: web::Data<PgPool>,
+    config: web::Data<Config>,
+    req: HttpRequest,
+    query: web::Query<ProposalQueryParams>,
+) -> HttpResponse {
+    let current_user_id = utils::get_user_id(&req);
+    let (page, page_size, offset) = utils::normalize_pagination(
+        query.page,
+        query.page_size,
+        config.pagination.default_page_size,
+        config.pagination.max_page_size,
+    );
+
+    let status = query.status.as_deref().unwrap_or("open");
+    if !PROPOSAL_STATUSES.contains(&status) {
+        return utils::bad_request("status must be open, accepted or rejected");
+    }
+    let order_by = match query.sort.as_deref().unwrap_or("top") {
+        "top" => "p.upvote_count DESC, p.created_at ASC",
+        "new" => "p.created_at DESC",
+        _ => return utils::bad_request("sort must be top or new"),
+    };
+
+    let total: (i64,) = sqlx::query_as(
+        r#"
+        SELECT COUNT(*) FROM challenge_proposals
+        WHERE status = $1 AND ($2::uuid IS NULL OR category_id = $2)
+        "#,
+    )
+    .bind(status)
+    .bind(query.category_id)
+    .fetch_one(pool.get_ref())
+    .await
+    .unwrap_or((0,));
+
+    let result = sqlx::query_as::<_, ProposalWithAuthor>(&format!(
+        r#"
+        SELECT {} FROM challenge_proposals p
+        JOIN users u ON u.id = p.user_id
+        WHERE p.status = $2 AND ($3::uuid IS NULL OR p.category_id = $3)
+        ORDER BY {}
+        LIMIT $4 OFFSET $5
+        "#,
+        PROPOSAL_COLUMNS, order_by
+    ))
+    .bind(current_user_id)
+    .bind(status)
+    .bind(query.category_id)
+    .bind(page_size)
+    .bind(offset)
+    .fetch_all(pool.get_ref())
+    .await;
+
+    match result {
+        Ok(proposals) => utils::paginated(proposals, page, page_size, total.0),
+        Err(e) => {
+            tracing::error!("Failed to fetch proposals: {}", e);
+            utils::internal_error("Failed to fetch proposals")
+        }
+    }
+}
+
+pub async fn create_proposal(
+    pool: web::Data<PgPool>,
+    content_filter: web::Data<FilterPipeline>,
+    req: HttpRequest,
+    body: web::Json<CreateProposalRequest>,
+) -> HttpResponse {
+    let user_id = match utils::get_user_id(&req) {
+        Some(id) => id,
+        None => return utils::unauthorized("User ID required"),
+    };
+
+    if let Err(e) = body.validate() {
+        return utils::bad_request(&e.to_string());
+    }
+
+    if auth::is_suspended(pool.get_ref(), user_id).await {
+        return utils::forbidden("Your account is suspended");
+    }
+
+    let category_exists = sqlx::query_scalar::<_, bool>(
+        r#"SELECT EXISTS(SELECT 1 FROM categories WHERE id = $1 AND status = 'active')"#,
+    )
+    .bind(body.category_id)
+    .fetch_one(pool.get_ref())
+    .await
+    .unwrap_or(false);
+
+    if !category_exists {
+        return utils::bad_request("Category not found");
+    }
+
+    // Editors review every proposal, so only outright rejections stop one here
+    let text = match &body.description {
+        Some(description) => format!("{}\n{}", body.title, description),
+        None => body.title.clone(),
+    };
+    let outcome = content_filter.check(ContentKind::Proposal, &text, &[]);
+    if outcome.verdict == Verdict::Reject {
+        return utils::unprocessable("Content rejected by filter", outcome);
+    }
+
+    let result = sqlx::query_as::<_, ChallengeProposal>(
+        r#"
+        INSERT INTO challenge_proposals (category_id, user_id, title, description)
+        VALUES ($1, $2, $3, $4)
+        RETURNING *
+        "#,
+    )
+    .bind(body.category_id)
+    .bind(user_id)
+    .bind(&body.title)
+    .bind(&body.description)
+    .fetch_one(pool.get_ref())
+    .await;
+
+    match result {
+        Ok(proposal) => utils::created(proposal),
+        Err(e) => {
+            tracing::error!("Failed to create proposal: {}", e);
+            utils::internal_error("Failed to create proposal")
+        }
+    }
+}
+
+pub async fn upvote_proposal(
+    pool: web::Data<PgPool>,
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+) -> HttpResponse {
+    let proposal_id = path.into_inner();
+    let user_id = match utils::get_user_id(&req) {
+        Some(id) => id,
+        None => return utils::unauthorized("User ID required"),
+    };
+
+    let proposal = match fetch_open_proposal(pool.get_ref(), proposal_id).await {
+        Ok(p) => p,
+        Err(resp) => return resp,
+    };
+    if proposal.user_id == user_id {
+        return utils::bad_request("You cannot upvote your own proposal");
+    }
+
+    let result = sqlx::query(
+        r#"
+        INSERT INTO proposal_upvotes (proposal_id, user_id)
+        VALUES ($1, $2)
+        ON CONFLICT (proposal_id, user_id) DO NOTHING
+        "#,
+    )
+    .bind(proposal_id)
+    .bind(user_id)
+    .execute(pool.get_ref())
+    .await;
+
+    match result {
+        Ok(r) if r.rows_affected() == 0 => utils::conflict("Already upvoted"),
+        Ok(_) => {
+            adjust_upvote_count(pool.get_ref(), proposal_id, 1).await;
+            utils::no_content()
+        }
+        Err(e) => {
+            tracing::error!("Failed to upvote proposal: {}", e);
+            utils::internal_error("Failed to upvote proposal")
+        }
+    }
+}
+
+pub async fn remove_proposal_upvote(
+    pool: web::Data<PgPool>,
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+) -> HttpResponse {
+    let proposal_id = path.into_inner();
+    let user_id = match utils::get_user_id(&req) {
+        Some(id) => id,
+        None => return utils::unauthorized("User ID required"),
+    };
+
+    if let Err(resp) = fetch_open_proposal(pool.get_ref(), proposal_id).await {
+        return resp;
+    }
+
+    let result = sqlx::query(r#"DELETE FROM proposal_upvotes WHERE proposal_id = $1 AND user_id = $2"#)
+        .bind(proposal_id)
+        .bind(user_id)
+        .execute(pool.get_ref())
+        .await;
+
+    match result {
+        Ok(r) if r.rows_affected() == 0 => utils::not_found("Upvote not found"),
+        Ok(_) => {
+            adjust_upvote_count(pool.get_ref(), proposal_id, -1).await;
+            utils::no_content()
+        }
+        Err(e) => {
+            tracing::error!("Failed to remove upvote: {}", e);
+            utils::internal_error("Failed to remove upvote")
+        }
+    }
+}
+
+/// Turns an open proposal into a draft challenge in its category, ready for
+/// an editor to polish and publish.
+pub async fn accept_proposal(
+    pool: web::Data<PgPool>,
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+    body: web::Json<ReviewProposalRequest>,
+) -> HttpResponse {
+    let proposal_id = path.into_inner();
+    let user_id = match utils::get_user_id(&req) {
+        Some(id) => id,
+        None => return utils::unauthorized("User ID required"),
+    };
+    if let Err(resp) = auth::require_role(pool.get_ref(), user_id, auth::EDITORIAL_ROLES).await {
+        return resp;
+    }
+
+    if let Err(e) = body.validate() {
+        return utils::bad_request(&e.to_string());
+    }
+
+    let proposal = match fetch_open_proposal(pool.get_ref(), proposal_id).await {
+        Ok(p) => p,
+        Err(resp) => return resp,
+    };
+
+    let result: Result<Option<ChallengeProposal>, sqlx::Error> = async {
+        let mut tx = pool.begin().await?;
+
+        let challenge = sqlx::query_as::<_, Challenge>(
+            r#"
+            INSERT INTO challenges (category_id, title, description, char_limit, status)
+            SELECT c.id, $2, $3, c.char_limit, 'draft'
+            FROM categories c
+            WHERE c.id = $1
+            RETURNING *
+            "#,
+        )
+        .bind(proposal.category_id)
+        .bind(&proposal.title)
+        .bind(&proposal.description)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        let accepted = sqlx::query_as::<_, ChallengeProposal>(
+            r#"
+            UPDATE challenge_proposals
+            SET status = 'accepted', challenge_id = $1, reviewed_by = $2, review_note = $3, reviewed_at = NOW()
+            WHERE id = $4 AND status = 'open'
+            RETURNING *
+            "#,
+        )
+        .bind(challenge.id)
+        .bind(user_id)
+        .bind(&body.note)
+        .bind(proposal_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        if accepted.is_some() {
+            tx.commit().await?;
+        }
+        Ok(accepted)
+    }
+    .await;
+
+    match result {
+        Ok(Some(proposal)) => utils::success(proposal),
+        Ok(None) => utils::conflict("Proposal already reviewed"),
+        Err(e) => {
+            tracing::error!("Failed to accept proposal: {}", e);
+            utils::internal_error("Failed to accept proposal")
+        }
+    }
+}
+
+pub async fn reject_proposal(
+    pool: web::Data<PgPool>,
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+    body: web::Json<ReviewProposalRequest>,
+) -> HttpResponse {
+    let proposal_id = path.into_inner();
+    let user_id = match utils::get_user_id(&req) {
+        Some(id) => id,
+        None => return utils::unauthorized("User ID required"),
+    };
+    if let Err(resp) = auth::require_role(pool.get_ref(), user_id, auth::EDITORIAL_ROLES).await {
+        return resp;
+    }
+
+    if let Err(e) = body.validate() {
+        return utils::bad_request(&e.to_string());
+    }
+
+    if let Err(resp) = fetch_open_proposal(pool.get_ref(), proposal_id).await {
+        return resp;
+    }
+
+    let result = sqlx::query_as::<_, ChallengeProposal>(
+        r#"
+        UPDATE challenge_proposals
+        SET status = 'rejected', reviewed_by = $1, review_note = $2, reviewed_at = NOW()
+        WHERE id = $3 AND status = 'open'
+        RETURNING *
+        "#,
+    )
+    .bind(user_id)
+    .bind(&body.note)
+    .bind(proposal_id)
+    .fetch_optional(pool.get_ref())
+    .await;
+
+    match result {
+        Ok(Some(proposal)) => utils::success(proposal),
+        Ok(None) => utils::conflict("Proposal already reviewed"),
+        Err(e) => {
+            tracing::error!("Failed to reject proposal: {}", e);
+            utils::internal_error("Failed to reject proposal")
+        }
+    }
+}
+
+/// Loads a proposal still open for votes and review.
+async fn fetch_open_proposal(pool: &PgPool, proposal_id: Uuid) -> Result<ChallengeProposal, HttpResponse> {
+    let result = sqlx::query_as::<_, ChallengeProposal>(r#"SELECT * FROM challenge_proposals WHERE id = $1"#)
+        .bind(proposal_id)
+        .fetch_optional(pool)
+        .await;
+
+    match result {
+        Ok(Some(p)) if p.status == "open" => Ok(p),
+        Ok(Some(_)) => Err(utils::conflict("Proposal already reviewed")),
+        Ok(None) => Err(utils::not_found("Proposal not found")),
+        Err(e) => {
+            tracing::error!("Failed to fetch proposal: {}", e);
+            Err(utils::internal_error("Failed to fetch proposal"))
+        }
+    }
+}
+
+async fn adjust_upvote_count(pool: &PgPool, proposal_id: Uuid, delta: i32) {
+    let _ = sqlx::query(
+        r#"UPDATE challenge_proposals SET upvote_count = GREATEST(upvote_count + $1, 0) WHERE id = $2"#,
+    )
+    .bind(delta)
+    .bind(proposal_id)
+    .execute(pool)
+    .await;
+}
@@ -2,9 +2,9 @@ use actix_web::{web, HttpRequest, HttpResponse};
 use sqlx::PgPool;
 
 use crate::config::Config;
-use crate::models::{
-    Answer, AnswerWithDetails, Challenge, FeedQueryParams, PaginationParams, User, UserSummary,
-};
+use crate::db::visibility::answer_listed_for;
+use crate::handlers::answer::answers_with_details;
+use crate::models::{Answer, FeedQueryParams, PaginationParams, RankingQueryParams, RatedUser};
 use crate::utils;
 
 pub async fn get_feed(
@@ -30,27 +30,31 @@ pub async fn get_feed(
                 None => return utils::unauthorized("User ID required for following feed"),
             };
 
-            let total: (i64,) = sqlx::query_as(
+            let total: (i64,) = sqlx::query_as(&format!(
                 r#"
                 SELECT COUNT(*) FROM answers a
                 JOIN follows f ON a.user_id = f.following_id
-                WHERE f.follower_id = $1 AND a.status = 'active'
+                WHERE f.follower_id = $1 AND f.status = 'accepted' AND a.status = 'active'
+                  AND {}
                 "#,
-            )
+                answer_listed_for("a", 1)
+            ))
             .bind(user_id)
             .fetch_one(pool.get_ref())
             .await
             .unwrap_or((0,));
 
-            let answers = sqlx::query_as::<_, Answer>(
+            let answers = sqlx::query_as::<_, Answer>(&format!(
                 r#"
                 SELECT a.* FROM answers a
                 JOIN follows f ON a.user_id = f.following_id
-                WHERE f.follower_id = $1 AND a.status = 'active'
+                WHERE f.follower_id = $1 AND f.status = 'accepted' AND a.status = 'active'
+                  AND {}
                 ORDER BY a.created_at DESC
                 LIMIT $2 OFFSET $3
                 "#,
-            )
+                answer_listed_for("a", 1)
+            ))
             .bind(user_id)
             .bind(page_size)
             .bind(offset)
@@ -59,25 +63,68 @@ pub async fn get_feed(
 
             (answers, total.0)
         }
+        "category" => {
+            let category_id = match query.category_id {
+                Some(id) => id,
+                None => return utils::bad_request("category_id required for category feed"),
+            };
+
+            let total: (i64,) = sqlx::query_as(&format!(
+                r#"
+                SELECT COUNT(*) FROM answers a
+                JOIN challenges c ON a.challenge_id = c.id
+                WHERE c.category_id = $1 AND a.status = 'active' AND {}
+                "#,
+                answer_listed_for("a", 2)
+            ))
+            .bind(category_id)
+            .bind(current_user_id)
+            .fetch_one(pool.get_ref())
+            .await
+            .unwrap_or((0,));
+
+            let answers = sqlx::query_as::<_, Answer>(&format!(
+                r#"
+                SELECT a.* FROM answers a
+                JOIN challenges c ON a.challenge_id = c.id
+                WHERE c.category_id = $1 AND a.status = 'active' AND {}
+                ORDER BY a.created_at DESC
+                LIMIT $2 OFFSET $3
+                "#,
+                answer_listed_for("a", 4)
+            ))
+            .bind(category_id)
+            .bind(page_size)
+            .bind(offset)
+            .bind(current_user_id)
+            .fetch_all(pool.get_ref())
+            .await;
+
+            (answers, total.0)
+        }
         _ => {
             // Default: all answers
-            let total: (i64,) = sqlx::query_as(
-                r#"SELECT COUNT(*) FROM answers WHERE status = 'active'"#,
-            )
+            let total: (i64,) = sqlx::query_as(&format!(
+                r#"SELECT COUNT(*) FROM answers WHERE status = 'active' AND {}"#,
+                answer_listed_for("answers", 1)
+            ))
+            .bind(current_user_id)
             .fetch_one(pool.get_ref())
             .await
             .unwrap_or((0,));
 
-            let answers = sqlx::query_as::<_, Answer>(
+            let answers = sqlx::query_as::<_, Answer>(&format!(
                 r#"
                 SELECT * FROM answers
-                WHERE status = 'active'
+                WHERE status = 'active' AND {}
                 ORDER BY created_at DESC
                 LIMIT $1 OFFSET $2
                 "#,
-            )
+                answer_listed_for("answers", 3)
+            ))
             .bind(page_size)
             .bind(offset)
+            .bind(current_user_id)
             .fetch_all(pool.get_ref())
             .await;
 
@@ -93,48 +140,7 @@ pub async fn get_feed(
         }
     };
 
-    let mut results: Vec<AnswerWithDetails> = Vec::new();
-    for answer in answers {
-        let user = sqlx::query_as::<_, User>(
-            r#"SELECT * FROM users WHERE id = $1"#,
-        )
-        .bind(answer.user_id)
-        .fetch_one(pool.get_ref())
-        .await;
-
-        let challenge = sqlx::query_as::<_, Challenge>(
-            r#"SELECT * FROM challenges WHERE id = $1"#,
-        )
-        .bind(answer.challenge_id)
-        .fetch_one(pool.get_ref())
-        .await;
-
-        let is_liked = if let Some(uid) = current_user_id {
-            sqlx::query_scalar::<_, bool>(
-                r#"SELECT EXISTS(SELECT 1 FROM likes WHERE answer_id = $1 AND user_id = $2)"#,
-            )
-            .bind(answer.id)
-            .bind(uid)
-            .fetch_one(pool.get_ref())
-            .await
-            .unwrap_or(false)
-        } else {
-            false
-        };
-
-        if let (Ok(user), Ok(challenge)) = (user, challenge) {
-            results.push(AnswerWithDetails {
-                answer,
-                user: UserSummary {
-                    id: user.id,
-                    name: user.name,
-                    avatar: user.avatar,
-                },
-                challenge,
-                is_liked,
-            });
-        }
-    }
+    let results = answers_with_details(pool.get_ref(), answers, current_user_id).await;
 
     utils::paginated(results, page, page_size, total)
 }
@@ -153,27 +159,31 @@ pub async fn get_trending(
         config.pagination.max_page_size,
     );
 
-    let total: (i64,) = sqlx::query_as(
+    let total: (i64,) = sqlx::query_as(&format!(
         r#"
         SELECT COUNT(*) FROM answers
-        WHERE status = 'active' AND created_at > NOW() - INTERVAL '7 days'
+        WHERE status = 'active' AND created_at > NOW() - INTERVAL '7 days' AND {}
         "#,
-    )
+        answer_listed_for("answers", 1)
+    ))
+    .bind(current_user_id)
     .fetch_one(pool.get_ref())
     .await
     .unwrap_or((0,));
 
     // Trending score: likes + comments*2 + views*0.1
-    let answers = sqlx::query_as::<_, Answer>(
+    let answers = sqlx::query_as::<_, Answer>(&format!(
         r#"
         SELECT * FROM answers
-        WHERE status = 'active' AND created_at > NOW() - INTERVAL '7 days'
+        WHERE status = 'active' AND created_at > NOW() - INTERVAL '7 days' AND {}
         ORDER BY (like_count + comment_count * 2 + view_count * 0.1) DESC
         LIMIT $1 OFFSET $2
         "#,
-    )
+        answer_listed_for("answers", 3)
+    ))
     .bind(page_size)
     .bind(offset)
+    .bind(current_user_id)
     .fetch_all(pool.get_ref())
     .await;
 
@@ -185,48 +195,7 @@ pub async fn get_trending(
         }
     };
 
-    let mut results: Vec<AnswerWithDetails> = Vec::new();
-    for answer in answers {
-        let user = sqlx::query_as::<_, User>(
-            r#"SELECT * FROM users WHERE id = $1"#,
-        )
-        .bind(answer.user_id)
-        .fetch_one(pool.get_ref())
-        .await;
-
-        let challenge = sqlx::query_as::<_, Challenge>(
-            r#"SELECT * FROM challenges WHERE id = $1"#,
-        )
-        .bind(answer.challenge_id)
-        .fetch_one(pool.get_ref())
-        .await;
-
-        let is_liked = if let Some(uid) = current_user_id {
-            sqlx::query_scalar::<_, bool>(
-                r#"SELECT EXISTS(SELECT 1 FROM likes WHERE answer_id = $1 AND user_id = $2)"#,
-            )
-            .bind(answer.id)
-            .bind(uid)
-            .fetch_one(pool.get_ref())
-            .await
-            .unwrap_or(false)
-        } else {
-            false
-        };
-
-        if let (Ok(user), Ok(challenge)) = (user, challenge) {
-            results.push(AnswerWithDetails {
-                answer,
-                user: UserSummary {
-                    id: user.id,
-                    name: user.name,
-                    avatar: user.avatar,
-                },
-                challenge,
-                is_liked,
-            });
-        }
-    }
+    let results = answers_with_details(pool.get_ref(), answers, current_user_id).await;
 
     utils::paginated(results, page, page_size, total.0)
 }
@@ -235,7 +204,7 @@ pub async fn get_daily_ranking(
     pool: web::Data<PgPool>,
     config: web::Data<Config>,
     req: HttpRequest,
-    query: web::Query<PaginationParams>,
+    query: web::Query<RankingQueryParams>,
 ) -> HttpResponse {
     get_ranking_by_period(pool, config, req, query, "1 day").await
 }
@@ -244,7 +213,7 @@ pub async fn get_weekly_ranking(
     pool: web::Data<PgPool>,
     config: web::Data<Config>,
     req: HttpRequest,
-    query: web::Query<PaginationParams>,
+    query: web::Query<RankingQueryParams>,
 ) -> HttpResponse {
     get_ranking_by_period(pool, config, req, query, "7 days").await
 }
@@ -253,7 +222,7 @@ pub async fn get_alltime_ranking(
     pool: web::Data<PgPool>,
     config: web::Data<Config>,
     req: HttpRequest,
-    query: web::Query<PaginationParams>,
+    query: web::Query<RankingQueryParams>,
 ) -> HttpResponse {
     let current_user_id = utils::get_user_id(&req);
     let (page, page_size, offset) = utils::normalize_pagination(
@@ -263,18 +232,71 @@ pub async fn get_alltime_ranking(
         config.pagination.max_page_size,
     );
 
+    let total: (i64,) = sqlx::query_as(&format!(
+        r#"SELECT COUNT(*) FROM answers WHERE status = 'active' AND {}{} AND {}"#,
+        answer_listed_for("answers", 1),
+        timed_filter(query.timed),
+        contest_filter(2)
+    ))
+    .bind(current_user_id)
+    .bind(query.contest_id)
+    .fetch_one(pool.get_ref())
+    .await
+    .unwrap_or((0,));
+
+    let order_by = match ranking_order(query.metric.as_deref()) {
+        Some(o) => o,
+        None => return utils::bad_request("metric must be likes, reactions or rating"),
+    };
+
+    let answers = sqlx::query_as::<_, Answer>(&format!(
+        r#"
+        SELECT * FROM answers
+        WHERE status = 'active' AND {}{} AND {}
+        ORDER BY {}
+        LIMIT $1 OFFSET $2
+        "#,
+        answer_listed_for("answers", 3),
+        timed_filter(query.timed),
+        contest_filter(4),
+        order_by
+    ))
+    .bind(page_size)
+    .bind(offset)
+    .bind(current_user_id)
+    .bind(query.contest_id)
+    .fetch_all(pool.get_ref())
+    .await;
+
+    build_ranking_response(pool, answers, current_user_id, page, page_size, total.0).await
+}
+
+/// Users ranked by the Elo rating their answers earned in battles. Only users
+/// who have fought at least one battle are listed.
+pub async fn get_user_ranking(
+    pool: web::Data<PgPool>,
+    config: web::Data<Config>,
+    query: web::Query<PaginationParams>,
+) -> HttpResponse {
+    let (page, page_size, offset) = utils::normalize_pagination(
+        query.page,
+        query.page_size,
+        config.pagination.default_page_size,
+        config.pagination.max_page_size,
+    );
+
     let total: (i64,) = sqlx::query_as(
-        r#"SELECT COUNT(*) FROM answers WHERE status = 'active'"#,
+        r#"SELECT COUNT(*) FROM users WHERE status = 'active' AND NOT is_private AND battle_count > 0"#,
     )
     .fetch_one(pool.get_ref())
     .await
     .unwrap_or((0,));
 
-    let answers = sqlx::query_as::<_, Answer>(
+    let users = sqlx::query_as::<_, RatedUser>(
         r#"
-        SELECT * FROM answers
-        WHERE status = 'active'
-        ORDER BY like_count DESC, created_at DESC
+        SELECT id, name, avatar, rating, battle_count FROM users
+        WHERE status = 'active' AND NOT is_private AND battle_count > 0
+        ORDER BY rating DESC, battle_count DESC, created_at ASC
         LIMIT $1 OFFSET $2
         "#,
     )
@@ -283,14 +305,20 @@ pub async fn get_alltime_ranking(
     .fetch_all(pool.get_ref())
     .await;
 
-    build_ranking_response(pool, answers, current_user_id, page, page_size, total.0).await
+    match users {
+        Ok(users) => utils::paginated(users, page, page_size, total.0),
+        Err(e) => {
+            tracing::error!("Failed to fetch user ranking: {}", e);
+            utils::internal_error("Failed to fetch ranking")
+        }
+    }
 }
 
 async fn get_ranking_by_period(
     pool: web::Data<PgPool>,
     config: web::Data<Config>,
     req: HttpRequest,
-    query: web::Query<PaginationParams>,
+    query: web::Query<RankingQueryParams>,
     period: &str,
 ) -> HttpResponse {
     let current_user_id = utils::get_user_id(&req);
@@ -302,30 +330,84 @@ async fn get_ranking_by_period(
     );
 
     let total: (i64,) = sqlx::query_as(&format!(
-        r#"SELECT COUNT(*) FROM answers WHERE status = 'active' AND created_at > NOW() - INTERVAL '{}'"#,
-        period
+        r#"SELECT COUNT(*) FROM answers WHERE status = 'active' AND created_at > NOW() - INTERVAL '{}' AND {}{} AND {}"#,
+        period,
+        answer_listed_for("answers", 1),
+        timed_filter(query.timed),
+        contest_filter(2)
     ))
+    .bind(current_user_id)
+    .bind(query.contest_id)
     .fetch_one(pool.get_ref())
     .await
     .unwrap_or((0,));
 
+    let order_by = match ranking_order(query.metric.as_deref()) {
+        Some(o) => o,
+        None => return utils::bad_request("metric must be likes, reactions or rating"),
+    };
+
     let answers = sqlx::query_as::<_, Answer>(&format!(
         r#"
         SELECT * FROM answers
-        WHERE status = 'active' AND created_at > NOW() - INTERVAL '{}'
-        ORDER BY like_count DESC, created_at DESC
+        WHERE status = 'active' AND created_at > NOW() - INTERVAL '{}' AND {}{} AND {}
+        ORDER BY {}
         LIMIT $1 OFFSET $2
         "#,
-        period
+        period,
+        answer_listed_for("answers", 3),
+        timed_filter(query.timed),
+        contest_filter(4),
+        order_by
     ))
     .bind(page_size)
     .bind(offset)
+    .bind(current_user_id)
+    .bind(query.contest_id)
     .fetch_all(pool.get_ref())
     .await;
 
     build_ranking_response(pool, answers, current_user_id, page, page_size, total.0).await
 }
 
+/// ORDER BY clause for a ranking metric. `likes` (the default) ranks by like count;
+/// `reactions` sums every reaction weighted by its type's `ranking_weight`;
+/// `rating` ranks by Elo rating from head-to-head battles.
+fn ranking_order(metric: Option<&str>) -> Option<&'static str> {
+    match metric.unwrap_or("likes") {
+        "likes" => Some("like_count DESC, created_at DESC"),
+        "reactions" => Some(
+            r#"(
+            SELECT COALESCE(SUM(rt.ranking_weight), 0)
+            FROM reactions r
+            JOIN reaction_types rt ON rt.key = r.reaction_type
+            WHERE r.answer_id = answers.id AND rt.status = 'active'
+        ) DESC, like_count DESC, created_at DESC"#,
+        ),
+        "rating" => Some("rating DESC, battle_count DESC, created_at DESC"),
+        _ => None,
+    }
+}
+
+/// Predicate limiting a ranking to entries of the contest bound at `$param`,
+/// or no limit when it is bound as `None`. Entries only show up once the
+/// contest stops taking them, as the visibility predicates hide them until then.
+fn contest_filter(param: usize) -> String {
+    format!(
+        "(${p}::uuid IS NULL OR challenge_id IN (SELECT id FROM challenges WHERE contest_id = ${p}))",
+        p = param
+    )
+}
+
+/// Extra predicate limiting a ranking to timed answers submitted within the time limit.
+fn timed_filter(timed: Option<bool>) -> &'static str {
+    if timed.unwrap_or(false) {
+        " AND answers.session_id IS NOT NULL AND NOT answers.is_late"
+    } else {
+        ""
+    }
+}
+
 async fn build_ranking_response(
     pool: web::Data<PgPool>,
     answers: Result<Vec<Answer>, sqlx::Error>,
@@ -342,48 +424,7 @@ async fn build_ranking_response(
         }
     };
 
-    let mut results: Vec<AnswerWithDetails> = Vec::new();
-    for answer in answers {
-        let user = sqlx::query_as::<_, User>(
-            r#"SELECT * FROM users WHERE id = $1"#,
-        )
-        .bind(answer.user_id)
-        .fetch_one(pool.get_ref())
-        .await;
-
-        let challenge = sqlx::query_as::<_, Challenge>(
-            r#"SELECT * FROM challenges WHERE id = $1"#,
-        )
-        .bind(answer.challenge_id)
-        .fetch_one(pool.get_ref())
-        .await;
-
-        let is_liked = if let Some(uid) = current_user_id {
-            sqlx::query_scalar::<_, bool>(
-                r#"SELECT EXISTS(SELECT 1 FROM likes WHERE answer_id = $1 AND user_id = $2)"#,
-            )
-            .bind(answer.id)
-            .bind(uid)
-            .fetch_one(pool.get_ref())
-            .await
-            .unwrap_or(false)
-        } else {
-            false
-        };
-
-        if let (Ok(user), Ok(challenge)) = (user, challenge) {
-            results.push(AnswerWithDetails {
-                answer,
-                user: UserSummary {
-                    id: user.id,
-                    name: user.name,
-                    avatar: user.avatar,
-                },
-                challenge,
-                is_liked,
-            });
-        }
-    }
+    let results = answers_with_details(pool.get_ref(), answers, current_user_id).await;
 
     utils::paginated(results, page, page_size, total)
 }
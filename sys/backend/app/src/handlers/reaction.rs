@@ -0,0 +1,341 @@
+use actix_web::{web, HttpRequest, HttpResponse};
+use sqlx::PgPool;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::auth;
+use crate::db::visibility::answer_visible_to;
+use crate::handlers::badge::{evaluate_badges, BadgeEvent};
+use crate::handlers::xp::{record_xp, XpEvent};
+use crate::models::{
+    Answer, AnswerReactions, CreateReactionRequest, Reaction, ReactionCount, ReactionType,
+    UpsertReactionTypeRequest,
+};
+use crate::utils;
+
+/// Reaction type backing the legacy like endpoints, `like_count` and `users.total_likes`.
+pub const LIKE_REACTION: &str = "like";
+
+pub enum ReactionError {
+    AnswerNotFound,
+    UnknownType,
+    Database(sqlx::Error),
+}
+
+pub async fn list_reaction_types(pool: web::Data<PgPool>) -> HttpResponse {
+    let result = sqlx::query_as::<_, ReactionType>(
+        r#"
+        SELECT * FROM reaction_types
+        WHERE status = 'active'
+        ORDER BY sort_order ASC, key ASC
+        "#,
+    )
+    .fetch_all(pool.get_ref())
+    .await;
+
+    match result {
+        Ok(types) => utils::success(types),
+        Err(e) => {
+            tracing::error!("Failed to fetch reaction types: {}", e);
+            utils::internal_error("Failed to fetch reaction types")
+        }
+    }
+}
+
+pub async fn upsert_reaction_type(
+    pool: web::Data<PgPool>,
+    req: HttpRequest,
+    path: web::Path<String>,
+    body: web::Json<UpsertReactionTypeRequest>,
+) -> HttpResponse {
+    let key = path.into_inner();
+    let user_id = match utils::get_user_id(&req) {
+        Some(id) => id,
+        None => return utils::unauthorized("User ID required"),
+    };
+    if let Err(resp) = auth::require_role(pool.get_ref(), user_id, auth::ADMIN_ROLES).await {
+        return resp;
+    }
+
+    if let Err(e) = body.validate() {
+        return utils::bad_request(&e.to_string());
+    }
+    if key.is_empty() || key.len() > 30 || !key.chars().all(|c| c.is_ascii_lowercase() || c == '_') {
+        return utils::bad_request("Reaction key must be 1-30 lowercase letters or underscores");
+    }
+    if let Some(status) = &body.status {
+        if status != "active" && status != "inactive" {
+            return utils::bad_request("Status must be active or inactive");
+        }
+    }
+    // The like reaction backs the compatibility endpoints and cannot be retired
+    if key == LIKE_REACTION && body.status.as_deref() == Some("inactive") {
+        return utils::bad_request("The like reaction cannot be deactivated");
+    }
+
+    let result = sqlx::query_as::<_, ReactionType>(
+        r#"
+        INSERT INTO reaction_types (key, label, emoji, ranking_weight, sort_order, status)
+        VALUES ($1, $2, $3, COALESCE($4, 1.0), COALESCE($5, 0), COALESCE($6, 'active'))
+        ON CONFLICT (key) DO UPDATE SET
+            label = EXCLUDED.label,
+            emoji = COALESCE($3, reaction_types.emoji),
+            ranking_weight = COALESCE($4, reaction_types.ranking_weight),
+            sort_order = COALESCE($5, reaction_types.sort_order),
+            status = COALESCE($6, reaction_types.status)
+        RETURNING *
+        "#,
+    )
+    .bind(&key)
+    .bind(&body.label)
+    .bind(&body.emoji)
+    .bind(body.ranking_weight)
+    .bind(body.sort_order)
+    .bind(&body.status)
+    .fetch_one(pool.get_ref())
+    .await;
+
+    match result {
+        Ok(reaction_type) => utils::success(reaction_type),
+        Err(e) => {
+            tracing::error!("Failed to save reaction type: {}", e);
+            utils::internal_error("Failed to save reaction type")
+        }
+    }
+}
+
+pub async fn get_answer_reactions(
+    pool: web::Data<PgPool>,
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+) -> HttpResponse {
+    let answer_id = path.into_inner();
+    let current_user_id = utils::get_user_id(&req);
+
+    let answer_exists = sqlx::query_scalar::<_, bool>(&format!(
+        r#"SELECT EXISTS(SELECT 1 FROM answers a WHERE a.id = $1 AND a.status = 'active' AND {})"#,
+        answer_visible_to("a", 2)
+    ))
+    .bind(answer_id)
+    .bind(current_user_id)
+    .fetch_one(pool.get_ref())
+    .await
+    .unwrap_or(false);
+
+    if !answer_exists {
+        return utils::not_found("Answer not found");
+    }
+
+    let (counts, my_reactions) = reaction_summary(pool.get_ref(), answer_id, current_user_id).await;
+    utils::success(AnswerReactions {
+        counts,
+        my_reactions,
+    })
+}
+
+pub async fn add_answer_reaction(
+    pool: web::Data<PgPool>,
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+    body: web::Json<CreateReactionRequest>,
+) -> HttpResponse {
+    let answer_id = path.into_inner();
+    let user_id = match utils::get_user_id(&req) {
+        Some(id) => id,
+        None => return utils::unauthorized("User ID required"),
+    };
+
+    match add_reaction(pool.get_ref(), answer_id, user_id, &body.reaction_type).await {
+        Ok(Some(reaction)) => utils::created(reaction),
+        Ok(None) => utils::conflict("Already reacted"),
+        Err(ReactionError::AnswerNotFound) => utils::not_found("Answer not found"),
+        Err(ReactionError::UnknownType) => utils::bad_request("Unknown reaction type"),
+        Err(ReactionError::Database(e)) => {
+            tracing::error!("Failed to add reaction: {}", e);
+            utils::internal_error("Failed to add reaction")
+        }
+    }
+}
+
+pub async fn remove_answer_reaction(
+    pool: web::Data<PgPool>,
+    req: HttpRequest,
+    path: web::Path<(Uuid, String)>,
+) -> HttpResponse {
+    let (answer_id, reaction_type) = path.into_inner();
+    let user_id = match utils::get_user_id(&req) {
+        Some(id) => id,
+        None => return utils::unauthorized("User ID required"),
+    };
+
+    match remove_reaction(pool.get_ref(), answer_id, user_id, &reaction_type).await {
+        Ok(true) => utils::no_content(),
+        Ok(false) => utils::not_found("Reaction not found"),
+        Err(ReactionError::AnswerNotFound) => utils::not_found("Answer not found"),
+        Err(ReactionError::UnknownType) => utils::bad_request("Unknown reaction type"),
+        Err(ReactionError::Database(e)) => {
+            tracing::error!("Failed to remove reaction: {}", e);
+            utils::internal_error("Failed to remove reaction")
+        }
+    }
+}
+
+/// Records a reaction. Returns `Ok(None)` if the user already reacted with this type.
+/// Likes also maintain `answers.like_count` and the author's `total_likes`.
+pub async fn add_reaction(
+    pool: &PgPool,
+    answer_id: Uuid,
+    user_id: Uuid,
+    reaction_type: &str,
+) -> Result<Option<Reaction>, ReactionError> {
+    let answer = fetch_visible_answer(pool, answer_id, user_id).await?;
+
+    let known = sqlx::query_scalar::<_, bool>(
+        r#"SELECT EXISTS(SELECT 1 FROM reaction_types WHERE key = $1 AND status = 'active')"#,
+    )
+    .bind(reaction_type)
+    .fetch_one(pool)
+    .await
+    .map_err(ReactionError::Database)?;
+
+    if !known {
+        return Err(ReactionError::UnknownType);
+    }
+
+    let reaction = sqlx::query_as::<_, Reaction>(
+        r#"
+        INSERT INTO reactions (answer_id, user_id, reaction_type)
+        VALUES ($1, $2, $3)
+        ON CONFLICT (answer_id, user_id, reaction_type) DO NOTHING
+        RETURNING *
+        "#,
+    )
+    .bind(answer_id)
+    .bind(user_id)
+    .bind(reaction_type)
+    .fetch_optional(pool)
+    .await
+    .map_err(ReactionError::Database)?;
+
+    if reaction.is_some() && reaction_type == LIKE_REACTION {
+        // Update answer like count
+        let _ = sqlx::query(
+            r#"UPDATE answers SET like_count = like_count + 1 WHERE id = $1"#,
+        )
+        .bind(answer_id)
+        .execute(pool)
+        .await;
+
+        // Update user total likes
+        let _ = sqlx::query(
+            r#"UPDATE users SET total_likes = total_likes + 1 WHERE id = $1"#,
+        )
+        .bind(answer.user_id)
+        .execute(pool)
+        .await;
+
+        record_xp(pool, answer_id, XpEvent::Like).await;
+        evaluate_badges(pool, answer.user_id, BadgeEvent::LikeReceived).await;
+    }
+
+    Ok(reaction)
+}
+
+/// Removes a reaction. Returns `Ok(false)` if there was nothing to remove.
+pub async fn remove_reaction(
+    pool: &PgPool,
+    answer_id: Uuid,
+    user_id: Uuid,
+    reaction_type: &str,
+) -> Result<bool, ReactionError> {
+    let answer = fetch_visible_answer(pool, answer_id, user_id).await?;
+
+    let result = sqlx::query(
+        r#"DELETE FROM reactions WHERE answer_id = $1 AND user_id = $2 AND reaction_type = $3"#,
+    )
+    .bind(answer_id)
+    .bind(user_id)
+    .bind(reaction_type)
+    .execute(pool)
+    .await
+    .map_err(ReactionError::Database)?;
+
+    let removed = result.rows_affected() > 0;
+    if removed && reaction_type == LIKE_REACTION {
+        // Update answer like count
+        let _ = sqlx::query(
+            r#"UPDATE answers SET like_count = GREATEST(0, like_count - 1) WHERE id = $1"#,
+        )
+        .bind(answer_id)
+        .execute(pool)
+        .await;
+
+        // Update user total likes
+        let _ = sqlx::query(
+            r#"UPDATE users SET total_likes = GREATEST(0, total_likes - 1) WHERE id = $1"#,
+        )
+        .bind(answer.user_id)
+        .execute(pool)
+        .await;
+
+        record_xp(pool, answer_id, XpEvent::Unlike).await;
+    }
+
+    Ok(removed)
+}
+
+/// Per-type reaction counts for an answer plus the viewer's own reaction types.
+pub async fn reaction_summary(
+    pool: &PgPool,
+    answer_id: Uuid,
+    current_user_id: Option<Uuid>,
+) -> (Vec<ReactionCount>, Vec<String>) {
+    let counts = sqlx::query_as::<_, ReactionCount>(
+        r#"
+        SELECT r.reaction_type, COUNT(*) AS count
+        FROM reactions r
+        JOIN reaction_types rt ON rt.key = r.reaction_type
+        WHERE r.answer_id = $1 AND rt.status = 'active'
+        GROUP BY r.reaction_type, rt.sort_order
+        ORDER BY rt.sort_order ASC
+        "#,
+    )
+    .bind(answer_id)
+    .fetch_all(pool)
+    .await
+    .unwrap_or_default();
+
+    let my_reactions = if let Some(uid) = current_user_id {
+        sqlx::query_scalar::<_, String>(
+            r#"
+            SELECT r.reaction_type FROM reactions r
+            JOIN reaction_types rt ON rt.key = r.reaction_type
+            WHERE r.answer_id = $1 AND r.user_id = $2 AND rt.status = 'active'
+            ORDER BY r.created_at
+            "#,
+        )
+        .bind(answer_id)
+        .bind(uid)
+        .fetch_all(pool)
+        .await
+        .unwrap_or_default()
+    } else {
+        Vec::new()
+    };
+
+    (counts, my_reactions)
+}
+
+/// Loads an active answer the user may see; others are reported as not found.
+async fn fetch_visible_answer(pool: &PgPool, answer_id: Uuid, user_id: Uuid) -> Result<Answer, ReactionError> {
+    sqlx::query_as::<_, Answer>(&format!(
+        r#"SELECT * FROM answers a WHERE a.id = $1 AND a.status = 'active' AND {}"#,
+        answer_visible_to("a", 2)
+    ))
+    .bind(answer_id)
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(ReactionError::Database)?
+    .ok_or(ReactionError::AnswerNotFound)
+}
@@ -0,0 +1,144 @@
+use actix_web::{web, HttpRequest, HttpResponse};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::config::Config;
+use crate::models::{Challenge, RecommendationQueryParams, RecommendedChallenge};
+use crate::utils;
+
+const DEFAULT_RECOMMENDATIONS: i64 = 5;
+const MAX_RECOMMENDATIONS: i64 = 20;
+
+/// Recommends released challenges the user hasn't answered, one category at a
+/// time so the list stays balanced. The category with the lowest average score
+/// gets two picks per round. Once everything has been answered, the user's
+/// lowest-scoring challenges are suggested for another attempt. Anonymous
+/// visitors get the latest challenge from each category.
+pub async fn get_recommended_challenges(
+    pool: web::Data<PgPool>,
+    config: web::Data<Config>,
+    req: HttpRequest,
+    query: web::Query<RecommendationQueryParams>,
+) -> HttpResponse {
+    let current_user_id = utils::get_user_id(&req);
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_RECOMMENDATIONS)
+        .clamp(1, MAX_RECOMMENDATIONS);
+
+    let weakest_category = match current_user_id {
+        Some(uid) => weakest_category(pool.get_ref(), uid).await,
+        None => None,
+    };
+
+    let result = sqlx::query_as::<_, Challenge>(
+        r#"
+        SELECT * FROM (
+            SELECT ch.*,
+                c.sort_order AS category_order,
+                ROW_NUMBER() OVER (
+                    PARTITION BY ch.category_id
+                    ORDER BY ch.release_date DESC NULLS LAST, ch.created_at DESC
+                ) AS rn
+            FROM challenges ch
+            JOIN categories c ON c.id = ch.category_id
+            WHERE ch.status = 'active' AND c.status = 'active' AND ch.organization_id IS NULL AND ch.contest_id IS NULL
+              AND (ch.release_date IS NULL OR ch.release_date <= (NOW() AT TIME ZONE $4)::date)
+              AND ($1::uuid IS NULL OR NOT EXISTS (
+                  SELECT 1 FROM answers a
+                  WHERE a.challenge_id = ch.id AND a.user_id = $1 AND a.status <> 'deleted'
+              ))
+        ) ranked
+        ORDER BY
+            CASE WHEN category_id = $2 THEN (rn + 1) / 2 ELSE rn END ASC,
+            (category_id = $2) IS TRUE DESC,
+            category_order ASC
+        LIMIT $3
+        "#,
+    )
+    .bind(current_user_id)
+    .bind(weakest_category)
+    .bind(limit)
+    .bind(&config.daily.timezone)
+    .fetch_all(pool.get_ref())
+    .await;
+
+    let challenges = match result {
+        Ok(c) => c,
+        Err(e) => {
+            tracing::error!("Failed to fetch recommendations: {}", e);
+            return utils::internal_error("Failed to fetch recommendations");
+        }
+    };
+
+    let mut results: Vec<RecommendedChallenge> = challenges
+        .into_iter()
+        .map(|challenge| {
+            let reason = if current_user_id.is_none() {
+                "latest"
+            } else if Some(challenge.category_id) == weakest_category {
+                "weakest_category"
+            } else {
+                "unanswered"
+            };
+            RecommendedChallenge {
+                challenge,
+                reason: reason.to_string(),
+            }
+        })
+        .collect();
+
+    if let Some(uid) = current_user_id {
+        let remaining = limit - results.len() as i64;
+        if remaining > 0 {
+            for challenge in retry_candidates(pool.get_ref(), uid, remaining).await {
+                results.push(RecommendedChallenge {
+                    challenge,
+                    reason: "retry".to_string(),
+                });
+            }
+        }
+    }
+
+    utils::success(results)
+}
+
+/// Active category with the user's lowest average score, if they have any scored answers.
+async fn weakest_category(pool: &PgPool, user_id: Uuid) -> Option<Uuid> {
+    sqlx::query_scalar::<_, Uuid>(
+        r#"
+        SELECT ch.category_id
+        FROM answers a
+        JOIN challenges ch ON ch.id = a.challenge_id
+        JOIN categories c ON c.id = ch.category_id
+        WHERE a.user_id = $1 AND a.status <> 'deleted' AND a.score IS NOT NULL
+          AND c.status = 'active'
+        GROUP BY ch.category_id
+        ORDER BY AVG(a.score) ASC
+        LIMIT 1
+        "#,
+    )
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await
+    .unwrap_or(None)
+}
+
+/// Challenges the user already answered, lowest best score first.
+async fn retry_candidates(pool: &PgPool, user_id: Uuid, limit: i64) -> Vec<Challenge> {
+    sqlx::query_as::<_, Challenge>(
+        r#"
+        SELECT ch.* FROM challenges ch
+        JOIN answers a ON a.challenge_id = ch.id
+        WHERE a.user_id = $1 AND a.status <> 'deleted' AND ch.status = 'active' AND ch.contest_id IS NULL
+        GROUP BY ch.id
+        ORDER BY MAX(a.score) ASC NULLS LAST, MAX(a.created_at) ASC
+        LIMIT $2
+        "#,
+    )
+    .bind(user_id)
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+    .unwrap_or_default()
+}
@@ -0,0 +1,561 @@
+use std::collections::{HashMap, HashSet};
+
+use actix_web::{web, HttpRequest, HttpResponse};
+use sqlx::PgPool;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::auth;
+use crate::handlers::organization::{assignment_submissions, fetch_staff_assignment, instructs};
+use crate::models::{
+    Answer, AnswerReview, AnswerReviewSummary, AssignmentSubmission, CriterionScore,
+    ReviewWithReviewer, RubricCriterion, SubmitReviewRequest, UpdateRubricRequest,
+};
+use crate::utils;
+
+pub async fn get_rubric(pool: web::Data<PgPool>, path: web::Path<Uuid>) -> HttpResponse {
+    let category_id = path.into_inner();
+
+    match fetch_rubric(pool.get_ref(), category_id).await {
+        Ok(criteria) => utils::success(criteria),
+        Err(e) => {
+            tracing::error!("Failed to fetch rubric: {}", e);
+            utils::internal_error("Failed to fetch rubric")
+        }
+    }
+}
+
+/// Replaces the category's rubric. Existing reviews keep the scores they were given.
+pub async fn update_rubric(
+    pool: web::Data<PgPool>,
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+    body: web::Json<UpdateRubricRequest>,
+) -> HttpResponse {
+    let category_id = path.into_inner();
+    let user_id = match utils::get_user_id(&req) {
+        Some(id) => id,
+        None => return utils::unauthorized("User ID required"),
+    };
+    if let Err(resp) = auth::require_role(pool.get_ref(), user_id, auth::ADMIN_ROLES).await {
+        return resp;
+    }
+
+    if let Err(e) = body.validate() {
+        return utils::bad_request(&e.to_string());
+    }
+
+    let mut keys = HashSet::new();
+    if !body.criteria.iter().all(|c| keys.insert(c.key.as_str())) {
+        return utils::bad_request("Criterion keys must be unique");
+    }
+
+    let category_exists = sqlx::query_scalar::<_, bool>(
+        r#"SELECT EXISTS(SELECT 1 FROM categories WHERE id = $1)"#,
+    )
+    .bind(category_id)
+    .fetch_one(pool.get_ref())
+    .await
+    .unwrap_or(false);
+
+    if !category_exists {
+        return utils::not_found("Category not found");
+    }
+
+    let result: Result<(), sqlx::Error> = async {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query(r#"DELETE FROM rubric_criteria WHERE category_id = $1"#)
+            .bind(category_id)
+            .execute(&mut *tx)
+            .await?;
+
+        for (index, criterion) in body.criteria.iter().enumerate() {
+            sqlx::query(
+                r#"
+                INSERT INTO rubric_criteria (category_id, key, name, description, max_points, position)
+                VALUES ($1, $2, $3, $4, $5, $6)
+                "#,
+            )
+            .bind(category_id)
+            .bind(&criterion.key)
+            .bind(&criterion.name)
+            .bind(&criterion.description)
+            .bind(criterion.max_points)
+            .bind(index as i32 + 1)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await
+    }
+    .await;
+
+    if let Err(e) = result {
+        tracing::error!("Failed to update rubric: {}", e);
+        return utils::internal_error("Failed to update rubric");
+    }
+
+    match fetch_rubric(pool.get_ref(), category_id).await {
+        Ok(criteria) => utils::success(criteria),
+        Err(e) => {
+            tracing::error!("Failed to fetch rubric: {}", e);
+            utils::internal_error("Failed to update rubric")
+        }
+    }
+}
+
+/// Grades an answer against its category's rubric. Each reviewer has one
+/// review per answer; submitting again replaces it.
+pub async fn submit_review(
+    pool: web::Data<PgPool>,
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+    body: web::Json<SubmitReviewRequest>,
+) -> HttpResponse {
+    let answer_id = path.into_inner();
+    let user_id = match utils::get_user_id(&req) {
+        Some(id) => id,
+        None => return utils::unauthorized("User ID required"),
+    };
+
+    if let Err(e) = body.validate() {
+        return utils::bad_request(&e.to_string());
+    }
+
+    let answer = match fetch_reviewable_answer(pool.get_ref(), answer_id, user_id).await {
+        Ok(a) => a,
+        Err(resp) => return resp,
+    };
+
+    let rubric = match challenge_rubric(pool.get_ref(), answer.challenge_id).await {
+        Ok(r) if r.is_empty() => return utils::bad_request("Category has no rubric"),
+        Ok(r) => r,
+        Err(e) => {
+            tracing::error!("Failed to fetch rubric: {}", e);
+            return utils::internal_error("Failed to submit review");
+        }
+    };
+
+    let (scores, total_score) = match score_on_rubric(&rubric, &body.scores) {
+        Ok(r) => r,
+        Err(message) => return utils::bad_request(&message),
+    };
+    let scores = match serde_json::to_value(&scores) {
+        Ok(v) => v,
+        Err(e) => {
+            tracing::error!("Failed to serialize review scores: {}", e);
+            return utils::internal_error("Failed to submit review");
+        }
+    };
+
+    let result = sqlx::query_as::<_, AnswerReview>(
+        r#"
+        INSERT INTO answer_reviews (answer_id, reviewer_id, scores, total_score, feedback)
+        VALUES ($1, $2, $3, $4, $5)
+        ON CONFLICT (answer_id, reviewer_id) DO UPDATE
+        SET scores = EXCLUDED.scores, total_score = EXCLUDED.total_score,
+            feedback = EXCLUDED.feedback, updated_at = NOW()
+        RETURNING *
+        "#,
+    )
+    .bind(answer_id)
+    .bind(user_id)
+    .bind(&scores)
+    .bind(total_score)
+    .bind(&body.feedback)
+    .fetch_one(pool.get_ref())
+    .await;
+
+    match result {
+        Ok(review) => {
+            let _ = sqlx::query(
+                r#"
+                UPDATE answers
+                SET human_score = (SELECT ROUND(AVG(total_score))::INTEGER FROM answer_reviews WHERE answer_id = $1)
+                WHERE id = $1
+                "#,
+            )
+            .bind(answer_id)
+            .execute(pool.get_ref())
+            .await;
+
+            utils::success(review)
+        }
+        Err(e) => {
+            tracing::error!("Failed to submit review: {}", e);
+            utils::internal_error("Failed to submit review")
+        }
+    }
+}
+
+/// Human and peer reviews of an answer side by side with its AI score.
+/// Visible to the answer's author and to those who may review it.
+pub async fn get_answer_reviews(
+    pool: web::Data<PgPool>,
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+) -> HttpResponse {
+    let answer_id = path.into_inner();
+    let user_id = match utils::get_user_id(&req) {
+        Some(id) => id,
+        None => return utils::unauthorized("User ID required"),
+    };
+
+    let answer = sqlx::query_as::<_, Answer>(
+        r#"SELECT * FROM answers WHERE id = $1 AND status <> 'deleted'"#,
+    )
+    .bind(answer_id)
+    .fetch_optional(pool.get_ref())
+    .await;
+
+    let answer = match answer {
+        Ok(Some(a)) if a.user_id == user_id => a,
+        Ok(Some(_)) => match fetch_reviewable_answer(pool.get_ref(), answer_id, user_id).await {
+            Ok(a) => a,
+            Err(resp) => return resp,
+        },
+        Ok(None) => return utils::not_found("Answer not found"),
+        Err(e) => {
+            tracing::error!("Failed to fetch answer: {}", e);
+            return utils::internal_error("Failed to fetch reviews");
+        }
+    };
+
+    let reviews = sqlx::query_as::<_, ReviewWithReviewer>(
+        r#"
+        SELECT r.*, u.name AS reviewer_name
+        FROM answer_reviews r
+        JOIN users u ON u.id = r.reviewer_id
+        WHERE r.answer_id = $1
+        ORDER BY r.updated_at DESC
+        "#,
+    )
+    .bind(answer_id)
+    .fetch_all(pool.get_ref())
+    .await;
+
+    let peer_feedback = sqlx::query_scalar::<_, String>(
+        r#"
+        SELECT feedback FROM peer_reviews
+        WHERE answer_id = $1 AND completed_at IS NOT NULL AND feedback IS NOT NULL
+        ORDER BY completed_at ASC
+        "#,
+    )
+    .bind(answer_id)
+    .fetch_all(pool.get_ref())
+    .await
+    .unwrap_or_default();
+
+    match reviews {
+        Ok(reviews) => utils::success(AnswerReviewSummary {
+            answer_id,
+            ai_score: answer.score,
+            ai_feedback: answer.ai_feedback,
+            human_score: answer.human_score,
+            score_gap: score_gap(answer.score, answer.human_score),
+            reviews,
+            peer_score: answer.peer_score,
+            peer_review_count: answer.peer_review_count,
+            peer_feedback,
+        }),
+        Err(e) => {
+            tracing::error!("Failed to fetch reviews: {}", e);
+            utils::internal_error("Failed to fetch reviews")
+        }
+    }
+}
+
+/// Grades for an assignment as CSV: one row per learner in the group with the
+/// AI and human scores, their gap, the average points per rubric criterion
+/// and the reviewers' written feedback. Staff only.
+pub async fn export_assignment_grades(
+    pool: web::Data<PgPool>,
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+) -> HttpResponse {
+    let assignment_id = path.into_inner();
+    let user_id = match utils::get_user_id(&req) {
+        Some(id) => id,
+        None => return utils::unauthorized("User ID required"),
+    };
+
+    let assignment = match fetch_staff_assignment(pool.get_ref(), assignment_id, user_id).await {
+        Ok(a) => a,
+        Err(resp) => return resp,
+    };
+
+    let submissions = match assignment_submissions(pool.get_ref(), assignment.id).await {
+        Ok(s) => s,
+        Err(e) => {
+            tracing::error!("Failed to fetch submissions: {}", e);
+            return utils::internal_error("Failed to export grades");
+        }
+    };
+
+    let rubric = challenge_rubric(pool.get_ref(), assignment.challenge_id)
+        .await
+        .unwrap_or_default();
+
+    let answer_ids: Vec<Uuid> = submissions.iter().filter_map(|s| s.answer_id).collect();
+    let reviews = sqlx::query_as::<_, AnswerReview>(
+        r#"SELECT * FROM answer_reviews WHERE answer_id = ANY($1) ORDER BY created_at ASC"#,
+    )
+    .bind(&answer_ids)
+    .fetch_all(pool.get_ref())
+    .await
+    .unwrap_or_default();
+
+    let mut header = vec![
+        "user_id", "name", "status", "submitted_at", "answer_id", "ai_score", "human_score", "score_gap",
+    ]
+    .into_iter()
+    .map(String::from)
+    .collect::<Vec<_>>();
+    header.extend(rubric.iter().map(|c| c.key.clone()));
+    header.push("feedback".to_string());
+
+    let mut csv = csv_row(&header);
+    for submission in &submissions {
+        csv.push_str(&csv_row(&grade_row(submission, &rubric, &reviews)));
+    }
+
+    HttpResponse::Ok()
+        .content_type("text/csv; charset=utf-8")
+        .insert_header((
+            "Content-Disposition",
+            format!("attachment; filename=\"grades-{}.csv\"", assignment.id),
+        ))
+        .body(csv)
+}
+
+fn grade_row(submission: &AssignmentSubmission, rubric: &[RubricCriterion], reviews: &[AnswerReview]) -> Vec<String> {
+    let reviews: Vec<&AnswerReview> = reviews
+        .iter()
+        .filter(|r| Some(r.answer_id) == submission.answer_id)
+        .collect();
+    let opt = |v: Option<i32>| v.map(|v| v.to_string()).unwrap_or_default();
+
+    let mut row = vec![
+        submission.user_id.to_string(),
+        submission.name.clone(),
+        submission.status.clone(),
+        submission.submitted_at.map(|t| t.to_rfc3339()).unwrap_or_default(),
+        submission.answer_id.map(|id| id.to_string()).unwrap_or_default(),
+        opt(submission.score),
+        opt(submission.human_score),
+        opt(score_gap(submission.score, submission.human_score)),
+    ];
+
+    for criterion in rubric {
+        let points: Vec<i32> = reviews
+            .iter()
+            .filter_map(|r| serde_json::from_value::<Vec<CriterionScore>>(r.scores.clone()).ok())
+            .filter_map(|scores| scores.into_iter().find(|s| s.name == criterion.key).map(|s| s.score))
+            .collect();
+        row.push(if points.is_empty() {
+            String::new()
+        } else {
+            format!("{:.1}", f64::from(points.iter().sum::<i32>()) / points.len() as f64)
+        });
+    }
+
+    row.push(
+        reviews
+            .iter()
+            .filter_map(|r| r.feedback.as_deref())
+            .collect::<Vec<_>>()
+            .join(" / "),
+    );
+    row
+}
+
+fn csv_row(fields: &[String]) -> String {
+    let escaped: Vec<String> = fields
+        .iter()
+        .map(|f| {
+            // Spreadsheets evaluate cells starting with these as formulas; plain numbers such
+            // as a negative score gap are left alone
+            let f = if f.starts_with(['=', '+', '-', '@']) && f.parse::<f64>().is_err() {
+                format!("'{}", f)
+            } else {
+                f.clone()
+            };
+            if f.contains([',', '"', '\n', '\r']) {
+                format!("\"{}\"", f.replace('"', "\"\""))
+            } else {
+                f
+            }
+        })
+        .collect();
+    format!("{}\r\n", escaped.join(","))
+}
+
+/// Human minus AI score, when both exist.
+fn score_gap(ai_score: Option<i32>, human_score: Option<i32>) -> Option<i32> {
+    Some(human_score? - ai_score?)
+}
+
+/// Site admins review any answer; organization staff review their learners'
+/// answers to the organization's challenges and assignments.
+async fn fetch_reviewable_answer(pool: &PgPool, answer_id: Uuid, reviewer_id: Uuid) -> Result<Answer, HttpResponse> {
+    let answer = sqlx::query_as::<_, Answer>(
+        r#"SELECT * FROM answers WHERE id = $1 AND status <> 'deleted'"#,
+    )
+    .bind(answer_id)
+    .fetch_optional(pool)
+    .await;
+
+    let answer = match answer {
+        Ok(Some(a)) => a,
+        Ok(None) => return Err(utils::not_found("Answer not found")),
+        Err(e) => {
+            tracing::error!("Failed to fetch answer: {}", e);
+            return Err(utils::internal_error("Failed to fetch answer"));
+        }
+    };
+
+    if answer.user_id == reviewer_id {
+        return Err(utils::forbidden("You cannot review your own answer"));
+    }
+
+    if auth::require_role(pool, reviewer_id, auth::ADMIN_ROLES).await.is_ok()
+        || instructs(pool, reviewer_id, answer.user_id, answer.challenge_id).await
+    {
+        Ok(answer)
+    } else {
+        Err(utils::forbidden("Insufficient permissions"))
+    }
+}
+
+async fn fetch_rubric(pool: &PgPool, category_id: Uuid) -> Result<Vec<RubricCriterion>, sqlx::Error> {
+    sqlx::query_as::<_, RubricCriterion>(
+        r#"SELECT * FROM rubric_criteria WHERE category_id = $1 ORDER BY position ASC"#,
+    )
+    .bind(category_id)
+    .fetch_all(pool)
+    .await
+}
+
+/// Checks that every criterion is scored exactly once within its points and
+/// returns the grades in rubric order with the total as a 0-100 score.
+pub fn score_on_rubric(
+    rubric: &[RubricCriterion],
+    grades: &[CriterionScore],
+) -> Result<(Vec<CriterionScore>, i32), String> {
+    let mut points: HashMap<&str, i32> = HashMap::new();
+    for grade in grades {
+        let criterion = match rubric.iter().find(|c| c.key == grade.name) {
+            Some(c) => c,
+            None => return Err(format!("Unknown criterion: {}", grade.name)),
+        };
+        if grade.score > criterion.max_points {
+            return Err(format!("{} is scored out of {}", criterion.key, criterion.max_points));
+        }
+        if points.insert(criterion.key.as_str(), grade.score).is_some() {
+            return Err(format!("Criterion scored twice: {}", criterion.key));
+        }
+    }
+    if points.len() != rubric.len() {
+        return Err("Every rubric criterion must be scored".to_string());
+    }
+
+    let available: i32 = rubric.iter().map(|c| c.max_points).sum();
+    let scored: i32 = points.values().sum();
+    let total_score = (f64::from(scored) * 100.0 / f64::from(available.max(1))).round() as i32;
+
+    let scores = rubric
+        .iter()
+        .map(|c| CriterionScore {
+            name: c.key.clone(),
+            score: points[c.key.as_str()],
+        })
+        .collect();
+    Ok((scores, total_score))
+}
+
+/// The rubric of the challenge's category.
+pub async fn challenge_rubric(pool: &PgPool, challenge_id: Uuid) -> Result<Vec<RubricCriterion>, sqlx::Error> {
+    sqlx::query_as::<_, RubricCriterion>(
+        r#"
+        SELECT r.* FROM rubric_criteria r
+        JOIN challenges ch ON ch.category_id = r.category_id
+        WHERE ch.id = $1
+        ORDER BY r.position ASC
+        "#,
+    )
+    .bind(challenge_id)
+    .fetch_all(pool)
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn rubric(points: &[(&str, i32)]) -> Vec<RubricCriterion> {
+        let category_id = Uuid::new_v4();
+        points
+            .iter()
+            .enumerate()
+            .map(|(i, (key, max_points))| RubricCriterion {
+                id: Uuid::new_v4(),
+                category_id,
+                key: key.to_string(),
+                name: key.to_string(),
+                description: None,
+                max_points: *max_points,
+                position: i as i32,
+                created_at: Utc::now(),
+            })
+            .collect()
+    }
+
+    fn grades(scores: &[(&str, i32)]) -> Vec<CriterionScore> {
+        scores
+            .iter()
+            .map(|(name, score)| CriterionScore {
+                name: name.to_string(),
+                score: *score,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn scores_in_rubric_order_out_of_100() {
+        let rubric = rubric(&[("clarity", 10), ("style", 30)]);
+        let (scores, total) = score_on_rubric(&rubric, &grades(&[("style", 20), ("clarity", 10)])).unwrap();
+
+        let ordered: Vec<(&str, i32)> = scores.iter().map(|s| (s.name.as_str(), s.score)).collect();
+        assert_eq!(ordered, vec![("clarity", 10), ("style", 20)]);
+        assert_eq!(total, 75);
+    }
+
+    #[test]
+    fn rounds_the_total() {
+        let rubric = rubric(&[("a", 3)]);
+        assert_eq!(score_on_rubric(&rubric, &grades(&[("a", 2)])).unwrap().1, 67);
+        assert_eq!(score_on_rubric(&rubric, &grades(&[("a", 0)])).unwrap().1, 0);
+    }
+
+    #[test]
+    fn rejects_invalid_grades() {
+        let rubric = rubric(&[("clarity", 10), ("style", 30)]);
+
+        let err = |g: &[(&str, i32)]| score_on_rubric(&rubric, &grades(g)).unwrap_err();
+        assert_eq!(err(&[("clarity", 5), ("tone", 5)]), "Unknown criterion: tone");
+        assert_eq!(err(&[("clarity", 11), ("style", 5)]), "clarity is scored out of 10");
+        assert_eq!(err(&[("clarity", 1), ("clarity", 2)]), "Criterion scored twice: clarity");
+        assert_eq!(err(&[("clarity", 5)]), "Every rubric criterion must be scored");
+    }
+
+    #[test]
+    fn neutralises_formula_cells() {
+        let row = |fields: &[&str]| csv_row(&fields.iter().map(|f| f.to_string()).collect::<Vec<_>>());
+        assert_eq!(row(&["=SUM(A1)", "@x", "+1+1"]), "'=SUM(A1),'@x,'+1+1\r\n");
+        assert_eq!(row(&["-12", "3.5", "name"]), "-12,3.5,name\r\n");
+        assert_eq!(row(&["a,b", "say \"hi\""]), "\"a,b\",\"say \"\"hi\"\"\"\r\n");
+        assert_eq!(row(&["=1,2"]), "\"'=1,2\"\r\n");
+    }
+}
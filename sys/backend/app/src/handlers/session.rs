@@ -0,0 +1,103 @@
+use actix_web::{web, HttpRequest, HttpResponse};
+use sqlx::{PgConnection, PgPool};
+use uuid::Uuid;
+
+use crate::config::{Config, TimedConfig};
+use crate::db::visibility::challenge_visible_to;
+use crate::models::AnswerSession;
+use crate::utils;
+
+/// Server-measured writing time of a timed answer.
+pub struct TimedResult {
+    pub elapsed_ms: i32,
+    pub is_late: bool,
+}
+
+/// Starts the clock for a timed answer. The returned session id is sent with
+/// the submission; the server, not the client, measures the elapsed time.
+pub async fn start_answer_session(
+    pool: web::Data<PgPool>,
+    config: web::Data<Config>,
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+) -> HttpResponse {
+    let challenge_id = path.into_inner();
+    let user_id = match utils::get_user_id(&req) {
+        Some(id) => id,
+        None => return utils::unauthorized("User ID required"),
+    };
+
+    let exists = sqlx::query_scalar::<_, bool>(&format!(
+        r#"SELECT EXISTS(SELECT 1 FROM challenges WHERE id = $1 AND status = 'active' AND {})"#,
+        challenge_visible_to("challenges", 2)
+    ))
+    .bind(challenge_id)
+    .bind(user_id)
+    .fetch_one(pool.get_ref())
+    .await
+    .unwrap_or(false);
+
+    if !exists {
+        return utils::not_found("Challenge not found");
+    }
+
+    let result = sqlx::query_as::<_, AnswerSession>(
+        r#"
+        INSERT INTO answer_sessions (user_id, challenge_id, deadline_at)
+        VALUES ($1, $2, NOW() + make_interval(secs => $3))
+        RETURNING *
+        "#,
+    )
+    .bind(user_id)
+    .bind(challenge_id)
+    .bind(f64::from(config.timed.time_limit_secs))
+    .fetch_one(pool.get_ref())
+    .await;
+
+    match result {
+        Ok(session) => utils::created(session),
+        Err(e) => {
+            tracing::error!("Failed to start answer session: {}", e);
+            utils::internal_error("Failed to start answer session")
+        }
+    }
+}
+
+/// Consumes the session for a submission and measures the writing time.
+/// Late submissions are rejected or flagged depending on configuration.
+/// Runs on the submission's transaction, so a failed insert leaves the
+/// session unused.
+pub async fn claim_session(
+    conn: &mut PgConnection,
+    config: &TimedConfig,
+    session_id: Uuid,
+    user_id: Uuid,
+    challenge_id: Uuid,
+) -> Result<TimedResult, HttpResponse> {
+    let result = sqlx::query_as::<_, (i32, bool)>(
+        r#"
+        UPDATE answer_sessions
+        SET submitted_at = NOW()
+        WHERE id = $1 AND user_id = $2 AND challenge_id = $3 AND submitted_at IS NULL
+        RETURNING
+            LEAST(EXTRACT(EPOCH FROM (submitted_at - started_at)) * 1000, 2147483647)::INTEGER,
+            submitted_at > deadline_at + make_interval(secs => $4)
+        "#,
+    )
+    .bind(session_id)
+    .bind(user_id)
+    .bind(challenge_id)
+    .bind(f64::from(config.grace_secs))
+    .fetch_optional(conn)
+    .await;
+
+    match result {
+        Ok(Some((_, true))) if config.reject_late => Err(utils::bad_request("Time limit exceeded")),
+        Ok(Some((elapsed_ms, is_late))) => Ok(TimedResult { elapsed_ms, is_late }),
+        Ok(None) => Err(utils::bad_request("Timed session not found or already used")),
+        Err(e) => {
+            tracing::error!("Failed to claim answer session: {}", e);
+            Err(utils::internal_error("Failed to create answer"))
+        }
+    }
+}
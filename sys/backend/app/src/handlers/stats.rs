@@ -0,0 +1,196 @@
+use actix_web::{web, HttpRequest, HttpResponse};
+use chrono::{Duration, NaiveDate};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::handlers::answer::answers_with_details;
+use crate::models::{Answer, CriterionStat, ScoreTrendPoint, StatsQueryParams, UserStats};
+use crate::utils;
+
+const DEFAULT_STATS_DAYS: i64 = 90;
+const BEST_ANSWERS_LIMIT: i64 = 5;
+const WEAKEST_CRITERIA_LIMIT: i64 = 3;
+const STATS_INTERVALS: &[&str] = &["day", "week", "month"];
+
+/// Answers in the requested range, by creation date in the user's timezone.
+/// Binds: $1 user id, $2 timezone, $3 from, $4 to.
+const RANGE_FILTER: &str = r#"
+    a.user_id = $1 AND a.status <> 'deleted'
+    AND (a.created_at AT TIME ZONE $2)::date BETWEEN $3 AND $4
+"#;
+
+/// Progress analytics over the user's own attempts. Only the user can see them,
+/// since they include private and unpublished practice.
+pub async fn get_user_stats(
+    pool: web::Data<PgPool>,
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+    query: web::Query<StatsQueryParams>,
+) -> HttpResponse {
+    let user_id = path.into_inner();
+    let current_user_id = match utils::get_user_id(&req) {
+        Some(id) => id,
+        None => return utils::unauthorized("User ID required"),
+    };
+
+    if user_id != current_user_id {
+        return utils::forbidden("You can only view your own stats");
+    }
+
+    let interval = query.interval.as_deref().unwrap_or("week");
+    if !STATS_INTERVALS.contains(&interval) {
+        return utils::bad_request("interval must be day, week or month");
+    }
+
+    let user = sqlx::query_as::<_, (String, NaiveDate)>(
+        r#"
+        SELECT timezone, (NOW() AT TIME ZONE timezone)::date
+        FROM users WHERE id = $1 AND status = 'active'
+        "#,
+    )
+    .bind(user_id)
+    .fetch_optional(pool.get_ref())
+    .await;
+
+    let (timezone, today) = match user {
+        Ok(Some(u)) => u,
+        Ok(None) => return utils::not_found("User not found"),
+        Err(e) => {
+            tracing::error!("Failed to fetch user: {}", e);
+            return utils::internal_error("Failed to fetch stats");
+        }
+    };
+
+    let to = query.to.unwrap_or(today);
+    let from = query
+        .from
+        .unwrap_or(to - Duration::days(DEFAULT_STATS_DAYS - 1));
+    if from > to {
+        return utils::bad_request("from must not be after to");
+    }
+
+    let result = compute_stats(pool.get_ref(), user_id, &timezone, from, to, interval).await;
+
+    match result {
+        Ok(stats) => utils::success(stats),
+        Err(e) => {
+            tracing::error!("Failed to compute stats: {}", e);
+            utils::internal_error("Failed to fetch stats")
+        }
+    }
+}
+
+async fn compute_stats(
+    pool: &PgPool,
+    user_id: Uuid,
+    timezone: &str,
+    from: NaiveDate,
+    to: NaiveDate,
+    interval: &str,
+) -> Result<UserStats, sqlx::Error> {
+    let (answer_count, average_score, average_length, char_limit_utilization, likes_per_answer) =
+        sqlx::query_as::<_, (i64, Option<f64>, f64, f64, f64)>(&format!(
+            r#"
+            SELECT
+                COUNT(*),
+                AVG(a.score)::DOUBLE PRECISION,
+                COALESCE(AVG(CHAR_LENGTH(a.content)), 0)::DOUBLE PRECISION,
+                COALESCE(AVG(CHAR_LENGTH(a.content)::DOUBLE PRECISION / NULLIF(ch.char_limit, 0)), 0),
+                COALESCE(AVG(a.like_count), 0)::DOUBLE PRECISION
+            FROM answers a
+            JOIN challenges ch ON ch.id = a.challenge_id
+            WHERE {}
+            "#,
+            RANGE_FILTER
+        ))
+        .bind(user_id)
+        .bind(timezone)
+        .bind(from)
+        .bind(to)
+        .fetch_one(pool)
+        .await?;
+
+    let score_trends = sqlx::query_as::<_, ScoreTrendPoint>(&format!(
+        r#"
+        SELECT
+            c.id AS category_id,
+            c.name AS category_name,
+            DATE_TRUNC($5, a.created_at AT TIME ZONE $2)::date AS period,
+            AVG(a.score)::DOUBLE PRECISION AS average_score,
+            COUNT(*) AS answer_count
+        FROM answers a
+        JOIN challenges ch ON ch.id = a.challenge_id
+        JOIN categories c ON c.id = ch.category_id
+        WHERE {} AND a.score IS NOT NULL
+        GROUP BY c.id, c.name, c.sort_order, period
+        ORDER BY period ASC, c.sort_order ASC
+        "#,
+        RANGE_FILTER
+    ))
+    .bind(user_id)
+    .bind(timezone)
+    .bind(from)
+    .bind(to)
+    .bind(interval)
+    .fetch_all(pool)
+    .await?;
+
+    let best = sqlx::query_as::<_, Answer>(&format!(
+        r#"
+        SELECT a.* FROM answers a
+        WHERE {} AND a.score IS NOT NULL
+        ORDER BY a.score DESC, a.like_count DESC, a.created_at DESC
+        LIMIT $5
+        "#,
+        RANGE_FILTER
+    ))
+    .bind(user_id)
+    .bind(timezone)
+    .bind(from)
+    .bind(to)
+    .bind(BEST_ANSWERS_LIMIT)
+    .fetch_all(pool)
+    .await?;
+    let best_answers = answers_with_details(pool, best, Some(user_id)).await;
+
+    // Criteria come from the per-criterion breakdown in ai_feedback, when present
+    let weakest_criteria = sqlx::query_as::<_, CriterionStat>(&format!(
+        r#"
+        SELECT
+            crit->>'name' AS name,
+            AVG((crit->>'score')::DOUBLE PRECISION) AS average_score,
+            COUNT(*) AS samples
+        FROM answers a
+        CROSS JOIN LATERAL jsonb_array_elements(
+            CASE WHEN jsonb_typeof(a.ai_feedback->'criteria') = 'array'
+                THEN a.ai_feedback->'criteria' ELSE '[]'::jsonb END
+        ) crit
+        WHERE {}
+        GROUP BY crit->>'name'
+        ORDER BY average_score ASC, samples DESC
+        LIMIT $5
+        "#,
+        RANGE_FILTER
+    ))
+    .bind(user_id)
+    .bind(timezone)
+    .bind(from)
+    .bind(to)
+    .bind(WEAKEST_CRITERIA_LIMIT)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(UserStats {
+        from,
+        to,
+        interval: interval.to_string(),
+        answer_count,
+        average_score,
+        average_length,
+        char_limit_utilization,
+        likes_per_answer,
+        score_trends,
+        best_answers,
+        weakest_criteria,
+    })
+}
@@ -0,0 +1,289 @@
+use actix_web::{web, HttpRequest, HttpResponse};
+use chrono::{Duration, NaiveDate};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::config::StreakConfig;
+use crate::db::visibility::profile_visible_to;
+use crate::models::{ActivityCalendar, ActivityDay, ActivityQueryParams, StreakSummary, UserStreak};
+use crate::utils;
+
+const DEFAULT_ACTIVITY_DAYS: i32 = 365;
+const MAX_ACTIVITY_DAYS: i32 = 366;
+
+/// Contribution-style calendar of answers submitted per day in the user's timezone.
+/// Private accounts only show it to themselves and approved followers.
+pub async fn get_user_activity(
+    pool: web::Data<PgPool>,
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+    query: web::Query<ActivityQueryParams>,
+) -> HttpResponse {
+    let user_id = path.into_inner();
+    let current_user_id = utils::get_user_id(&req);
+    let days = query
+        .days
+        .unwrap_or(DEFAULT_ACTIVITY_DAYS)
+        .clamp(1, MAX_ACTIVITY_DAYS);
+
+    let user = sqlx::query_as::<_, (String, NaiveDate, bool)>(&format!(
+        r#"
+        SELECT u.timezone, (NOW() AT TIME ZONE u.timezone)::date, {}
+        FROM users u WHERE u.id = $1 AND u.status = 'active'
+        "#,
+        profile_visible_to("u", 2)
+    ))
+    .bind(user_id)
+    .bind(current_user_id)
+    .fetch_optional(pool.get_ref())
+    .await;
+
+    let (timezone, today) = match user {
+        Ok(Some((_, _, false))) => return utils::forbidden("This account is private"),
+        Ok(Some((timezone, today, true))) => (timezone, today),
+        Ok(None) => return utils::not_found("User not found"),
+        Err(e) => {
+            tracing::error!("Failed to fetch user: {}", e);
+            return utils::internal_error("Failed to fetch activity");
+        }
+    };
+
+    let from = today - Duration::days(i64::from(days - 1));
+
+    // Every attempt counts as practice, whatever its visibility or publish state
+    let result = sqlx::query_as::<_, ActivityDay>(
+        r#"
+        SELECT (created_at AT TIME ZONE $2)::date AS date, COUNT(*) AS count
+        FROM answers
+        WHERE user_id = $1 AND status <> 'deleted'
+            AND (created_at AT TIME ZONE $2)::date BETWEEN $3 AND $4
+        GROUP BY 1
+        ORDER BY 1 ASC
+        "#,
+    )
+    .bind(user_id)
+    .bind(&timezone)
+    .bind(from)
+    .bind(today)
+    .fetch_all(pool.get_ref())
+    .await;
+
+    match result {
+        Ok(days) => utils::success(ActivityCalendar {
+            timezone,
+            from,
+            to: today,
+            days,
+        }),
+        Err(e) => {
+            tracing::error!("Failed to fetch activity: {}", e);
+            utils::internal_error("Failed to fetch activity")
+        }
+    }
+}
+
+/// Advances the user's streak for a new answer. Called after every successful
+/// submission; repeat submissions on the same local day are no-ops.
+pub async fn record_activity(pool: &PgPool, config: &StreakConfig, user_id: Uuid) {
+    let today = match local_today(pool, user_id).await {
+        Some(d) => d,
+        None => return,
+    };
+
+    let current = sqlx::query_as::<_, UserStreak>(
+        r#"SELECT * FROM user_streaks WHERE user_id = $1"#,
+    )
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await;
+
+    let current = match current {
+        Ok(s) => s,
+        Err(e) => {
+            tracing::error!("Failed to fetch streak: {}", e);
+            return;
+        }
+    };
+
+    let next = match &current {
+        Some(s) => advance_streak(
+            (s.current_streak, s.longest_streak, s.freeze_tokens),
+            s.last_active_date,
+            today,
+            config,
+        ),
+        None => advance_streak((0, 0, 0), None, today, config),
+    };
+    let (streak, longest, tokens) = match next {
+        Some(state) => state,
+        None => return,
+    };
+
+    let result = sqlx::query(
+        r#"
+        INSERT INTO user_streaks (user_id, current_streak, longest_streak, last_active_date, freeze_tokens)
+        VALUES ($1, $2, $3, $4, $5)
+        ON CONFLICT (user_id) DO UPDATE SET
+            current_streak = EXCLUDED.current_streak,
+            longest_streak = EXCLUDED.longest_streak,
+            last_active_date = EXCLUDED.last_active_date,
+            freeze_tokens = EXCLUDED.freeze_tokens,
+            updated_at = NOW()
+        "#,
+    )
+    .bind(user_id)
+    .bind(streak)
+    .bind(longest)
+    .bind(today)
+    .bind(tokens)
+    .execute(pool)
+    .await;
+
+    if let Err(e) = result {
+        tracing::error!("Failed to record streak: {}", e);
+    }
+}
+
+/// The user's streak as of today. A streak whose gap can no longer be covered
+/// by freeze tokens is reported as 0 even though the row is only reset on the
+/// next submission.
+pub async fn streak_summary(pool: &PgPool, user_id: Uuid) -> StreakSummary {
+    let streak = sqlx::query_as::<_, UserStreak>(
+        r#"SELECT * FROM user_streaks WHERE user_id = $1"#,
+    )
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await
+    .unwrap_or(None);
+
+    let (streak, today) = match (streak, local_today(pool, user_id).await) {
+        (Some(s), Some(today)) => (s, today),
+        _ => {
+            return StreakSummary {
+                current_streak: 0,
+                longest_streak: 0,
+                freeze_tokens: 0,
+                last_active_date: None,
+                active_today: false,
+            }
+        }
+    };
+
+    let (current_streak, active_today) = match streak.last_active_date {
+        Some(last) => {
+            let alive = missed_days(last, today) <= streak.freeze_tokens;
+            (if alive { streak.current_streak } else { 0 }, last >= today)
+        }
+        None => (0, false),
+    };
+
+    StreakSummary {
+        current_streak,
+        longest_streak: streak.longest_streak,
+        freeze_tokens: streak.freeze_tokens,
+        last_active_date: streak.last_active_date,
+        active_today,
+    }
+}
+
+/// The (current, longest, freeze tokens) streak state after activity on
+/// `today`, or `None` when the user was already active that day.
+fn advance_streak(
+    (mut streak, longest, mut tokens): (i32, i32, i32),
+    last_active: Option<NaiveDate>,
+    today: NaiveDate,
+    config: &StreakConfig,
+) -> Option<(i32, i32, i32)> {
+    match last_active {
+        Some(last) if last >= today => return None,
+        Some(last) => {
+            // Each missed day burns one freeze token; too many and the streak restarts
+            let missed = missed_days(last, today);
+            if missed <= tokens {
+                tokens -= missed;
+                streak += 1;
+            } else {
+                streak = 1;
+            }
+        }
+        None => streak = 1,
+    }
+
+    if config.freeze_earn_days > 0 && streak % config.freeze_earn_days == 0 {
+        tokens = (tokens + 1).min(config.max_freeze_tokens);
+    }
+    Some((streak, longest.max(streak), tokens))
+}
+
+/// Days without activity strictly between `last` and `today`.
+fn missed_days(last: NaiveDate, today: NaiveDate) -> i32 {
+    (today - last).num_days() as i32 - 1
+}
+
+async fn local_today(pool: &PgPool, user_id: Uuid) -> Option<NaiveDate> {
+    sqlx::query_scalar::<_, NaiveDate>(
+        r#"SELECT (NOW() AT TIME ZONE timezone)::date FROM users WHERE id = $1"#,
+    )
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await
+    .ok()
+    .flatten()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: StreakConfig = StreakConfig {
+        freeze_earn_days: 7,
+        max_freeze_tokens: 2,
+    };
+
+    fn day(d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 3, d).unwrap()
+    }
+
+    #[test]
+    fn starts_and_extends_a_streak() {
+        assert_eq!(advance_streak((0, 0, 0), None, day(1), &CONFIG), Some((1, 1, 0)));
+        assert_eq!(advance_streak((1, 1, 0), Some(day(1)), day(2), &CONFIG), Some((2, 2, 0)));
+    }
+
+    #[test]
+    fn same_day_activity_is_a_no_op() {
+        assert_eq!(advance_streak((3, 5, 1), Some(day(4)), day(4), &CONFIG), None);
+        // A timezone change can move "today" before the last active date
+        assert_eq!(advance_streak((3, 5, 1), Some(day(5)), day(4), &CONFIG), None);
+    }
+
+    #[test]
+    fn freeze_tokens_cover_missed_days() {
+        assert_eq!(advance_streak((4, 4, 2), Some(day(1)), day(4), &CONFIG), Some((5, 5, 0)));
+        assert_eq!(advance_streak((4, 4, 1), Some(day(1)), day(3), &CONFIG), Some((5, 5, 0)));
+    }
+
+    #[test]
+    fn too_many_missed_days_restart_the_streak() {
+        assert_eq!(advance_streak((9, 9, 1), Some(day(1)), day(4), &CONFIG), Some((1, 9, 1)));
+    }
+
+    #[test]
+    fn earns_tokens_up_to_the_cap() {
+        assert_eq!(advance_streak((6, 6, 0), Some(day(6)), day(7), &CONFIG), Some((7, 7, 1)));
+        assert_eq!(advance_streak((13, 13, 2), Some(day(13)), day(14), &CONFIG), Some((14, 14, 2)));
+
+        let never = StreakConfig {
+            freeze_earn_days: 0,
+            max_freeze_tokens: 2,
+        };
+        assert_eq!(advance_streak((6, 6, 0), Some(day(6)), day(7), &never), Some((7, 7, 0)));
+    }
+
+    #[test]
+    fn counts_missed_days() {
+        assert_eq!(missed_days(day(1), day(2)), 0);
+        assert_eq!(missed_days(day(1), day(4)), 2);
+        assert_eq!(missed_days(day(4), day(4)), -1);
+    }
+}
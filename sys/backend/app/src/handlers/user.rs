@@ -3,11 +3,18 @@ use sqlx::PgPool;
 use uuid::Uuid;
 
 use crate::config::Config;
+use crate::content_filter::{ContentKind, FilterOutcome, FilterPipeline, Verdict};
+use crate::db::visibility::answer_visible_to;
+use crate::handlers::answer::answers_with_details;
+use crate::handlers::badge::awarded_badges;
+use crate::handlers::moderation;
+use crate::handlers::streak::streak_summary;
 use crate::models::{
-    Answer, AnswerWithDetails, Challenge, CreateUserRequest, PaginationParams,
-    UpdateUserRequest, User, UserProfile, UserSummary,
+    Answer, CreateUserRequest, PaginationParams, UpdateUserRequest, User, UserProfile,
+    UserSearchParams, UserSummary,
 };
 use crate::utils;
+use crate::utils::text::search_key;
 
 pub async fn get_user(
     pool: web::Data<PgPool>,
@@ -34,16 +41,19 @@ pub async fn get_user(
     };
 
     // Get counts
-    let answer_count: (i64,) = sqlx::query_as(
-        r#"SELECT COUNT(*) FROM answers WHERE user_id = $1 AND status = 'active'"#,
-    )
+    // Counts what the viewer can see; owners also count their private practice answers
+    let answer_count: (i64,) = sqlx::query_as(&format!(
+        r#"SELECT COUNT(*) FROM answers WHERE user_id = $1 AND status = 'active' AND {}"#,
+        answer_visible_to("answers", 2)
+    ))
     .bind(user_id)
+    .bind(current_user_id)
     .fetch_one(pool.get_ref())
     .await
     .unwrap_or((0,));
 
     let follower_count: (i64,) = sqlx::query_as(
-        r#"SELECT COUNT(*) FROM follows WHERE following_id = $1"#,
+        r#"SELECT COUNT(*) FROM follows WHERE following_id = $1 AND status = 'accepted'"#,
     )
     .bind(user_id)
     .fetch_one(pool.get_ref())
@@ -51,24 +61,35 @@ pub async fn get_user(
     .unwrap_or((0,));
 
     let following_count: (i64,) = sqlx::query_as(
-        r#"SELECT COUNT(*) FROM follows WHERE follower_id = $1"#,
+        r#"SELECT COUNT(*) FROM follows WHERE follower_id = $1 AND status = 'accepted'"#,
     )
     .bind(user_id)
     .fetch_one(pool.get_ref())
     .await
     .unwrap_or((0,));
 
-    let is_following = if let Some(uid) = current_user_id {
-        sqlx::query_scalar::<_, bool>(
-            r#"SELECT EXISTS(SELECT 1 FROM follows WHERE follower_id = $1 AND following_id = $2)"#,
+    let follow_status = if let Some(uid) = current_user_id {
+        sqlx::query_scalar::<_, String>(
+            r#"SELECT status FROM follows WHERE follower_id = $1 AND following_id = $2"#,
         )
         .bind(uid)
         .bind(user_id)
-        .fetch_one(pool.get_ref())
+        .fetch_optional(pool.get_ref())
         .await
-        .unwrap_or(false)
+        .unwrap_or(None)
+    } else {
+        None
+    };
+
+    // Private accounts keep their streak and badges to themselves and approved followers
+    let is_following = follow_status.as_deref() == Some("accepted");
+    let (streak, badges) = if !user.is_private || current_user_id == Some(user.id) || is_following {
+        (
+            Some(streak_summary(pool.get_ref(), user_id).await),
+            awarded_badges(pool.get_ref(), user_id).await,
+        )
     } else {
-        false
+        (None, Vec::new())
     };
 
     utils::success(UserProfile {
@@ -80,7 +101,11 @@ pub async fn get_user(
         answer_count: answer_count.0,
         follower_count: follower_count.0,
         following_count: following_count.0,
+        is_private: user.is_private,
         is_following,
+        is_follow_requested: follow_status.as_deref() == Some("pending"),
+        streak,
+        badges,
     })
 }
 
@@ -100,25 +125,29 @@ pub async fn get_user_answers(
         config.pagination.max_page_size,
     );
 
-    let total: (i64,) = sqlx::query_as(
-        r#"SELECT COUNT(*) FROM answers WHERE user_id = $1 AND status = 'active'"#,
-    )
+    let total: (i64,) = sqlx::query_as(&format!(
+        r#"SELECT COUNT(*) FROM answers WHERE user_id = $1 AND status = 'active' AND {}"#,
+        answer_visible_to("answers", 2)
+    ))
     .bind(user_id)
+    .bind(current_user_id)
     .fetch_one(pool.get_ref())
     .await
     .unwrap_or((0,));
 
-    let answers = sqlx::query_as::<_, Answer>(
+    let answers = sqlx::query_as::<_, Answer>(&format!(
         r#"
         SELECT * FROM answers
-        WHERE user_id = $1 AND status = 'active'
+        WHERE user_id = $1 AND status = 'active' AND {}
         ORDER BY created_at DESC
         LIMIT $2 OFFSET $3
         "#,
-    )
+        answer_visible_to("answers", 4)
+    ))
     .bind(user_id)
     .bind(page_size)
     .bind(offset)
+    .bind(current_user_id)
     .fetch_all(pool.get_ref())
     .await;
 
@@ -130,70 +159,91 @@ pub async fn get_user_answers(
         }
     };
 
-    let user = sqlx::query_as::<_, User>(
-        r#"SELECT * FROM users WHERE id = $1"#,
+    let results = answers_with_details(pool.get_ref(), answers, current_user_id).await;
+
+    utils::paginated(results, page, page_size, total.0)
+}
+
+pub async fn search_users(
+    pool: web::Data<PgPool>,
+    config: web::Data<Config>,
+    query: web::Query<UserSearchParams>,
+) -> HttpResponse {
+    let (page, page_size, offset) = utils::normalize_pagination(
+        query.page,
+        query.page_size,
+        config.pagination.default_page_size,
+        config.pagination.max_page_size,
+    );
+
+    // Match on folded names so カタカナ/ひらがな and full/half-width spellings agree
+    let key = search_key(&query.q);
+    if key.is_empty() {
+        return utils::bad_request("Search query must contain letters or digits");
+    }
+
+    let total: (i64,) = sqlx::query_as(
+        r#"
+        SELECT COUNT(*) FROM users
+        WHERE status = 'active' AND STRPOS(search_name, $1) > 0
+        "#,
     )
-    .bind(user_id)
+    .bind(&key)
     .fetch_one(pool.get_ref())
+    .await
+    .unwrap_or((0,));
+
+    // Exact matches first, then prefix matches, then anywhere in the name
+    let users = sqlx::query_as::<_, User>(
+        r#"
+        SELECT * FROM users
+        WHERE status = 'active' AND STRPOS(search_name, $1) > 0
+        ORDER BY
+            CASE WHEN search_name = $1 THEN 0 WHEN STRPOS(search_name, $1) = 1 THEN 1 ELSE 2 END,
+            total_likes DESC,
+            name ASC
+        LIMIT $2 OFFSET $3
+        "#,
+    )
+    .bind(&key)
+    .bind(page_size)
+    .bind(offset)
+    .fetch_all(pool.get_ref())
     .await;
 
-    let user = match user {
-        Ok(u) => u,
-        Err(e) => {
-            tracing::error!("Failed to fetch user: {}", e);
-            return utils::internal_error("Failed to fetch user answers");
+    match users {
+        Ok(users) => {
+            let summaries: Vec<UserSummary> = users
+                .into_iter()
+                .map(|u| UserSummary {
+                    id: u.id,
+                    name: u.name,
+                    avatar: u.avatar,
+                })
+                .collect();
+            utils::paginated(summaries, page, page_size, total.0)
         }
-    };
-
-    let user_summary = UserSummary {
-        id: user.id,
-        name: user.name,
-        avatar: user.avatar,
-    };
-
-    let mut results: Vec<AnswerWithDetails> = Vec::new();
-    for answer in answers {
-        let challenge = sqlx::query_as::<_, Challenge>(
-            r#"SELECT * FROM challenges WHERE id = $1"#,
-        )
-        .bind(answer.challenge_id)
-        .fetch_one(pool.get_ref())
-        .await;
-
-        let is_liked = if let Some(uid) = current_user_id {
-            sqlx::query_scalar::<_, bool>(
-                r#"SELECT EXISTS(SELECT 1 FROM likes WHERE answer_id = $1 AND user_id = $2)"#,
-            )
-            .bind(answer.id)
-            .bind(uid)
-            .fetch_one(pool.get_ref())
-            .await
-            .unwrap_or(false)
-        } else {
-            false
-        };
-
-        if let Ok(challenge) = challenge {
-            results.push(AnswerWithDetails {
-                answer,
-                user: user_summary.clone(),
-                challenge,
-                is_liked,
-            });
+        Err(e) => {
+            tracing::error!("Failed to search users: {}", e);
+            utils::internal_error("Failed to search users")
         }
     }
-
-    utils::paginated(results, page, page_size, total.0)
 }
 
 pub async fn create_user(
     pool: web::Data<PgPool>,
+    content_filter: web::Data<FilterPipeline>,
     body: web::Json<CreateUserRequest>,
 ) -> HttpResponse {
+    let outcome = check_profile(&content_filter, Some(&body.name), body.bio.as_deref());
+    if outcome.verdict == Verdict::Reject {
+        return utils::unprocessable("Profile rejected by filter", outcome);
+    }
+
     let result = sqlx::query_as::<_, User>(
         r#"
-        INSERT INTO users (email, name, avatar, bio)
-        VALUES ($1, $2, $3, $4)
+        INSERT INTO users (email, name, avatar, bio, search_name)
+        VALUES ($1, $2, $3, $4, $5)
         RETURNING *
         "#,
     )
@@ -201,11 +251,18 @@ pub async fn create_user(
     .bind(&body.name)
     .bind(&body.avatar)
     .bind(&body.bio)
+    .bind(search_key(&body.name))
     .fetch_one(pool.get_ref())
     .await;
 
     match result {
-        Ok(user) => utils::created(user),
+        Ok(user) => {
+            // Held profiles stay usable; a moderator reviews them from the queue
+            if outcome.verdict == Verdict::Hold {
+                moderation::file_system_report(pool.get_ref(), "user", user.id, &outcome.describe()).await;
+            }
+            utils::created(user)
+        }
         Err(e) => {
             tracing::error!("Failed to create user: {}", e);
             if e.to_string().contains("duplicate") {
@@ -219,6 +276,7 @@ pub async fn create_user(
 
 pub async fn update_user(
     pool: web::Data<PgPool>,
+    content_filter: web::Data<FilterPipeline>,
     req: HttpRequest,
     path: web::Path<Uuid>,
     body: web::Json<UpdateUserRequest>,
@@ -249,30 +307,83 @@ pub async fn update_user(
         }
     };
 
+    let outcome = check_profile(&content_filter, body.name.as_deref(), body.bio.as_deref());
+    if outcome.verdict == Verdict::Reject {
+        return utils::unprocessable("Profile rejected by filter", outcome);
+    }
+
     let name = body.name.as_ref().unwrap_or(&user.name);
     let avatar = body.avatar.as_ref().or(user.avatar.as_ref());
     let bio = body.bio.as_ref().or(user.bio.as_ref());
+    let is_private = body.is_private.unwrap_or(user.is_private);
+
+    if let Some(tz) = &body.timezone {
+        let known = sqlx::query_scalar::<_, bool>(
+            r#"SELECT EXISTS(SELECT 1 FROM pg_timezone_names WHERE name = $1)"#,
+        )
+        .bind(tz)
+        .fetch_one(pool.get_ref())
+        .await
+        .unwrap_or(false);
+        if !known {
+            return utils::bad_request("Unknown timezone");
+        }
+    }
+    let timezone = body.timezone.as_ref().unwrap_or(&user.timezone);
 
     let result = sqlx::query_as::<_, User>(
         r#"
         UPDATE users
-        SET name = $1, avatar = $2, bio = $3, updated_at = NOW()
-        WHERE id = $4
+        SET name = $1, avatar = $2, bio = $3, search_name = $4, is_private = $5, timezone = $6,
+            updated_at = NOW()
+        WHERE id = $7
         RETURNING *
         "#,
     )
     .bind(name)
     .bind(avatar)
     .bind(bio)
+    .bind(search_key(name))
+    .bind(is_private)
+    .bind(timezone)
     .bind(user_id)
     .fetch_one(pool.get_ref())
     .await;
 
     match result {
-        Ok(user) => utils::success(user),
+        Ok(user) => {
+            if outcome.verdict == Verdict::Hold {
+                moderation::file_system_report(pool.get_ref(), "user", user.id, &outcome.describe()).await;
+            }
+            // Going public lets everyone in; outstanding requests become follows
+            if !user.is_private {
+                let _ = sqlx::query(
+                    r#"UPDATE follows SET status = 'accepted' WHERE following_id = $1 AND status = 'pending'"#,
+                )
+                .bind(user.id)
+                .execute(pool.get_ref())
+                .await;
+            }
+            utils::success(user)
+        }
         Err(e) => {
             tracing::error!("Failed to update user: {}", e);
             utils::internal_error("Failed to update user")
         }
     }
 }
+
+/// Runs the display name and bio through the content filter.
+fn check_profile(content_filter: &FilterPipeline, name: Option<&str>, bio: Option<&str>) -> FilterOutcome {
+    let mut outcome = FilterOutcome {
+        verdict: Verdict::Allow,
+        reasons: Vec::new(),
+    };
+    if let Some(name) = name {
+        outcome = outcome.merge(content_filter.check(ContentKind::UserName, name, &[]));
+    }
+    if let Some(bio) = bio {
+        outcome = outcome.merge(content_filter.check(ContentKind::UserBio, bio, &[]));
+    }
+    outcome
+}
@@ -0,0 +1,168 @@
+use actix_web::{web, HttpResponse};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::config::{Config, XpConfig};
+use crate::models::{CategoryLevel, UserLevels};
+use crate::utils;
+
+/// Ledger entries. Each is credited to the author of the answer it refers to.
+#[derive(Debug, Clone, Copy)]
+pub enum XpEvent {
+    Answer,
+    Score(i32),
+    /// The answer's score was cleared by an edit; reverses its score XP
+    Unscore,
+    Like,
+    Unlike,
+}
+
+impl XpEvent {
+    fn as_str(&self) -> &'static str {
+        match self {
+            XpEvent::Answer => "answer",
+            XpEvent::Score(_) => "score",
+            XpEvent::Unscore => "unscore",
+            XpEvent::Like => "like",
+            XpEvent::Unlike => "unlike",
+        }
+    }
+
+    fn value(&self) -> Option<i32> {
+        match self {
+            XpEvent::Score(score) => Some(*score),
+            _ => None,
+        }
+    }
+}
+
+pub async fn get_user_levels(
+    pool: web::Data<PgPool>,
+    config: web::Data<Config>,
+    path: web::Path<Uuid>,
+) -> HttpResponse {
+    let user_id = path.into_inner();
+
+    let exists = sqlx::query_scalar::<_, bool>(
+        r#"SELECT EXISTS(SELECT 1 FROM users WHERE id = $1 AND status = 'active')"#,
+    )
+    .bind(user_id)
+    .fetch_one(pool.get_ref())
+    .await
+    .unwrap_or(false);
+
+    if !exists {
+        return utils::not_found("User not found");
+    }
+
+    match user_levels(pool.get_ref(), &config.xp, user_id).await {
+        Ok(levels) => utils::success(levels),
+        Err(e) => {
+            tracing::error!("Failed to compute levels: {}", e);
+            utils::internal_error("Failed to fetch levels")
+        }
+    }
+}
+
+/// Appends an entry to the XP ledger for the answer's author.
+pub async fn record_xp(pool: &PgPool, answer_id: Uuid, event: XpEvent) {
+    let result = sqlx::query(
+        r#"
+        INSERT INTO xp_events (user_id, event_type, answer_id, challenge_id, category_id, value)
+        SELECT a.user_id, $2, a.id, a.challenge_id, ch.category_id, $3
+        FROM answers a
+        JOIN challenges ch ON ch.id = a.challenge_id
+        WHERE a.id = $1
+        "#,
+    )
+    .bind(answer_id)
+    .bind(event.as_str())
+    .bind(event.value())
+    .execute(pool)
+    .await;
+
+    if let Err(e) = result {
+        tracing::error!("Failed to record xp event: {}", e);
+    }
+}
+
+/// Replays the user's ledger under the current XP rules. Every active category
+/// is listed, starting at Lv.1.
+pub async fn user_levels(
+    pool: &PgPool,
+    config: &XpConfig,
+    user_id: Uuid,
+) -> Result<UserLevels, sqlx::Error> {
+    // Only the first answer per challenge and the latest score per answer earn
+    // XP, so resubmitting or rescoring cannot farm it. An unscore entry carries
+    // no value, so an answer whose score was cleared earns nothing until rescored.
+    let rows = sqlx::query_as::<_, (Uuid, String, i64)>(
+        r#"
+        WITH earned AS (
+            (
+                SELECT DISTINCT ON (challenge_id) category_id, $2::DOUBLE PRECISION AS xp
+                FROM xp_events
+                WHERE user_id = $1 AND event_type = 'answer'
+            )
+            UNION ALL
+            SELECT category_id,
+                CASE event_type WHEN 'like' THEN $3::DOUBLE PRECISION ELSE -$3::DOUBLE PRECISION END
+            FROM xp_events
+            WHERE user_id = $1 AND event_type IN ('like', 'unlike')
+            UNION ALL
+            (
+                SELECT DISTINCT ON (answer_id) category_id, value * $4::DOUBLE PRECISION
+                FROM xp_events
+                WHERE user_id = $1 AND event_type IN ('score', 'unscore')
+                ORDER BY answer_id, created_at DESC, id DESC
+            )
+        )
+        SELECT c.id, c.name, GREATEST(COALESCE(ROUND(SUM(e.xp)), 0), 0)::BIGINT
+        FROM categories c
+        LEFT JOIN earned e ON e.category_id = c.id
+        WHERE c.status = 'active'
+        GROUP BY c.id, c.name, c.sort_order
+        ORDER BY c.sort_order ASC
+        "#,
+    )
+    .bind(user_id)
+    .bind(config.answer_xp)
+    .bind(config.like_xp)
+    .bind(config.score_multiplier)
+    .fetch_all(pool)
+    .await?;
+
+    let categories: Vec<CategoryLevel> = rows
+        .into_iter()
+        .map(|(category_id, category_name, xp)| {
+            let (level, next_level_xp) = level_for(xp, config.level_base);
+            CategoryLevel {
+                category_id,
+                category_name,
+                xp,
+                level,
+                next_level_xp,
+            }
+        })
+        .collect();
+
+    let total_xp: i64 = categories.iter().map(|c| c.xp).sum();
+    let (level, next_level_xp) = level_for(total_xp, config.level_base);
+
+    Ok(UserLevels {
+        total_xp,
+        level,
+        next_level_xp,
+        categories,
+    })
+}
+
+/// Level for an XP total and the XP at which the next level starts.
+fn level_for(xp: i64, level_base: i32) -> (i32, i64) {
+    let base = i64::from(level_base.max(1));
+    let mut level: i64 = 1;
+    while base * level * level <= xp {
+        level += 1;
+    }
+    (level as i32, base * level * level)
+}
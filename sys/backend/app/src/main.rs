@@ -1,4 +1,6 @@
+mod auth;
 mod config;
+mod content_filter;
 mod db;
 mod handlers;
 mod middleware;
@@ -30,8 +32,16 @@ async fn main() -> std::io::Result<()> {
 
     // Run migrations
     db::run_migrations(&pool).await;
+    db::backfill_search_names(&pool).await;
+    handlers::badge::backfill_badges(&pool).await;
+    handlers::difficulty::refresh_derived_difficulty(&pool, None).await;
     info!("Migrations completed");
 
+    handlers::daily::spawn_scheduler(pool.clone(), config.daily.clone());
+    handlers::contest::spawn_finalizer(pool.clone(), config.contest.clone());
+
+    let bind_addr = (config.server.host.clone(), config.server.port);
+    let content_filter = web::Data::new(content_filter::FilterPipeline::from_config(&config.content_filter));
     let pool = web::Data::new(pool);
     let config = web::Data::new(config);
 
@@ -40,11 +50,12 @@ async fn main() -> std::io::Result<()> {
         App::new()
             .app_data(pool.clone())
             .app_data(config.clone())
+            .app_data(content_filter.clone())
             .wrap(middleware::cors())
             .wrap(tracing_actix_web::TracingLogger::default())
             .configure(routes::configure)
     })
-    .bind(("0.0.0.0", 8080))?
+    .bind(bind_addr)?
     .run()
     .await
 }
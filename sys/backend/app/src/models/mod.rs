@@ -15,6 +15,9 @@ pub struct User {
     pub bio: Option<String>,
     pub total_likes: i32,
     pub status: String,
+    pub role: String,
+    pub is_private: bool,
+    pub timezone: String,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -29,24 +32,694 @@ pub struct UserProfile {
     pub answer_count: i64,
     pub follower_count: i64,
     pub following_count: i64,
+    pub is_private: bool,
     pub is_following: bool,
+    pub is_follow_requested: bool,
+    pub streak: Option<StreakSummary>, // None for private accounts the viewer may not see
+    pub badges: Vec<AwardedBadge>,
+}
+
+/// A suggested account to follow, with the signals that produced it.
+#[derive(Debug, Serialize, FromRow)]
+pub struct UserSuggestion {
+    pub id: Uuid,
+    pub name: String,
+    pub avatar: Option<String>,
+    pub bio: Option<String>,
+    pub mutual_follow_count: i64, // people you follow who follow them
+    pub shared_category_count: i64, // categories you both answer in
+    pub high_rated_answer_count: i64,
+    pub score: f64,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateUserRequest {
+    pub email: Option<String>,
+    #[validate(length(min = 1, max = 100))]
+    pub name: String,
+    pub avatar: Option<String>,
+    pub bio: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateUserRequest {
+    #[validate(length(min = 1, max = 100))]
+    pub name: Option<String>,
+    pub avatar: Option<String>,
+    pub bio: Option<String>,
+    pub is_private: Option<bool>,
+    pub timezone: Option<String>, // IANA name, e.g. Asia/Tokyo
+}
+
+// ============ Streak ============
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct UserStreak {
+    pub user_id: Uuid,
+    pub current_streak: i32,
+    pub longest_streak: i32,
+    pub last_active_date: Option<NaiveDate>,
+    pub freeze_tokens: i32,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StreakSummary {
+    pub current_streak: i32,
+    pub longest_streak: i32,
+    pub freeze_tokens: i32,
+    pub last_active_date: Option<NaiveDate>,
+    pub active_today: bool,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct ActivityDay {
+    pub date: NaiveDate,
+    pub count: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ActivityCalendar {
+    pub timezone: String,
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub days: Vec<ActivityDay>,
+}
+
+// ============ Badge ============
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Badge {
+    pub key: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub icon: Option<String>,
+    pub rule: String,
+    pub threshold: i32,
+    pub sort_order: i32,
+    pub status: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct AwardedBadge {
+    pub key: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub icon: Option<String>,
+    pub awarded_at: DateTime<Utc>,
+}
+
+// ============ Experience ============
+
+#[derive(Debug, Serialize)]
+pub struct CategoryLevel {
+    pub category_id: Uuid,
+    pub category_name: String,
+    pub xp: i64,
+    pub level: i32,
+    pub next_level_xp: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct UserLevels {
+    pub total_xp: i64,
+    pub level: i32,
+    pub next_level_xp: i64,
+    pub categories: Vec<CategoryLevel>,
+}
+
+// ============ Stats ============
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct ScoreTrendPoint {
+    pub category_id: Uuid,
+    pub category_name: String,
+    pub period: NaiveDate,
+    pub average_score: f64,
+    pub answer_count: i64,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct CriterionStat {
+    pub name: String,
+    pub average_score: f64,
+    pub samples: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct UserStats {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub interval: String,
+    pub answer_count: i64,
+    pub average_score: Option<f64>,
+    pub average_length: f64,
+    /// Average share of the challenge's char_limit used, 0.0-1.0
+    pub char_limit_utilization: f64,
+    pub likes_per_answer: f64,
+    pub score_trends: Vec<ScoreTrendPoint>,
+    pub best_answers: Vec<AnswerWithDetails>,
+    pub weakest_criteria: Vec<CriterionStat>,
+}
+
+// ============ Course ============
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Course {
+    pub id: Uuid,
+    pub title: String,
+    pub description: Option<String>,
+    pub category_id: Option<Uuid>,
+    pub status: String,
+    pub created_by: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct CourseStep {
+    pub id: Uuid,
+    pub course_id: Uuid,
+    pub challenge_id: Uuid,
+    pub position: i32,
+    pub unlock_rule: String,     // sequential, open
+    pub pass_score: Option<i32>, // minimum score to complete; any answer if null
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct CourseEnrollment {
+    pub id: Uuid,
+    pub course_id: Uuid,
+    pub user_id: Uuid,
+    pub completed_steps: i32,
+    pub enrolled_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct CourseCertificate {
+    pub id: Uuid,
+    pub course_id: Uuid,
+    pub user_id: Uuid,
+    pub course_title: String,
+    pub average_score: Option<f64>,
+    pub issued_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct CourseSummary {
+    #[serde(flatten)]
+    #[sqlx(flatten)]
+    pub course: Course,
+    pub step_count: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CourseStepProgress {
+    #[serde(flatten)]
+    pub step: CourseStep,
+    pub challenge: Challenge,
+    pub best_score: Option<i32>,
+    pub completed: bool,
+    pub unlocked: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CourseDetail {
+    #[serde(flatten)]
+    pub course: Course,
+    pub steps: Vec<CourseStepProgress>,
+    pub enrollment: Option<CourseEnrollment>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CourseResume {
+    pub enrollment: CourseEnrollment,
+    /// First unlocked step not yet completed; None once the course is finished
+    pub next_step: Option<CourseStepProgress>,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct EnrollmentWithCourse {
+    #[serde(flatten)]
+    #[sqlx(flatten)]
+    pub enrollment: CourseEnrollment,
+    pub course_title: String,
+    pub step_count: i64,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateCourseRequest {
+    #[validate(length(min = 1, max = 200))]
+    pub title: String,
+    pub description: Option<String>,
+    pub category_id: Option<Uuid>,
+    #[validate(length(min = 1, max = 50))]
+    pub steps: Vec<CourseStepInput>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CourseStepInput {
+    pub challenge_id: Uuid,
+    pub unlock_rule: Option<String>,
+    pub pass_score: Option<i32>,
+}
+
+// ============ Organization ============
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Organization {
+    pub id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    pub created_by: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// An organization as seen by one of its members.
+#[derive(Debug, Serialize, FromRow)]
+pub struct OrganizationWithRole {
+    #[sqlx(flatten)]
+    #[serde(flatten)]
+    pub organization: Organization,
+    pub role: String,
+    pub member_count: i64,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct OrganizationMember {
+    pub user_id: Uuid,
+    pub name: String,
+    pub avatar: Option<String>,
+    pub role: String, // owner, instructor, learner
+    pub joined_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct OrgGroup {
+    pub id: Uuid,
+    pub organization_id: Uuid,
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct OrgGroupWithCount {
+    #[sqlx(flatten)]
+    #[serde(flatten)]
+    pub group: OrgGroup,
+    pub member_count: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Assignment {
+    pub id: Uuid,
+    pub organization_id: Uuid,
+    pub group_id: Uuid,
+    pub challenge_id: Uuid,
+    pub deadline_at: DateTime<Utc>,
+    pub created_by: Uuid,
+    pub created_at: DateTime<Utc>,
+}
+
+/// An assignment with its challenge and group names. `status` is the viewer's
+/// own submission status, absent for staff outside the group.
+#[derive(Debug, Serialize, FromRow)]
+pub struct AssignmentWithStatus {
+    #[sqlx(flatten)]
+    #[serde(flatten)]
+    pub assignment: Assignment,
+    pub challenge_title: String,
+    pub group_name: String,
+    pub status: Option<String>, // submitted, late, pending, missing
+}
+
+/// One learner's submission for an assignment.
+#[derive(Debug, Serialize, FromRow)]
+pub struct AssignmentSubmission {
+    pub user_id: Uuid,
+    pub name: String,
+    pub avatar: Option<String>,
+    pub status: String, // submitted, late, pending, missing
+    pub answer_id: Option<Uuid>,
+    pub submitted_at: Option<DateTime<Utc>>,
+    pub score: Option<i32>,
+    pub human_score: Option<i32>,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct OrgLeaderboardEntry {
+    pub user_id: Uuid,
+    pub name: String,
+    pub avatar: Option<String>,
+    pub answer_count: i64,
+    pub average_score: Option<f64>,
+    pub like_count: i64,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateOrganizationRequest {
+    #[validate(length(min = 1, max = 100))]
+    pub name: String,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AddOrgMemberRequest {
+    pub user_id: Uuid,
+    pub role: Option<String>, // defaults to learner
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateOrgGroupRequest {
+    #[validate(length(min = 1, max = 100))]
+    pub name: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AddGroupMemberRequest {
+    pub user_id: Uuid,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateAssignmentRequest {
+    pub group_id: Uuid,
+    pub challenge_id: Uuid,
+    pub deadline_at: DateTime<Utc>,
+}
+
+// ============ Review ============
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct RubricCriterion {
+    pub id: Uuid,
+    pub category_id: Uuid,
+    pub key: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub max_points: i32,
+    pub position: i32,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateRubricRequest {
+    #[validate(length(max = 20))]
+    #[validate]
+    pub criteria: Vec<RubricCriterionInput>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct RubricCriterionInput {
+    #[validate(length(min = 1, max = 50))]
+    pub key: String,
+    #[validate(length(min = 1, max = 100))]
+    pub name: String,
+    pub description: Option<String>,
+    #[validate(range(min = 1, max = 100))]
+    pub max_points: i32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct AnswerReview {
+    pub id: Uuid,
+    pub answer_id: Uuid,
+    pub reviewer_id: Uuid,
+    pub scores: serde_json::Value, // [{name, score}] keyed by rubric criterion key
+    pub total_score: i32,          // 0-100, points scored over points available
+    pub feedback: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct ReviewWithReviewer {
+    #[sqlx(flatten)]
+    #[serde(flatten)]
+    pub review: AnswerReview,
+    pub reviewer_name: String,
+}
+
+/// Human reviews of an answer next to its AI score.
+#[derive(Debug, Serialize)]
+pub struct AnswerReviewSummary {
+    pub answer_id: Uuid,
+    pub ai_score: Option<i32>,
+    pub ai_feedback: Option<serde_json::Value>,
+    pub human_score: Option<i32>,
+    pub score_gap: Option<i32>, // human minus AI
+    pub reviews: Vec<ReviewWithReviewer>,
+    pub peer_score: Option<i32>,
+    pub peer_review_count: i32,
+    pub peer_feedback: Vec<String>, // anonymous
+}
+
+/// A peer review task as shown to the reviewer. The answer's author and id
+/// are withheld so reviews stay anonymous.
+#[derive(Debug, Serialize, FromRow)]
+pub struct PeerReviewTask {
+    pub id: Uuid,
+    pub challenge_id: Uuid,
+    pub challenge_title: String,
+    pub content: String,
+    pub scores: Option<serde_json::Value>,
+    pub total_score: Option<i32>,
+    pub feedback: Option<String>,
+    pub assigned_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct SubmitReviewRequest {
+    #[validate]
+    pub scores: Vec<CriterionScore>, // name is the rubric criterion key
+    #[validate(length(max = 2000))]
+    pub feedback: Option<String>,
+}
+
+// ============ Battle ============
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Battle {
+    pub id: Uuid,
+    pub challenge_id: Uuid,
+    pub voter_id: Uuid,
+    pub answer_a_id: Uuid,
+    pub answer_b_id: Uuid,
+    pub winner_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub voted_at: Option<DateTime<Utc>>,
+}
+
+/// An answer as shown in a battle, without its author.
+#[derive(Debug, Serialize, FromRow)]
+pub struct BattleAnswer {
+    pub id: Uuid,
+    pub content: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BattlePair {
+    pub battle_id: Uuid,
+    pub challenge_id: Uuid,
+    pub answer_a: BattleAnswer,
+    pub answer_b: BattleAnswer,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct AnswerRating {
+    pub answer_id: Uuid,
+    pub rating: f64,
+    pub battle_count: i32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BattleVoteResult {
+    pub battle: Battle,
+    pub ratings: Vec<AnswerRating>,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct RatedUser {
+    pub id: Uuid,
+    pub name: String,
+    pub avatar: Option<String>,
+    pub rating: f64,
+    pub battle_count: i32,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BattleVoteRequest {
+    pub winner_id: Uuid,
+}
+
+// ============ Contest ============
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Contest {
+    pub id: Uuid,
+    pub title: String,
+    pub description: Option<String>,
+    pub starts_at: DateTime<Utc>,
+    pub entries_close_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+    pub finalized_at: Option<DateTime<Utc>>,
+    pub created_by: Uuid,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct ContestWithPhase {
+    #[sqlx(flatten)]
+    #[serde(flatten)]
+    pub contest: Contest,
+    pub phase: String, // upcoming, open, voting, closed
+    pub challenge_count: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ContestDetail {
+    #[serde(flatten)]
+    pub contest: ContestWithPhase,
+    pub challenges: Vec<Challenge>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ContestVote {
+    pub id: Uuid,
+    pub contest_id: Uuid,
+    pub answer_id: Uuid,
+    pub voter_id: Uuid,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ContestEntry {
+    #[serde(flatten)]
+    pub answer: AnswerWithDetails,
+    pub vote_count: i64,
+    pub is_voted: bool,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct ContestStanding {
+    pub rank: i32,
+    pub user_id: Uuid,
+    pub user_name: String,
+    pub user_avatar: Option<String>,
+    pub total_score: f64,
+    pub ai_score: i32,
+    pub vote_count: i32,
+    pub entry_count: i32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ContestStandings {
+    pub is_final: bool,
+    pub standings: Vec<ContestStanding>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateContestRequest {
+    #[validate(length(min = 1, max = 200))]
+    pub title: String,
+    pub description: Option<String>,
+    pub starts_at: DateTime<Utc>,
+    pub entries_close_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+}
+
+// ============ Editorial Pick ============
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct EditorialPick {
+    pub id: Uuid,
+    pub challenge_id: Uuid,
+    pub answer_id: Uuid,
+    pub picked_by: Uuid,
+    pub note: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A picked answer as shown in a challenge's featured section.
+#[derive(Debug, Serialize)]
+pub struct FeaturedAnswer {
+    #[serde(flatten)]
+    pub answer: AnswerWithDetails,
+    pub note: Option<String>,
+    pub picked_at: DateTime<Utc>,
+    pub is_example: bool,
 }
 
 #[derive(Debug, Deserialize, Validate)]
-pub struct CreateUserRequest {
-    pub email: Option<String>,
-    #[validate(length(min = 1, max = 100))]
-    pub name: String,
-    pub avatar: Option<String>,
-    pub bio: Option<String>,
+pub struct PickAnswerRequest {
+    #[validate(length(max = 500))]
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SetExampleAnswerRequest {
+    pub answer_id: Option<Uuid>, // null goes back to the AI's example answer
+}
+
+// ============ Challenge Proposal ============
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ChallengeProposal {
+    pub id: Uuid,
+    pub category_id: Uuid,
+    pub user_id: Uuid,
+    pub title: String,
+    pub description: Option<String>,
+    pub status: String, // open, accepted, rejected
+    pub upvote_count: i32,
+    pub challenge_id: Option<Uuid>, // the draft challenge created on acceptance
+    pub reviewed_by: Option<Uuid>,
+    pub review_note: Option<String>,
+    pub reviewed_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct ProposalWithAuthor {
+    #[sqlx(flatten)]
+    #[serde(flatten)]
+    pub proposal: ChallengeProposal,
+    pub author_name: String,
+    pub is_upvoted: bool,
 }
 
 #[derive(Debug, Deserialize, Validate)]
-pub struct UpdateUserRequest {
-    #[validate(length(min = 1, max = 100))]
-    pub name: Option<String>,
-    pub avatar: Option<String>,
-    pub bio: Option<String>,
+pub struct CreateProposalRequest {
+    pub category_id: Uuid,
+    #[validate(length(min = 1, max = 200))]
+    pub title: String,
+    #[validate(length(max = 1000))]
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct ReviewProposalRequest {
+    #[validate(length(max = 500))]
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct PublishChallengeRequest {
+    #[validate(length(min = 1, max = 200))]
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub char_limit: Option<i32>,
+    pub release_date: Option<NaiveDate>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct RateChallengeRequest {
+    #[validate(range(min = 1, max = 5))]
+    pub rating: i32,
 }
 
 // ============ Category ============
@@ -76,16 +749,41 @@ pub struct Challenge {
     pub char_limit: i32,
     pub release_date: Option<NaiveDate>,
     pub answer_count: i32,
-    pub status: String,
+    pub status: String, // active, draft (accepted proposal awaiting publication), retired
+    pub difficulty: Option<String>, // easy, normal, hard; overrides derived_difficulty
+    pub derived_difficulty: String,
+    pub hard_mode_char_limit: Option<i32>,
+    pub organization_id: Option<Uuid>, // set for an organization's private challenges
+    pub contest_id: Option<Uuid>, // set for challenges that belong to a contest
+    pub example_answer_id: Option<Uuid>, // picked answer replacing the AI's example answer
+    pub rating_avg: Option<f64>, // average 1-5 user rating
+    pub rating_count: i32,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
 #[derive(Debug, Serialize)]
-pub struct ChallengeWithCategory {
+pub struct ChallengeDetail {
     #[serde(flatten)]
     pub challenge: Challenge,
     pub category: Category,
+    pub featured: Vec<FeaturedAnswer>,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct DailyScheduleEntry {
+    pub schedule_date: NaiveDate,
+    #[serde(flatten)]
+    #[sqlx(flatten)]
+    pub challenge: Challenge,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RecommendedChallenge {
+    #[serde(flatten)]
+    pub challenge: Challenge,
+    /// weakest_category, unanswered, retry or latest
+    pub reason: String,
 }
 
 #[derive(Debug, Deserialize, Validate)]
@@ -96,6 +794,37 @@ pub struct CreateChallengeRequest {
     pub description: Option<String>,
     pub char_limit: Option<i32>,
     pub release_date: Option<NaiveDate>,
+    pub difficulty: Option<String>,
+    pub hard_mode_char_limit: Option<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateDifficultyRequest {
+    pub difficulty: Option<String>, // null goes back to the derived tier
+    /// Omitted keeps the current limit; null goes back to the default share of char_limit
+    #[serde(default, deserialize_with = "present")]
+    pub hard_mode_char_limit: Option<Option<i32>>,
+}
+
+/// Deserializes a field that was present in the body, even as null, to `Some`,
+/// so `#[serde(default)]` leaves only omitted fields as `None`.
+fn present<'de, T, D>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    T: Deserialize<'de>,
+    D: serde::Deserializer<'de>,
+{
+    T::deserialize(deserializer).map(Some)
+}
+
+#[derive(Debug, Serialize)]
+pub struct AdaptiveChallenge {
+    #[serde(flatten)]
+    pub challenge: Challenge,
+    pub effective_difficulty: String,
+    pub hard_mode: bool,
+    pub effective_char_limit: i32,
+    /// Average of the user's recent scores in the category
+    pub skill_score: Option<f64>,
 }
 
 // ============ Answer ============
@@ -108,10 +837,22 @@ pub struct Answer {
     pub content: String,
     pub score: Option<i32>,
     pub ai_feedback: Option<serde_json::Value>,
+    pub human_score: Option<i32>, // average of reviewers' rubric scores
+    pub peer_score: Option<i32>, // reliability-weighted average of peer reviews
+    pub peer_review_count: i32,
+    pub rating: f64, // Elo rating from battles
+    pub battle_count: i32,
     pub like_count: i32,
     pub comment_count: i32,
     pub view_count: i32,
     pub status: String,
+    pub visibility: String, // public, followers, private
+    pub attempt_number: i32,
+    pub is_published: bool, // the attempt shown to others; one per user and challenge
+    pub hard_mode: bool,
+    pub session_id: Option<Uuid>, // set for timed answers
+    pub elapsed_ms: Option<i32>,
+    pub is_late: bool,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -122,6 +863,9 @@ pub struct AnswerWithUser {
     pub answer: Answer,
     pub user: UserSummary,
     pub is_liked: bool,
+    pub is_bookmarked: bool,
+    pub reactions: Vec<ReactionCount>,
+    pub my_reactions: Vec<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -131,6 +875,9 @@ pub struct AnswerWithDetails {
     pub user: UserSummary,
     pub challenge: Challenge,
     pub is_liked: bool,
+    pub is_bookmarked: bool,
+    pub reactions: Vec<ReactionCount>,
+    pub my_reactions: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -144,20 +891,101 @@ pub struct UserSummary {
 pub struct CreateAnswerRequest {
     #[validate(length(min = 1, max = 200))]
     pub content: String,
+    pub visibility: Option<String>,
+    pub hard_mode: Option<bool>,
+    pub session_id: Option<Uuid>, // from POST /challenges/{id}/start
 }
 
 #[derive(Debug, Deserialize, Validate)]
 pub struct UpdateAnswerRequest {
     #[validate(length(min = 1, max = 200))]
     pub content: Option<String>,
+    pub visibility: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Validate)]
 pub struct AiFeedback {
+    #[validate(range(min = 0, max = 100))]
     pub score: i32,
     pub good_points: String,
     pub improvement: String,
     pub example_answer: String,
+    /// Per-criterion breakdown of the score, when the scorer provides one
+    #[serde(default)]
+    #[validate]
+    pub criteria: Vec<CriterionScore>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct CriterionScore {
+    #[validate(length(min = 1, max = 50))]
+    pub name: String,
+    #[validate(range(min = 0, max = 100))]
+    pub score: i32,
+}
+
+/// Snapshot of an attempt's wording, with the score it received.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct AnswerRevision {
+    pub id: Uuid,
+    pub answer_id: Uuid,
+    pub challenge_id: Uuid,
+    pub user_id: Uuid,
+    pub attempt_number: i32,
+    pub revision_number: i32,
+    pub content: String,
+    pub score: Option<i32>,
+    pub ai_feedback: Option<serde_json::Value>,
+    pub created_at: DateTime<Utc>,
+}
+
+// ============ Draft ============
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Draft {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub challenge_id: Uuid,
+    pub content: String,
+    pub version: i32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A draft with its length checked against the challenge's char limit.
+#[derive(Debug, Serialize)]
+pub struct DraftWithCount {
+    #[serde(flatten)]
+    pub draft: Draft,
+    pub char_count: i32,
+    pub char_limit: i32,
+    pub remaining: i32,
+    pub over_limit: bool,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct SaveDraftRequest {
+    #[validate(length(max = 2000))]
+    pub content: String,
+    pub version: Option<i32>, // version the client edited; omit when creating
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PublishDraftRequest {
+    pub version: i32, // version the client last saw
+    pub visibility: Option<String>,
+    pub hard_mode: Option<bool>,
+    pub session_id: Option<Uuid>,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct AnswerSession {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub challenge_id: Uuid,
+    pub started_at: DateTime<Utc>,
+    pub deadline_at: DateTime<Utc>,
+    pub submitted_at: Option<DateTime<Utc>>,
 }
 
 // ============ Comment ============
@@ -186,9 +1014,116 @@ pub struct CreateCommentRequest {
     pub content: String,
 }
 
-// ============ Like ============
+// ============ Reaction ============
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ReactionType {
+    pub key: String,
+    pub label: String,
+    pub emoji: Option<String>,
+    pub ranking_weight: f64,
+    pub sort_order: i32,
+    pub status: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Reaction {
+    pub id: Uuid,
+    pub answer_id: Uuid,
+    pub user_id: Uuid,
+    pub reaction_type: String,
+    pub created_at: DateTime<Utc>,
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ReactionCount {
+    pub reaction_type: String,
+    pub count: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AnswerReactions {
+    pub counts: Vec<ReactionCount>,
+    pub my_reactions: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateReactionRequest {
+    pub reaction_type: String,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpsertReactionTypeRequest {
+    #[validate(length(min = 1, max = 50))]
+    pub label: String,
+    #[validate(length(max = 20))]
+    pub emoji: Option<String>,
+    #[validate(range(min = 0.0, max = 10.0))]
+    pub ranking_weight: Option<f64>,
+    pub sort_order: Option<i32>,
+    pub status: Option<String>, // active, inactive
+}
+
+// ============ Bookmark ============
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Bookmark {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub answer_id: Uuid,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Collection {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    pub item_count: i32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CollectionWithItems {
+    #[serde(flatten)]
+    pub collection: Collection,
+    pub items: Vec<AnswerWithDetails>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateCollectionRequest {
+    #[validate(length(min = 1, max = 100))]
+    pub name: String,
+    #[validate(length(max = 500))]
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateCollectionRequest {
+    #[validate(length(min = 1, max = 100))]
+    pub name: Option<String>,
+    #[validate(length(max = 500))]
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AddCollectionItemRequest {
+    pub answer_id: Uuid,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReorderCollectionRequest {
+    /// Answer IDs in the desired order; must list every item exactly once
+    pub answer_ids: Vec<Uuid>,
+}
+
+// ============ Like ============
+
+/// Response shape of the legacy like endpoints; likes are stored as reactions.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Like {
     pub id: Uuid,
     pub answer_id: Uuid,
@@ -203,6 +1138,76 @@ pub struct Follow {
     pub id: Uuid,
     pub follower_id: Uuid,
     pub following_id: Uuid,
+    pub status: String, // pending, accepted
+    pub created_at: DateTime<Utc>,
+}
+
+// ============ Moderation ============
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Report {
+    pub id: Uuid,
+    pub reporter_id: Option<Uuid>,
+    pub target_type: String, // answer, comment, user
+    pub target_id: Uuid,
+    pub reason: String, // user-selected reason, or "filter" for content held by the content filter
+    pub detail: Option<String>,
+    pub status: String, // open, resolved, dismissed
+    pub resolved_by: Option<Uuid>,
+    pub resolved_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateReportRequest {
+    pub target_type: String,
+    pub target_id: Uuid,
+    pub reason: String, // spam, harassment, hate, sexual, personal_info, impersonation, other
+    #[validate(length(max = 1000))]
+    pub detail: Option<String>,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct ModerationQueueItem {
+    pub target_type: String,
+    pub target_id: Uuid,
+    pub target_status: Option<String>,
+    pub content: Option<String>,
+    pub author_id: Option<Uuid>,
+    pub report_count: i64,
+    pub reasons: Vec<String>,
+    pub author_warning_count: i64,
+    pub first_reported_at: DateTime<Utc>,
+    pub last_reported_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ModerationAction {
+    pub id: Uuid,
+    pub moderator_id: Option<Uuid>,
+    pub target_type: String,
+    pub target_id: Uuid,
+    pub action: String, // hide, restore, warn, suspend, dismiss
+    pub reason: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct ModerationActionRequest {
+    pub target_type: String,
+    pub target_id: Uuid,
+    pub action: String,
+    #[validate(length(max = 1000))]
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct UserWarning {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub moderator_id: Uuid,
+    pub reason: String,
+    pub acknowledged_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
 }
 
@@ -225,6 +1230,84 @@ pub struct AnswerQueryParams {
 pub struct FeedQueryParams {
     pub page: Option<i64>,
     pub page_size: Option<i64>,
-    pub filter: Option<String>, // all, following, category_id
+    pub filter: Option<String>, // all, following, category
+    pub category_id: Option<Uuid>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RankingQueryParams {
+    pub page: Option<i64>,
+    pub page_size: Option<i64>,
+    pub metric: Option<String>, // likes, reactions, rating
+    pub timed: Option<bool>,    // only on-time timed answers
+    pub contest_id: Option<Uuid>, // only entries to this contest
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ModerationQueryParams {
+    pub page: Option<i64>,
+    pub page_size: Option<i64>,
+    pub status: Option<String>, // reports only: open (default), resolved, dismissed
+    pub target_type: Option<String>,
+    pub target_id: Option<Uuid>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UserSearchParams {
+    pub q: String,
+    pub page: Option<i64>,
+    pub page_size: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SuggestionQueryParams {
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ActivityQueryParams {
+    pub days: Option<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StatsQueryParams {
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+    pub interval: Option<String>, // day, week or month
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RecommendationQueryParams {
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AdaptiveQueryParams {
+    pub category_id: Option<Uuid>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct OrgLeaderboardQueryParams {
+    pub period: Option<String>, // day, week or all
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PeerReviewQueryParams {
+    pub status: Option<String>, // pending (default), completed or all
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ContestQueryParams {
+    pub page: Option<i64>,
+    pub page_size: Option<i64>,
+    pub phase: Option<String>, // upcoming, open, voting, closed
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ProposalQueryParams {
+    pub page: Option<i64>,
+    pub page_size: Option<i64>,
     pub category_id: Option<Uuid>,
+    pub status: Option<String>, // open (default), accepted, rejected
+    pub sort: Option<String>,   // top (default) or new
 }
@@ -12,11 +12,30 @@ pub fn configure(cfg: &mut web::ServiceConfig) {
                 "/categories/{id}/challenges",
                 web::get().to(handlers::get_challenges_by_category),
             )
+            .route("/categories/{id}/rubric", web::get().to(handlers::get_rubric))
+            .route("/categories/{id}/rubric", web::put().to(handlers::update_rubric))
             // Challenges
             .route("/challenges", web::get().to(handlers::list_challenges))
             .route("/challenges", web::post().to(handlers::create_challenge))
             .route("/challenges/daily", web::get().to(handlers::get_daily_challenges))
+            .route("/challenges/daily/schedule", web::get().to(handlers::get_daily_schedule))
+            .route("/challenges/adaptive", web::get().to(handlers::get_adaptive_challenges))
+            .route(
+                "/challenges/recommended",
+                web::get().to(handlers::get_recommended_challenges),
+            )
+            .route("/challenges/drafts", web::get().to(handlers::list_draft_challenges))
             .route("/challenges/{id}", web::get().to(handlers::get_challenge))
+            .route("/challenges/{id}/publish", web::post().to(handlers::publish_challenge))
+            .route("/challenges/{id}/rating", web::put().to(handlers::rate_challenge))
+            .route(
+                "/challenges/{id}/start",
+                web::post().to(handlers::start_answer_session),
+            )
+            .route(
+                "/challenges/{id}/difficulty",
+                web::put().to(handlers::update_challenge_difficulty),
+            )
             .route(
                 "/challenges/{id}/answers",
                 web::get().to(handlers::get_challenge_answers),
@@ -25,12 +44,53 @@ pub fn configure(cfg: &mut web::ServiceConfig) {
                 "/challenges/{id}/answers",
                 web::post().to(handlers::create_answer),
             )
+            .route(
+                "/challenges/{id}/attempts",
+                web::get().to(handlers::get_challenge_attempts),
+            )
+            .route(
+                "/challenges/{id}/peer-reviews",
+                web::post().to(handlers::request_peer_reviews),
+            )
+            .route(
+                "/challenges/{id}/example-answer",
+                web::put().to(handlers::set_example_answer),
+            )
+            .route("/challenges/{id}/draft", web::get().to(handlers::get_draft))
+            .route("/challenges/{id}/draft", web::put().to(handlers::save_draft))
+            .route("/challenges/{id}/draft", web::delete().to(handlers::delete_draft))
+            .route(
+                "/challenges/{id}/draft/publish",
+                web::post().to(handlers::publish_draft),
+            )
+            // Challenge proposals
+            .route("/proposals", web::get().to(handlers::list_proposals))
+            .route("/proposals", web::post().to(handlers::create_proposal))
+            .route("/proposals/{id}/upvote", web::post().to(handlers::upvote_proposal))
+            .route("/proposals/{id}/upvote", web::delete().to(handlers::remove_proposal_upvote))
+            .route("/proposals/{id}/accept", web::post().to(handlers::accept_proposal))
+            .route("/proposals/{id}/reject", web::post().to(handlers::reject_proposal))
             // Answers
             .route("/answers/{id}", web::get().to(handlers::get_answer))
             .route("/answers/{id}", web::put().to(handlers::update_answer))
             .route("/answers/{id}", web::delete().to(handlers::delete_answer))
+            .route("/answers/{id}/publish", web::post().to(handlers::publish_answer))
+            .route("/answers/{id}/revisions", web::get().to(handlers::get_answer_revisions))
+            .route("/answers/{id}/feedback", web::put().to(handlers::update_answer_feedback))
+            .route("/answers/{id}/review", web::put().to(handlers::submit_review))
+            .route("/answers/{id}/reviews", web::get().to(handlers::get_answer_reviews))
+            .route("/answers/{id}/pick", web::post().to(handlers::pick_answer))
+            .route("/answers/{id}/pick", web::delete().to(handlers::unpick_answer))
             .route("/answers/{id}/like", web::post().to(handlers::like_answer))
             .route("/answers/{id}/like", web::delete().to(handlers::unlike_answer))
+            .route("/answers/{id}/reactions", web::get().to(handlers::get_answer_reactions))
+            .route("/answers/{id}/reactions", web::post().to(handlers::add_answer_reaction))
+            .route(
+                "/answers/{id}/reactions/{reaction_type}",
+                web::delete().to(handlers::remove_answer_reaction),
+            )
+            .route("/answers/{id}/bookmark", web::post().to(handlers::bookmark_answer))
+            .route("/answers/{id}/bookmark", web::delete().to(handlers::unbookmark_answer))
             .route(
                 "/answers/{id}/comments",
                 web::get().to(handlers::get_answer_comments),
@@ -41,21 +101,130 @@ pub fn configure(cfg: &mut web::ServiceConfig) {
             )
             // Comments
             .route("/comments/{id}", web::delete().to(handlers::delete_comment))
+            // Drafts
+            .route("/drafts", web::get().to(handlers::list_drafts))
+            // Bookmarks & Collections
+            .route("/bookmarks", web::get().to(handlers::get_bookmarks))
+            .route("/collections", web::get().to(handlers::list_collections))
+            .route("/collections", web::post().to(handlers::create_collection))
+            .route("/collections/{id}", web::get().to(handlers::get_collection))
+            .route("/collections/{id}", web::put().to(handlers::update_collection))
+            .route("/collections/{id}", web::delete().to(handlers::delete_collection))
+            .route("/collections/{id}/items", web::post().to(handlers::add_collection_item))
+            .route(
+                "/collections/{id}/items/order",
+                web::put().to(handlers::reorder_collection_items),
+            )
+            .route(
+                "/collections/{id}/items/{answer_id}",
+                web::delete().to(handlers::remove_collection_item),
+            )
+            // Reactions
+            .route("/reactions", web::get().to(handlers::list_reaction_types))
+            .route("/reactions/{key}", web::put().to(handlers::upsert_reaction_type))
+            // Badges
+            .route("/badges", web::get().to(handlers::list_badges))
+            // Courses
+            .route("/courses", web::get().to(handlers::list_courses))
+            .route("/courses", web::post().to(handlers::create_course))
+            .route("/courses/{id}", web::get().to(handlers::get_course))
+            .route("/courses/{id}/enroll", web::post().to(handlers::enroll_course))
+            .route("/courses/{id}/resume", web::get().to(handlers::resume_course))
+            .route("/enrollments", web::get().to(handlers::list_enrollments))
+            .route("/certificates", web::get().to(handlers::list_certificates))
+            .route("/certificates/{id}", web::get().to(handlers::get_certificate))
+            // Peer reviews
+            .route("/peer-reviews", web::get().to(handlers::list_peer_reviews))
+            .route("/peer-reviews/{id}", web::put().to(handlers::submit_peer_review))
+            // Organizations
+            .route("/organizations", web::get().to(handlers::list_organizations))
+            .route("/organizations", web::post().to(handlers::create_organization))
+            .route("/organizations/{id}", web::get().to(handlers::get_organization))
+            .route("/organizations/{id}/members", web::get().to(handlers::list_org_members))
+            .route("/organizations/{id}/members", web::post().to(handlers::add_org_member))
+            .route(
+                "/organizations/{id}/members/{user_id}",
+                web::delete().to(handlers::remove_org_member),
+            )
+            .route("/organizations/{id}/groups", web::get().to(handlers::list_org_groups))
+            .route("/organizations/{id}/groups", web::post().to(handlers::create_org_group))
+            .route(
+                "/organizations/{id}/groups/{group_id}/members",
+                web::post().to(handlers::add_group_member),
+            )
+            .route(
+                "/organizations/{id}/groups/{group_id}/members/{user_id}",
+                web::delete().to(handlers::remove_group_member),
+            )
+            .route("/organizations/{id}/challenges", web::get().to(handlers::list_org_challenges))
+            .route("/organizations/{id}/challenges", web::post().to(handlers::create_org_challenge))
+            .route("/organizations/{id}/assignments", web::get().to(handlers::list_assignments))
+            .route("/organizations/{id}/assignments", web::post().to(handlers::create_assignment))
+            .route("/organizations/{id}/leaderboard", web::get().to(handlers::get_org_leaderboard))
+            .route(
+                "/assignments/{id}/submissions",
+                web::get().to(handlers::get_assignment_submissions),
+            )
+            .route("/assignments/{id}/grades", web::get().to(handlers::export_assignment_grades))
+            // Reports & Moderation
+            .route("/reports", web::post().to(handlers::create_report))
+            .route("/moderation/queue", web::get().to(handlers::get_moderation_queue))
+            .route("/moderation/reports", web::get().to(handlers::list_reports))
+            .route("/moderation/actions", web::get().to(handlers::list_moderation_actions))
+            .route("/moderation/actions", web::post().to(handlers::take_moderation_action))
+            .route("/moderation/users/{id}/warnings", web::get().to(handlers::list_user_warnings))
+            .route("/warnings", web::get().to(handlers::list_my_warnings))
+            .route("/warnings/{id}/acknowledge", web::post().to(handlers::acknowledge_warning))
             // Users
             .route("/users", web::post().to(handlers::create_user))
+            .route("/users/search", web::get().to(handlers::search_users))
+            .route("/users/suggestions", web::get().to(handlers::get_follow_suggestions))
             .route("/users/{id}", web::get().to(handlers::get_user))
             .route("/users/{id}", web::put().to(handlers::update_user))
             .route("/users/{id}/answers", web::get().to(handlers::get_user_answers))
+            .route("/users/{id}/activity", web::get().to(handlers::get_user_activity))
+            .route("/users/{id}/levels", web::get().to(handlers::get_user_levels))
+            .route("/users/{id}/stats", web::get().to(handlers::get_user_stats))
             .route("/users/{id}/follow", web::post().to(handlers::follow_user))
             .route("/users/{id}/follow", web::delete().to(handlers::unfollow_user))
             .route("/users/{id}/followers", web::get().to(handlers::get_followers))
             .route("/users/{id}/following", web::get().to(handlers::get_following))
+            // Follow requests (private accounts)
+            .route("/follow-requests", web::get().to(handlers::get_follow_requests))
+            .route(
+                "/follow-requests/{user_id}/approve",
+                web::post().to(handlers::approve_follow_request),
+            )
+            .route(
+                "/follow-requests/{user_id}/decline",
+                web::post().to(handlers::decline_follow_request),
+            )
             // Feed & Rankings
             .route("/feed", web::get().to(handlers::get_feed))
             .route("/trending", web::get().to(handlers::get_trending))
             .route("/rankings/daily", web::get().to(handlers::get_daily_ranking))
             .route("/rankings/weekly", web::get().to(handlers::get_weekly_ranking))
-            .route("/rankings/all-time", web::get().to(handlers::get_alltime_ranking)),
+            .route("/rankings/all-time", web::get().to(handlers::get_alltime_ranking))
+            .route("/rankings/users", web::get().to(handlers::get_user_ranking))
+            // Contests
+            .route("/contests", web::get().to(handlers::list_contests))
+            .route("/contests", web::post().to(handlers::create_contest))
+            .route("/contests/{id}", web::get().to(handlers::get_contest))
+            .route("/contests/{id}/challenges", web::post().to(handlers::create_contest_challenge))
+            .route("/contests/{id}/entries", web::get().to(handlers::list_contest_entries))
+            .route(
+                "/contests/{id}/entries/{answer_id}/vote",
+                web::post().to(handlers::vote_contest_entry),
+            )
+            .route(
+                "/contests/{id}/entries/{answer_id}/vote",
+                web::delete().to(handlers::unvote_contest_entry),
+            )
+            .route("/contests/{id}/standings", web::get().to(handlers::get_contest_standings))
+            // Answer battles
+            .route("/challenges/{id}/battle", web::get().to(handlers::get_battle))
+            .route("/challenges/{id}/battle", web::post().to(handlers::create_battle))
+            .route("/battles/{id}/vote", web::post().to(handlers::vote_battle)),
     )
     // Health check (outside /api/v1)
     .route("/health", web::get().to(handlers::health_check));
@@ -1,3 +1,5 @@
+pub mod text;
+
 use actix_web::{HttpResponse, http::StatusCode};
 use serde::Serialize;
 
@@ -95,6 +97,26 @@ pub fn conflict(message: &str) -> HttpResponse {
     error(StatusCode::CONFLICT, message)
 }
 
+/// 409 carrying the current server state, e.g. for version conflicts
+pub fn conflict_with<T: Serialize>(message: &str, data: T) -> HttpResponse {
+    HttpResponse::Conflict().json(ApiResponse {
+        success: false,
+        data: Some(data),
+        error: Some(message.to_string()),
+        pagination: None,
+    })
+}
+
+/// 422 carrying a machine-readable payload alongside the message
+pub fn unprocessable<T: Serialize>(message: &str, data: T) -> HttpResponse {
+    HttpResponse::UnprocessableEntity().json(ApiResponse {
+        success: false,
+        data: Some(data),
+        error: Some(message.to_string()),
+        pagination: None,
+    })
+}
+
 pub fn internal_error(message: &str) -> HttpResponse {
     error(StatusCode::INTERNAL_SERVER_ERROR, message)
 }
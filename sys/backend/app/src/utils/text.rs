@@ -0,0 +1,119 @@
+// Full-width forms of half-width katakana U+FF66 (ｦ) ..= U+FF9D (ﾝ), index for index
+const FULLWIDTH_KATAKANA: &str = "ヲァィゥェォャュョッーアイウエオカキクケコサシスセソタチツテトナニヌネノハヒフヘホマミムメモヤユヨラリルレロワン";
+
+// Hiragana that take a dakuten (voiced = +1) and handakuten (semi-voiced = +2)
+const VOICEABLE: &str = "かきくけこさしすせそたちつてとはひふへほ";
+const SEMI_VOICEABLE: &str = "はひふへほ";
+
+/// Folds the width and script variants used to dodge word filters:
+/// full-width ASCII to ASCII, half-width katakana to full-width, katakana to
+/// hiragana, ASCII to lowercase, and detached (han)dakuten onto their base.
+/// Punctuation and spacing are preserved so URLs stay detectable.
+pub fn fold(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+
+    for ch in text.chars() {
+        let ch = fold_width(ch);
+
+        match ch {
+            // Dakuten: half-width, combining, spacing
+            '\u{FF9E}' | '\u{3099}' | '\u{309B}' => {
+                if let Some(prev) = out.pop() {
+                    out.push(voice(prev, 1));
+                }
+            }
+            // Handakuten: half-width, combining, spacing
+            '\u{FF9F}' | '\u{309A}' | '\u{309C}' => {
+                if let Some(prev) = out.pop() {
+                    out.push(voice(prev, 2));
+                }
+            }
+            // Katakana to hiragana (ァ..ヶ)
+            '\u{30A1}'..='\u{30F6}' => out.push(shift(ch, -0x60)),
+            _ => out.extend(ch.to_lowercase()),
+        }
+    }
+
+    out
+}
+
+/// Removes whitespace, punctuation and symbols from folded text so that
+/// spaced-out or dotted variants ("し・ね", "死 ね") match dictionary entries.
+pub fn compact(folded: &str) -> String {
+    folded
+        .chars()
+        .filter(|c| c.is_alphanumeric() || *c == 'ー')
+        .collect()
+}
+
+fn fold_width(ch: char) -> char {
+    match ch {
+        // Full-width ASCII variants
+        '\u{FF01}'..='\u{FF5E}' => shift(ch, -0xFEE0),
+        '\u{3000}' => ' ',
+        // Half-width katakana
+        '\u{FF66}'..='\u{FF9D}' => FULLWIDTH_KATAKANA
+            .chars()
+            .nth(ch as usize - 0xFF66)
+            .unwrap_or(ch),
+        _ => ch,
+    }
+}
+
+fn voice(base: char, offset: i32) -> char {
+    let table = if offset == 2 { SEMI_VOICEABLE } else { VOICEABLE };
+    if table.contains(base) {
+        shift(base, offset)
+    } else if base == 'う' && offset == 1 {
+        'ゔ'
+    } else {
+        base
+    }
+}
+
+fn shift(ch: char, offset: i32) -> char {
+    char::from_u32((ch as i32 + offset) as u32).unwrap_or(ch)
+}
+
+/// Lookup key for Japanese-aware name matching. Uses the same folding as the
+/// content filters, so katakana/hiragana and full/half-width spellings compare equal.
+pub fn search_key(text: &str) -> String {
+    compact(&fold(text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn folds_width_and_script_variants() {
+        assert_eq!(fold("ＡＢＣ１２３"), "abc123");
+        assert_eq!(fold("ｼﾈ"), "しね");
+        assert_eq!(fold("シネ"), "しね");
+        assert_eq!(fold("Hello\u{3000}World"), "hello world");
+    }
+
+    #[test]
+    fn attaches_detached_dakuten() {
+        assert_eq!(fold("ｶﾞｯｺｳ"), "がっこう");
+        assert_eq!(fold("ﾊﾟﾝ"), "ぱん");
+        assert_eq!(fold("か\u{3099}"), "が");
+        assert_eq!(fold("ｳﾞ"), "ゔ");
+        // Marks on a base that cannot take them are dropped
+        assert_eq!(fold("あﾞ"), "あ");
+    }
+
+    #[test]
+    fn keeps_punctuation_until_compacted() {
+        let folded = fold("し・ね！ http://ｅｘ.com");
+        assert_eq!(folded, "し・ね! http://ex.com");
+        assert_eq!(compact(&folded), "しねhttpexcom");
+        assert_eq!(compact("らーめん"), "らーめん");
+    }
+
+    #[test]
+    fn search_key_matches_across_spellings() {
+        assert_eq!(search_key("デモ ユーザー"), search_key("でもゆーざー"));
+        assert_eq!(search_key("ﾃﾞﾓ"), "でも");
+    }
+}